@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use quickleaf::{Cache, Filter, ListProps, Order};
+use quickleaf::{Cache, EvictionPolicy, Filter, ListProps, Order};
 use std::hint::black_box;
 use std::sync::mpsc::channel;
 use std::time::Duration;
@@ -183,6 +183,118 @@ fn bench_list_operations(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_bulk_insert_then_list(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bulk_insert_then_list");
+    group.sample_size(10);
+
+    // Inserts don't sort, so time should grow roughly n log n (one sort in
+    // `list`), not quadratically
+    for size in &[1000, 10000, 100000] {
+        group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
+            b.iter(|| {
+                let mut cache = Cache::new(size);
+                for i in 0..size {
+                    cache.insert(format!("key{:06}", (i * 7919) % size), i);
+                }
+                let props = ListProps::default().unlimited();
+                black_box(cache.list(props).unwrap().len());
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_list_small_page(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_small_page");
+
+    let mut cache = Cache::new(50000);
+    for i in 0..50000 {
+        cache.insert(format!("key{:05}", (i * 7919) % 50000), i);
+    }
+
+    group.bench_function("list_limit_10_50k", |b| {
+        b.iter(|| {
+            let props = ListProps::default().order(Order::Asc).limit(10);
+            black_box(cache.list(props).unwrap());
+        });
+    });
+
+    group.bench_function("full_sort_baseline_50k", |b| {
+        b.iter(|| {
+            let mut keys: Vec<String> = cache.get_list().into_iter().cloned().collect();
+            keys.sort();
+            keys.truncate(10);
+            black_box(keys);
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_list_repeated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("list_repeated");
+
+    let mut cache = Cache::new(5000);
+    for i in 0..5000 {
+        cache.insert(format!("key{:04}", (i * 7919) % 5000), i);
+    }
+
+    group.bench_function("unchanged_5k", |b| {
+        b.iter(|| {
+            let props = ListProps::default().order(Order::Asc).limit(1000);
+            black_box(cache.list(props).unwrap().len());
+        });
+    });
+
+    group.bench_function("mutated_5k", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            let key = format!("key{:04}", i % 5000);
+            cache.remove(&key).unwrap();
+            cache.insert(key, i);
+            i += 1;
+
+            let props = ListProps::default().order(Order::Asc).limit(1000);
+            black_box(cache.list(props).unwrap().len());
+        });
+    });
+
+    group.finish();
+}
+
+fn bench_hashers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hashers");
+    let size = 10000;
+    let keys: Vec<String> = (0..size).map(|i| format!("key{}", i)).collect();
+
+    group.bench_function("default_insert_get", |b| {
+        b.iter(|| {
+            let mut cache = Cache::new(size);
+            for key in &keys {
+                cache.insert(key.as_str(), 1);
+            }
+            for key in &keys {
+                black_box(cache.get(key));
+            }
+        });
+    });
+
+    group.bench_function("foldhash_insert_get", |b| {
+        b.iter(|| {
+            let mut cache = Cache::with_hasher(size, hashbrown::DefaultHashBuilder::default());
+            for key in &keys {
+                cache.insert(key.as_str(), 1);
+            }
+            for key in &keys {
+                black_box(cache.get(key));
+            }
+        });
+    });
+
+    group.finish();
+}
+
 fn bench_lru_eviction(c: &mut Criterion) {
     c.bench_function("lru_eviction", |b| {
         let mut cache = Cache::new(100); // Small capacity to trigger evictions
@@ -195,6 +307,44 @@ fn bench_lru_eviction(c: &mut Criterion) {
     });
 }
 
+fn bench_eviction_policies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eviction_policies");
+
+    for (name, policy) in [
+        ("lru", EvictionPolicy::Lru),
+        ("clock", EvictionPolicy::Clock),
+    ] {
+        group.bench_function(format!("get_{}", name), |b| {
+            let mut cache = Cache::new(10000);
+            cache.set_eviction_policy(policy);
+
+            for i in 0..10000 {
+                cache.insert(format!("key{}", i), format!("value{}", i));
+            }
+
+            let mut i = 0;
+            b.iter(|| {
+                black_box(cache.get(&format!("key{}", i)));
+                i = (i + 1) % 10000;
+            });
+        });
+
+        group.bench_function(format!("insert_evicting_{}", name), |b| {
+            let mut cache = Cache::new(1000);
+            cache.set_eviction_policy(policy);
+            let mut i = 0;
+
+            b.iter(|| {
+                cache.insert(format!("key{}", i), format!("value{}", i));
+                black_box(cache.get(&format!("key{}", i / 2)));
+                i += 1;
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_ttl_operations(c: &mut Criterion) {
     let mut group = c.benchmark_group("ttl_operations");
 
@@ -470,7 +620,12 @@ criterion_group!(
     bench_contains_key,
     bench_remove,
     bench_list_operations,
+    bench_bulk_insert_then_list,
+    bench_list_small_page,
+    bench_list_repeated,
+    bench_hashers,
     bench_lru_eviction,
+    bench_eviction_policies,
     bench_ttl_operations,
     bench_event_system,
     bench_mixed_operations,
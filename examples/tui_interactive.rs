@@ -207,11 +207,8 @@ impl App {
             Some(MenuItem::List) => {
                 let items = self
                     .cache
-                    .list(ListProps::default().order(Order::Asc))
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|(k, v)| (k, v.clone()))
-                    .collect::<Vec<_>>();
+                    .list_owned(ListProps::default().order(Order::Asc))
+                    .unwrap_or_default();
 
                 if items.is_empty() {
                     self.add_message("📋 Cache is empty".to_string());
@@ -247,12 +244,8 @@ impl App {
                         props = props.start_after_key(&key);
                     }
                     
-                    match self.cache.list(props) {
+                    match self.cache.list_owned(props) {
                         Ok(items) => {
-                            let items: Vec<_> = items.into_iter()
-                                .map(|(k, v)| (k, v.clone()))
-                                .collect();
-                            
                             if items.is_empty() {
                                 self.add_message("📄 No items found with given pagination".to_string());
                             } else {
@@ -278,15 +271,12 @@ impl App {
                 let prefix = self.input_buffer.clone();
                 let items = self
                     .cache
-                    .list(
+                    .list_owned(
                         ListProps::default()
                             .filter(Filter::StartWith(prefix.clone()))
                             .order(Order::Asc),
                     )
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|(k, v)| (k, v.clone()))
-                    .collect::<Vec<_>>();
+                    .unwrap_or_default();
 
                 if items.is_empty() {
                     self.add_message(format!("🔍 No items found with prefix: {}", prefix));
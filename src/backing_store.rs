@@ -0,0 +1,194 @@
+//! Pluggable storage behind a cache.
+//!
+//! This module provides the [`BackingStore`] trait, which lets a cache mirror its
+//! entries into external storage (a database, Redis, files, ...), and the
+//! background writer shared by every store, including the built-in SQLite one.
+
+use crate::cache::{CacheItem, Key};
+use std::fmt::Debug;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Error returned by [`BackingStore`] operations.
+pub type StoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Number of processed operations after which the writer purges expired entries,
+/// so store cleanup also happens under constant write load (when it never idles)
+pub(crate) const CLEANUP_EVENT_INTERVAL: usize = 256;
+
+/// How long the writer waits for an operation before purging expired entries
+const WRITER_IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Storage that a cache keeps in sync with its entries.
+///
+/// The cache calls [`load`](Self::load) once when it is created, then
+/// [`put`](Self::put) for every inserted or updated entry,
+/// [`delete`](Self::delete) for every removed, evicted or expired entry, and
+/// [`clear`](Self::clear) when it is cleared. See
+/// [`Cache::with_backing_store`](crate::Cache::with_backing_store).
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{BackingStore, Cache, CacheItem, StoreError, WriteMode};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+/// use std::collections::HashMap;
+/// use std::sync::{Arc, Mutex};
+///
+/// #[derive(Clone, Default)]
+/// struct MemoryStore(Arc<Mutex<HashMap<String, CacheItem>>>);
+///
+/// impl BackingStore for MemoryStore {
+///     fn load(&self) -> Result<Vec<(String, CacheItem)>, StoreError> {
+///         Ok(self.0.lock().unwrap().clone().into_iter().collect())
+///     }
+///
+///     fn put(&self, key: &str, item: &CacheItem) -> Result<(), StoreError> {
+///         self.0.lock().unwrap().insert(key.to_string(), item.clone());
+///         Ok(())
+///     }
+///
+///     fn delete(&self, key: &str) -> Result<(), StoreError> {
+///         self.0.lock().unwrap().remove(key);
+///         Ok(())
+///     }
+///
+///     fn clear(&self) -> Result<(), StoreError> {
+///         self.0.lock().unwrap().clear();
+///         Ok(())
+///     }
+/// }
+///
+/// let store = MemoryStore::default();
+/// let mut cache = Cache::with_backing_store(10, store.clone(), WriteMode::WriteThrough).unwrap();
+/// cache.insert("key", "value");
+///
+/// let reloaded = Cache::with_backing_store(10, store, WriteMode::WriteThrough).unwrap();
+/// assert_eq!(reloaded.len(), 1);
+/// ```
+pub trait BackingStore: Send + Sync + 'static {
+    /// Returns the stored entries. Expired entries are skipped by the cache.
+    fn load(&self) -> Result<Vec<(Key, CacheItem)>, StoreError>;
+
+    /// Stores `item` under `key`, replacing any previous entry.
+    fn put(&self, key: &str, item: &CacheItem) -> Result<(), StoreError>;
+
+    /// Deletes the entry stored under `key`, if any.
+    fn delete(&self, key: &str) -> Result<(), StoreError>;
+
+    /// Deletes every stored entry.
+    fn clear(&self) -> Result<(), StoreError>;
+
+    /// Deletes expired entries.
+    ///
+    /// Called periodically by the write-back writer. The default does nothing.
+    fn purge_expired(&self) -> Result<(), StoreError> {
+        Ok(())
+    }
+}
+
+/// When a cache writes changes to its [`BackingStore`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Each change is written synchronously, before the cache method returns.
+    #[default]
+    WriteThrough,
+    /// Changes are queued and written by a background thread.
+    WriteBack,
+}
+
+/// A change to apply to a backing store
+#[derive(Clone, Debug)]
+pub(crate) enum StoreOp {
+    Put(Key, Box<CacheItem>),
+    Delete(Key),
+    Clear,
+    PurgeExpired,
+}
+
+impl StoreOp {
+    fn apply(&self, store: &dyn BackingStore) -> Result<(), StoreError> {
+        match self {
+            StoreOp::Put(key, item) => store.put(key, item),
+            StoreOp::Delete(key) => store.delete(key),
+            StoreOp::Clear => store.clear(),
+            StoreOp::PurgeExpired => store.purge_expired(),
+        }
+    }
+}
+
+/// Applies queued operations to `store` until every sender is dropped.
+///
+/// Expired entries are purged whenever the queue idles and every
+/// [`CLEANUP_EVENT_INTERVAL`] operations. Failures are passed to `on_error`.
+pub(crate) fn run_writer<F>(store: &dyn BackingStore, receiver: Receiver<StoreOp>, mut on_error: F)
+where
+    F: FnMut(&StoreOp, StoreError),
+{
+    let mut ops_since_cleanup = 0;
+
+    loop {
+        match receiver.recv_timeout(WRITER_IDLE_TIMEOUT) {
+            Ok(op) => {
+                if let Err(e) = op.apply(store) {
+                    on_error(&op, e);
+                }
+
+                ops_since_cleanup += 1;
+                if ops_since_cleanup >= CLEANUP_EVENT_INTERVAL {
+                    ops_since_cleanup = 0;
+                    purge(store, &mut on_error);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                ops_since_cleanup = 0;
+                purge(store, &mut on_error);
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Purges expired entries, passing a failure to `on_error`
+fn purge<F>(store: &dyn BackingStore, on_error: &mut F)
+where
+    F: FnMut(&StoreOp, StoreError),
+{
+    if let Err(e) = StoreOp::PurgeExpired.apply(store) {
+        on_error(&StoreOp::PurgeExpired, e);
+    }
+}
+
+/// The store a cache writes to, and how
+#[derive(Clone)]
+pub(crate) enum StoreHandle {
+    WriteThrough(Arc<dyn BackingStore>),
+    WriteBack(Sender<StoreOp>),
+}
+
+impl StoreHandle {
+    pub fn apply(&self, op: StoreOp) {
+        match self {
+            StoreHandle::WriteThrough(store) => {
+                if let Err(e) = op.apply(store.as_ref()) {
+                    log_warn!("Backing store write failed for {:?}: {}", op, e);
+                }
+            }
+            StoreHandle::WriteBack(sender) => {
+                if let Err(e) = sender.send(op) {
+                    log_debug!("Failed to queue backing store write: {}", e);
+                }
+            }
+        }
+    }
+}
+
+impl Debug for StoreHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StoreHandle::WriteThrough(_) => f.write_str("StoreHandle::WriteThrough"),
+            StoreHandle::WriteBack(_) => f.write_str("StoreHandle::WriteBack"),
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Builder for configuring a cache in one expression.
+//!
+//! This module provides [`CacheBuilder`], an alternative to the `with_*`
+//! constructors and `set_*` methods of [`Cache`].
+
+use crate::cache::{Cache, InsertValidator, KeyNormalizer, ValueTransform};
+use crate::clock::Clock;
+use crate::event::{Event, EventOverflow};
+use crate::eviction::{EvictionPolicy, ResizePolicy};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+#[cfg(feature = "persist")]
+use std::path::Path;
+use std::sync::mpsc::{Sender, SyncSender};
+use std::time::Duration;
+
+/// Builder for [`Cache`].
+///
+/// Options left unset keep the defaults of [`Cache::new`].
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, CacheBuilder, EvictionPolicy};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+/// use std::time::Duration;
+///
+/// let mut cache = CacheBuilder::new(100)
+///     .default_ttl(Duration::from_secs(60))
+///     .eviction_policy(EvictionPolicy::Clock)
+///     .normalize_keys(|key| key.to_lowercase())
+///     .build();
+///
+/// cache.insert("User:1", "alice");
+/// assert_eq!(cache.get("user:1"), Some(&"alice".to_value()));
+/// assert_eq!(cache.get_default_ttl(), Some(Duration::from_secs(60)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CacheBuilder {
+    capacity: usize,
+    preallocate: Option<usize>,
+    default_ttl: Option<Duration>,
+    ttl_jitter: Duration,
+    stale_grace: Duration,
+    sender: Option<Sender<Event>>,
+    bounded_sender: Option<(SyncSender<Event>, EventOverflow)>,
+    eviction_policy: EvictionPolicy,
+    eviction_batch_size: usize,
+    resize_policy: ResizePolicy,
+    key_normalizer: Option<KeyNormalizer>,
+    value_transform: Option<ValueTransform>,
+    insert_validator: Option<InsertValidator>,
+    track_access_counts: bool,
+    clock: Option<Clock>,
+    #[cfg(feature = "compress")]
+    compress_over: Option<usize>,
+}
+
+impl CacheBuilder {
+    /// Starts building a cache with the specified capacity.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            preallocate: None,
+            default_ttl: None,
+            ttl_jitter: Duration::ZERO,
+            stale_grace: Duration::ZERO,
+            sender: None,
+            bounded_sender: None,
+            eviction_policy: EvictionPolicy::default(),
+            eviction_batch_size: 1,
+            resize_policy: ResizePolicy::default(),
+            key_normalizer: None,
+            value_transform: None,
+            insert_validator: None,
+            track_access_counts: false,
+            clock: None,
+            #[cfg(feature = "compress")]
+            compress_over: None,
+        }
+    }
+
+    /// Allocates room for `slots` entries up front instead of the full capacity.
+    ///
+    /// The capacity passed to [`new`](Self::new) stays the maximum number of
+    /// entries; the map grows on demand past `slots`. Useful for large limits that
+    /// are rarely reached.
+    pub fn preallocate(mut self, slots: usize) -> Self {
+        self.preallocate = Some(slots);
+        self
+    }
+
+    /// Sets the TTL applied to items inserted without an explicit one.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = Some(ttl);
+        self
+    }
+
+    /// Randomizes each item's TTL within `ttl ± jitter`.
+    ///
+    /// See [`Cache::set_ttl_jitter`] for details.
+    pub fn ttl_jitter(mut self, jitter: Duration) -> Self {
+        self.ttl_jitter = jitter;
+        self
+    }
+
+    /// Keeps expired entries for `grace` so they can be served stale.
+    ///
+    /// See [`Cache::set_stale_grace`] for details.
+    pub fn stale_grace(mut self, grace: Duration) -> Self {
+        self.stale_grace = grace;
+        self
+    }
+
+    /// Sends cache events to `sender`.
+    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+        self.sender = Some(sender);
+        self.bounded_sender = None;
+        self
+    }
+
+    /// Sends cache events to a bounded channel, handling a full channel per `overflow`.
+    ///
+    /// See [`Cache::set_bounded_event`] for details.
+    pub fn bounded_sender(mut self, sender: SyncSender<Event>, overflow: EventOverflow) -> Self {
+        self.bounded_sender = Some((sender, overflow));
+        self.sender = None;
+        self
+    }
+
+    /// Sets the policy used to pick a victim when the cache is full.
+    pub fn eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
+    }
+
+    /// Evicts up to `batch_size` entries at once when the cache is full.
+    ///
+    /// See [`Cache::set_eviction_batch_size`] for details.
+    pub fn eviction_batch_size(mut self, batch_size: usize) -> Self {
+        self.eviction_batch_size = batch_size;
+        self
+    }
+
+    /// Grows the capacity instead of evicting, per `policy`.
+    ///
+    /// See [`Cache::set_resize_policy`] for details.
+    pub fn resize_policy(mut self, policy: ResizePolicy) -> Self {
+        self.resize_policy = policy;
+        self
+    }
+
+    /// Normalizes every key before it is stored or looked up.
+    ///
+    /// See [`Cache::set_key_normalizer`] for details.
+    pub fn normalize_keys(mut self, normalizer: KeyNormalizer) -> Self {
+        self.key_normalizer = Some(normalizer);
+        self
+    }
+
+    /// Transforms every value before it is stored.
+    ///
+    /// See [`Cache::set_value_transform`] for details.
+    pub fn transform_values(mut self, transform: ValueTransform) -> Self {
+        self.value_transform = Some(transform);
+        self
+    }
+
+    /// Checks every key and value before it is inserted.
+    ///
+    /// See [`Cache::set_insert_validator`] for details.
+    pub fn validate_inserts(mut self, validator: InsertValidator) -> Self {
+        self.insert_validator = Some(validator);
+        self
+    }
+
+    /// Counts `get` hits per entry for [`Cache::hot_keys`].
+    pub fn track_access_counts(mut self, enabled: bool) -> Self {
+        self.track_access_counts = enabled;
+        self
+    }
+
+    /// Uses `clock` instead of the system clock for TTL bookkeeping.
+    ///
+    /// See [`Cache::set_clock`] for details.
+    pub fn clock(mut self, clock: Clock) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Compresses values whose serialized form is longer than `bytes` when they
+    /// are written to SQLite.
+    ///
+    /// Applies to [`build_with_persist`](Self::build_with_persist) and to
+    /// persistence enabled later with [`Cache::enable_persistence`]. Values stay
+    /// uncompressed in memory; see
+    /// [`SqliteStore::compress_values_over`](crate::SqliteStore::compress_values_over).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use quickleaf::CacheBuilder;
+    ///
+    /// let mut cache = CacheBuilder::new(1000)
+    ///     .compress_values_over(4096)
+    ///     .build_with_persist("data/cache.db")
+    ///     .unwrap();
+    /// cache.insert("report", "x".repeat(100_000));
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn compress_values_over(mut self, bytes: usize) -> Self {
+        self.compress_over = Some(bytes);
+        self
+    }
+
+    /// Builds the cache.
+    pub fn build(self) -> Cache {
+        self.build_with_hasher(RandomState::new())
+    }
+
+    /// Builds the cache, hashing keys with `hash_builder`.
+    ///
+    /// See [`Cache::with_hasher`] for details.
+    pub fn build_with_hasher<S: BuildHasher>(self, hash_builder: S) -> Cache<S> {
+        let slots = self.preallocate.unwrap_or(self.capacity);
+        let mut cache = Cache::with_hasher(slots, hash_builder);
+        cache.set_capacity(self.capacity);
+        cache.set_default_ttl(self.default_ttl);
+        cache.set_ttl_jitter(self.ttl_jitter);
+        cache.set_stale_grace(self.stale_grace);
+        if let Some(sender) = self.sender {
+            cache.set_event(sender);
+        }
+        if let Some((sender, overflow)) = self.bounded_sender {
+            cache.set_bounded_event(sender, overflow);
+        }
+        cache.set_eviction_policy(self.eviction_policy);
+        cache.set_eviction_batch_size(self.eviction_batch_size);
+        cache.set_resize_policy(self.resize_policy);
+        cache.set_key_normalizer(self.key_normalizer);
+        cache.set_value_transform(self.value_transform);
+        cache.set_insert_validator(self.insert_validator);
+        cache.set_track_access_counts(self.track_access_counts);
+        if let Some(clock) = self.clock {
+            cache.set_clock(clock);
+        }
+        #[cfg(feature = "compress")]
+        cache.set_persist_compression(self.compress_over);
+        cache
+    }
+
+    /// Builds the cache with SQLite persistence at `path`.
+    ///
+    /// Unlike [`Cache::with_persist`], the stored entries are loaded after the
+    /// cache is configured, so their keys go through the key normalizer. When
+    /// several stored keys normalize to the same key, the one created last is
+    /// kept (ties go to the greatest stored key) and the database is rewritten
+    /// to match. Expired entries are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::CacheBuilder;
+    ///
+    /// let mut cache = CacheBuilder::new(1000)
+    ///     .normalize_keys(|key| key.to_lowercase())
+    ///     .build_with_persist("data/cache.db")
+    ///     .unwrap();
+    /// cache.insert("Key", "value");
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn build_with_persist<P: AsRef<Path>>(
+        self,
+        path: P,
+    ) -> Result<Cache, Box<dyn std::error::Error>> {
+        let mut cache = self.build();
+        cache.attach_persist(path.as_ref())?;
+        Ok(cache)
+    }
+}
+
+impl Cache {
+    /// Starts building a cache with the specified capacity.
+    ///
+    /// Shorthand for [`CacheBuilder::new`].
+    pub fn builder(capacity: usize) -> CacheBuilder {
+        CacheBuilder::new(capacity)
+    }
+}
@@ -1,14 +1,31 @@
+use crate::backing_store::{BackingStore, StoreError, StoreHandle, StoreOp, WriteMode};
+use crate::clock::{system_clock, Clock};
+use crate::compound_key::{self, CompoundKey, KEY_ESCAPE};
 use crate::error::Error;
-use crate::event::Event;
-use crate::filters::apply_filter_fast;
-use crate::list_props::{ListProps, Order, StartAfter};
+use crate::event::{Event, EventOverflow, KeyWatcher};
+use crate::eviction::{EvictDecision, EvictionPolicy, ResizePolicy, Watermark};
+use crate::filter::Filter;
+use crate::filters::{apply_filter_fast, fast_prefix_match};
+use crate::list_props::{within_byte_budget, Collator, ListProps, Order, StartAfter};
+use crate::snapshot::CacheSnapshot;
 use indexmap::IndexMap;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::hash_map::RandomState;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::convert::Infallible;
 use std::fmt::Debug;
-use std::sync::mpsc::Sender;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::ControlFlow;
+use std::sync::mpsc::{Receiver, Sender, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, PoisonError, Weak};
 use std::time::{Duration, SystemTime};
-use valu3::traits::ToValueBehavior;
+use valu3::prelude::{NumberBehavior, StringBehavior};
+use valu3::traits::{FromValueBehavior, ToValueBehavior};
 use valu3::value::Value;
 
+#[cfg(feature = "persist")]
+use crate::sqlite_store::{PersistError, PersistErrorChannel, PersistOperation, PersistReport};
 #[cfg(feature = "persist")]
 use std::path::Path;
 #[cfg(feature = "persist")]
@@ -17,13 +34,190 @@ use std::sync::mpsc::channel;
 /// Type alias for cache keys.
 pub type Key = String;
 
+/// Upper bound of the per-item access counter used by [`EvictionPolicy::Clock`].
+const CLOCK_MAX_FREQUENCY: u8 = 3;
+
+/// `list` switches from a full sort to a bounded selection when the cache holds
+/// more than this many times `limit` entries.
+const PARTIAL_SELECT_FACTOR: usize = 8;
+
+/// Number of keys listed by [`Cache::debug_dump`].
+const DEBUG_DUMP_SAMPLE: usize = 10;
+
+/// Function applied to keys before they are stored or looked up.
+pub type KeyNormalizer = fn(&str) -> String;
+
+/// Function applied to values before they are stored.
+pub type ValueTransform = fn(Value) -> Value;
+
+/// Function that accepts or rejects a key and value before they are inserted.
+pub type InsertValidator = fn(&str, &Value) -> Result<(), String>;
+
+/// Function invoked with each entry removed by capacity eviction.
+type EvictFn = dyn FnMut(&str, &Value) + Send;
+
+/// Registered [`EvictFn`], shared between clones of a cache like the event sender.
+#[derive(Clone)]
+struct EvictionCallback(Arc<Mutex<EvictFn>>);
+
+impl Debug for EvictionCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("EvictionCallback")
+    }
+}
+
+/// Function deciding whether an eviction victim may be removed.
+type EvictGuardFn = dyn FnMut(&str, &Value) -> EvictDecision + Send;
+
+/// Registered [`EvictGuardFn`], shared between clones like [`EvictionCallback`].
+#[derive(Clone)]
+struct EvictionGuard(Arc<Mutex<EvictGuardFn>>);
+
+impl Debug for EvictionGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("EvictionGuard")
+    }
+}
+
+/// Channel the cache sends its events to (see [`Cache::set_event`] and
+/// [`Cache::set_bounded_event`]).
+#[derive(Clone, Debug)]
+enum EventSender {
+    Unbounded(Sender<Event>),
+    Bounded(SyncSender<Event>, EventOverflow),
+}
+
+/// Outcome of [`EventSender::send`]
+enum SendStatus {
+    Sent,
+    /// The bounded channel was full and the event was dropped
+    Full,
+    /// The receiver is gone
+    Disconnected,
+}
+
+impl EventSender {
+    /// Sends `event`, reporting a full channel only under [`EventOverflow::Drop`].
+    fn send(&self, event: Event) -> SendStatus {
+        let sent = match self {
+            Self::Unbounded(sender) => sender.send(event).is_ok(),
+            Self::Bounded(sender, EventOverflow::Block) => sender.send(event).is_ok(),
+            Self::Bounded(sender, EventOverflow::Drop) => match sender.try_send(event) {
+                Err(TrySendError::Full(_)) => return SendStatus::Full,
+                result => result.is_ok(),
+            },
+        };
+        if sent {
+            SendStatus::Sent
+        } else {
+            SendStatus::Disconnected
+        }
+    }
+}
+
+/// A page of entries with the total number of matches (see [`Cache::list_with_count`])
+type CountedPage<'a> = (Vec<(Key, &'a Value)>, usize);
+
+/// Callback invoked when utilization crosses a watermark (see [`Cache::on_utilization`])
+type WatermarkFn = dyn FnMut(Watermark, f64) + Send;
+
+/// A key watcher's sender and a handle that dies with its [`KeyWatcher`]
+type WatcherSlot = (Sender<Event>, Weak<()>);
+
+/// Registered [`WatermarkFn`] with its thresholds, shared between clones like [`EvictionCallback`].
+#[derive(Clone)]
+struct Watermarks {
+    low: f64,
+    high: f64,
+    callback: Arc<Mutex<WatermarkFn>>,
+    /// Whether `High` was the last crossing, so `Low` is reported next
+    above: bool,
+}
+
+impl Debug for Watermarks {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Watermarks")
+            .field("low", &self.low)
+            .field("high", &self.high)
+            .field("above", &self.above)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Separator used to join [`CompoundKey`] segments unless configured otherwise.
+const DEFAULT_KEY_SEPARATOR: char = ':';
+
 /// Helper function to get current time in milliseconds since UNIX_EPOCH
 #[inline(always)]
 fn current_time_millis() -> u64 {
-    SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or(Duration::ZERO)
-        .as_millis() as u64
+    system_clock()
+}
+
+/// Returns a random number, seeded per call from the process's hashing keys
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(current_time_millis());
+    hasher.finish()
+}
+
+/// The number in `value` re-encoded at every width it fits, for typed reads.
+///
+/// valu3 keeps the width a number was built with (`insert(key, 5)` stores an
+/// `i32`) and `from_value` only accepts that exact width, so `try_get` tries
+/// these before reporting a mismatch. Empty for non-numbers.
+fn numeric_widths(value: &Value) -> Vec<Value> {
+    let mut widths = Vec::new();
+
+    if let Some(n) = value.to_i64() {
+        widths.push(n.to_value());
+        widths.push(i128::from(n).to_value());
+        if let Ok(n) = i32::try_from(n) {
+            widths.push(n.to_value());
+        }
+        if let Ok(n) = i16::try_from(n) {
+            widths.push(n.to_value());
+        }
+        if let Ok(n) = i8::try_from(n) {
+            widths.push(n.to_value());
+        }
+    }
+    if let Some(n) = value.to_u64() {
+        widths.push(n.to_value());
+        widths.push(u128::from(n).to_value());
+        if let Ok(n) = u32::try_from(n) {
+            widths.push(n.to_value());
+        }
+        if let Ok(n) = u16::try_from(n) {
+            widths.push(n.to_value());
+        }
+        if let Ok(n) = u8::try_from(n) {
+            widths.push(n.to_value());
+        }
+    }
+    if let Some(n) = value.to_f64() {
+        widths.push(n.to_value());
+        widths.push((n as f32).to_value());
+    }
+
+    widths
+}
+
+/// Returns the `limit` smallest items in ascending order using a bounded max-heap,
+/// in O(n log limit) instead of sorting everything.
+fn select_smallest<T: Ord>(items: impl Iterator<Item = T>, limit: usize) -> Vec<T> {
+    let mut heap = BinaryHeap::with_capacity(limit + 1);
+
+    for item in items {
+        if heap.len() < limit {
+            heap.push(item);
+        } else if let Some(mut largest) = heap.peek_mut() {
+            if item < *largest {
+                *largest = item;
+            }
+        }
+    }
+
+    heap.into_sorted_vec()
 }
 
 /// Represents an item stored in the cache with optional TTL (Time To Live).
@@ -54,8 +248,16 @@ pub struct CacheItem {
     pub value: Value,
     /// When this item was created (millis since epoch)
     pub created_at: u64,
+    /// When this item's value last changed (millis since epoch)
+    pub modified_at: u64,
     /// Optional TTL in milliseconds
     pub ttl_millis: Option<u64>,
+    /// Whether the item is exempt from capacity eviction (see [`Cache::pin`])
+    pub pinned: bool,
+    /// Saturating access counter used by the CLOCK eviction policy
+    pub(crate) frequency: u8,
+    /// Number of `get` hits, counted when access tracking is enabled
+    pub(crate) access_count: u64,
 }
 
 impl CacheItem {
@@ -73,10 +275,15 @@ impl CacheItem {
     /// ```
     #[inline]
     pub fn new(value: Value) -> Self {
+        let now = current_time_millis();
         Self {
             value,
-            created_at: current_time_millis(),
+            created_at: now,
+            modified_at: now,
             ttl_millis: None,
+            pinned: false,
+            frequency: 0,
+            access_count: 0,
         }
     }
 
@@ -95,10 +302,15 @@ impl CacheItem {
     /// ```
     #[inline]
     pub fn with_ttl(value: Value, ttl: Duration) -> Self {
+        let now = current_time_millis();
         Self {
             value,
-            created_at: current_time_millis(),
+            created_at: now,
+            modified_at: now,
             ttl_millis: Some(ttl.as_millis() as u64),
+            pinned: false,
+            frequency: 0,
+            access_count: 0,
         }
     }
 
@@ -125,8 +337,26 @@ impl CacheItem {
     /// ```
     #[inline(always)]
     pub fn is_expired(&self) -> bool {
+        self.is_expired_at(current_time_millis())
+    }
+
+    /// Checks if this cache item has expired at `now_millis` (millis since epoch).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::CacheItem;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    ///
+    /// let item = CacheItem::with_ttl("temp".to_value(), Duration::from_millis(100));
+    /// assert!(!item.is_expired_at(item.created_at + 100));
+    /// assert!(item.is_expired_at(item.created_at + 101));
+    /// ```
+    #[inline(always)]
+    pub fn is_expired_at(&self, now_millis: u64) -> bool {
         if let Some(ttl) = self.ttl_millis {
-            (current_time_millis() - self.created_at) > ttl
+            now_millis.saturating_sub(self.created_at) > ttl
         } else {
             false
         }
@@ -143,6 +373,23 @@ impl CacheItem {
     pub fn created_at_time(&self) -> SystemTime {
         std::time::UNIX_EPOCH + Duration::from_millis(self.created_at)
     }
+
+    /// Returns [`modified_at`](Self::modified_at) as a `SystemTime`.
+    #[inline]
+    pub fn modified_at_time(&self) -> SystemTime {
+        std::time::UNIX_EPOCH + Duration::from_millis(self.modified_at)
+    }
+
+    /// Records a read for the CLOCK eviction policy and, optionally, hot-key tracking
+    #[inline(always)]
+    fn touch(&mut self, track_access_counts: bool) {
+        if self.frequency < CLOCK_MAX_FREQUENCY {
+            self.frequency += 1;
+        }
+        if track_access_counts {
+            self.access_count += 1;
+        }
+    }
 }
 
 impl PartialEq for CacheItem {
@@ -151,6 +398,79 @@ impl PartialEq for CacheItem {
     }
 }
 
+/// Aggregate TTL statistics over the live entries of a cache.
+///
+/// Returned by [`Cache::ttl_distribution`]. The remaining-time fields only
+/// consider entries with a TTL and are `None` when there are none.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TtlStats {
+    /// Number of live entries without a TTL.
+    pub permanent: usize,
+    /// Number of live entries with a TTL.
+    pub ttl_count: usize,
+    /// Shortest time left before an entry expires.
+    pub min_remaining: Option<Duration>,
+    /// Longest time left before an entry expires.
+    pub max_remaining: Option<Duration>,
+    /// Average time left before an entry expires.
+    pub avg_remaining: Option<Duration>,
+}
+
+/// Differences between the live entries of two caches.
+///
+/// Returned by [`Cache::diff`]. Keys in each list are sorted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheDiff {
+    /// Keys only present in the cache `diff` was called on.
+    pub only_self: Vec<Key>,
+    /// Keys only present in the other cache.
+    pub only_other: Vec<Key>,
+    /// Keys present in both whose values differ.
+    pub value_mismatch: Vec<Key>,
+}
+
+impl CacheDiff {
+    /// Returns `true` if both caches hold the same live entries.
+    pub fn is_empty(&self) -> bool {
+        self.only_self.is_empty() && self.only_other.is_empty() && self.value_mismatch.is_empty()
+    }
+}
+
+/// Hits and misses from replaying an access trace.
+///
+/// Returned by [`Cache::simulate`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SimReport {
+    /// Accesses whose key was cached.
+    pub hits: usize,
+    /// Accesses whose key was not cached, after which it was inserted.
+    pub misses: usize,
+    /// `hits` divided by the number of accesses, or `0.0` for an empty trace.
+    pub hit_ratio: f64,
+}
+
+/// Result of [`Cache::get_stale`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GetResult<'a> {
+    /// The entry is live.
+    Fresh(&'a Value),
+    /// The entry expired but is within the stale grace period; serve it and
+    /// refresh it.
+    Stale(&'a Value),
+    /// The key is missing, or expired past the grace period.
+    Miss,
+}
+
+impl<'a> GetResult<'a> {
+    /// Returns the value of a fresh or stale entry.
+    pub fn value(&self) -> Option<&'a Value> {
+        match self {
+            GetResult::Fresh(value) | GetResult::Stale(value) => Some(value),
+            GetResult::Miss => None,
+        }
+    }
+}
+
 /// Core cache implementation with LRU eviction, TTL support, and event notifications.
 ///
 /// This cache provides:
@@ -160,6 +480,45 @@ impl PartialEq for CacheItem {
 /// - Event notifications for cache operations
 /// - Filtering and ordering capabilities for listing entries
 ///
+/// # Cloning
+///
+/// `clone` is a deep copy: the clone gets its own entries, and changes to one
+/// cache are not visible in the other. Only the event sender and
+/// [`observe`](Self::observe) and [`watch`](Self::watch) receivers are shared,
+/// so both caches emit events into the same channels, in the order the
+/// operations run.
+/// The same goes for the [`on_evict`](Self::on_evict) and
+/// [`on_utilization`](Self::on_utilization) callbacks.
+/// Each copy evicts and expires based on its own entries, so the combined event
+/// stream does not describe a single cache. To share one cache (and get one
+/// consistent event stream) between owners or threads, use
+/// [`shared_handle`](Self::shared_handle).
+///
+/// # Thread Safety
+///
+/// `Cache` is `Send` and `Sync` (given a `Send + Sync` hasher), so it can be
+/// moved to another thread or put behind a lock. `Sync` alone is of little use,
+/// though: reads such as [`get`](Self::get) take `&mut self` because they remove
+/// expired entries and record accesses. To use one cache from several
+/// threads, wrap it in a [`SyncCache`](crate::SyncCache) with
+/// [`shared_handle`](Self::shared_handle).
+///
+/// # Ordering
+///
+/// Entries are kept in insertion order. [`get_list`](Self::get_list),
+/// [`get_map`](Self::get_map) and [`partition`](Self::partition) preserve it,
+/// and `EvictionPolicy::Lru` evicts the oldest entry first.
+/// [`list`](Self::list) and [`get_batch_ordered`](Self::get_batch_ordered)
+/// sort by key instead. Inserts never sort: `list` keeps a sorted key index
+/// that it rebuilds only when keys were added or removed since the last call.
+///
+/// # Hashing
+///
+/// The internal map uses the standard library's DoS-resistant `RandomState`
+/// hasher by default. When keys are trusted, a faster hasher can be supplied
+/// with [`with_hasher`](Self::with_hasher) or
+/// [`CacheBuilder::build_with_hasher`](crate::CacheBuilder::build_with_hasher).
+///
 /// # Examples
 ///
 /// ## Basic Usage
@@ -215,17 +574,47 @@ impl PartialEq for CacheItem {
 /// }
 /// ```
 #[derive(Clone, Debug)]
-pub struct Cache {
-    map: IndexMap<Key, CacheItem>,
+pub struct Cache<S = RandomState> {
+    map: IndexMap<Key, CacheItem, S>,
     capacity: usize,
     default_ttl: Option<Duration>,
-    sender: Option<Sender<Event>>,
+    ttl_jitter: Duration,
+    stale_grace: Duration,
+    sender: Option<EventSender>,
+    dropped_events: u64,
+    observers: Vec<Sender<Event>>,
+    key_watchers: HashMap<Key, Vec<WatcherSlot>>,
+    eviction_policy: EvictionPolicy,
+    clock_hand: usize,
+    sorted_keys: Vec<Key>,
+    sorted_keys_dirty: bool,
+    recent_events: VecDeque<Event>,
+    recent_events_capacity: usize,
+    key_separator: char,
+    key_normalizer: Option<KeyNormalizer>,
+    value_transform: Option<ValueTransform>,
+    insert_validator: Option<InsertValidator>,
+    track_access_counts: bool,
+    clock: Clock,
+    eviction_batch_size: usize,
+    on_evict: Option<EvictionCallback>,
+    eviction_guard: Option<EvictionGuard>,
+    resize_policy: ResizePolicy,
+    watermarks: Option<Watermarks>,
+    last_modified: Option<u64>,
+    backing_store: Option<StoreHandle>,
     #[cfg(feature = "persist")]
     persist_path: Option<std::path::PathBuf>,
+    #[cfg(feature = "persist")]
+    persist_errors: Option<PersistErrorChannel>,
+    #[cfg(feature = "persist")]
+    persist_writer: Option<Arc<std::thread::JoinHandle<()>>>,
+    #[cfg(feature = "persist")]
+    persist_compress_over: Option<usize>,
     _phantom: std::marker::PhantomData<Value>,
 }
 
-impl PartialEq for Cache {
+impl<S: BuildHasher> PartialEq for Cache<S> {
     fn eq(&self, other: &Self) -> bool {
         self.map == other.map
             && self.capacity == other.capacity
@@ -236,6 +625,9 @@ impl PartialEq for Cache {
 impl Cache {
     /// Creates a new cache with the specified capacity.
     ///
+    /// A cache with capacity 0 stores nothing: every insert is dropped without
+    /// sending an event.
+    ///
     /// # Examples
     ///
     /// ```
@@ -246,15 +638,122 @@ impl Cache {
     /// assert!(cache.is_empty());
     /// ```
     pub fn new(capacity: usize) -> Self {
-        Self {
-            map: IndexMap::with_capacity(capacity),
-            capacity,
-            default_ttl: None,
-            sender: None,
-            #[cfg(feature = "persist")]
-            persist_path: None,
-            _phantom: std::marker::PhantomData,
+        Self::with_hasher(capacity, RandomState::new())
+    }
+
+    /// Replays `access_trace` against an empty cache with the given capacity
+    /// and eviction policy, and reports how many accesses would have hit.
+    ///
+    /// Each missed key is inserted with a `Value::Null` placeholder, evicting
+    /// as a real cache would, so different capacities and policies can be
+    /// compared on a recorded trace before picking one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, EvictionPolicy};
+    ///
+    /// let trace = ["a", "b", "a", "c", "a", "b"];
+    ///
+    /// let small = Cache::simulate(trace, 2, EvictionPolicy::Clock);
+    /// assert_eq!((small.hits, small.misses), (2, 4));
+    ///
+    /// let large = Cache::simulate(trace, 3, EvictionPolicy::Clock);
+    /// assert_eq!(large.hits, 3);
+    /// assert_eq!(large.hit_ratio, 0.5);
+    /// ```
+    pub fn simulate<I, T>(access_trace: I, capacity: usize, policy: EvictionPolicy) -> SimReport
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let mut cache = Self::new(capacity);
+        cache.set_eviction_policy(policy);
+
+        let mut report = SimReport::default();
+        for key in access_trace {
+            let key = key.into();
+            if cache.get(&key).is_some() {
+                report.hits += 1;
+            } else {
+                report.misses += 1;
+                cache.insert(key, Value::Null);
+            }
+        }
+
+        let accesses = report.hits + report.misses;
+        if accesses > 0 {
+            report.hit_ratio = report.hits as f64 / accesses as f64;
+        }
+        report
+    }
+
+    /// Rebuilds a cache by replaying `events`, e.g. from an event log kept as the
+    /// source of truth.
+    ///
+    /// `Insert` sets the key (the last write wins), `Remove` deletes it and
+    /// `Clear` empties the cache. Events carry no TTL, so the rebuilt entries
+    /// never expire, and inserts past `capacity` evict as usual. Replaying sends
+    /// no events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Event};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let events = vec![
+    ///     Event::insert("a".to_string(), 1.to_value()),
+    ///     Event::insert("b".to_string(), 2.to_value()),
+    ///     Event::remove("a".to_string(), 1.to_value()),
+    /// ];
+    ///
+    /// let mut cache = Cache::from_events(10, events);
+    /// assert_eq!(cache.len(), 1);
+    /// assert_eq!(cache.get("b"), Some(&2.to_value()));
+    /// ```
+    pub fn from_events<I>(capacity: usize, events: I) -> Self
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let mut cache = Self::new(capacity);
+        for event in events {
+            match event {
+                Event::Insert(data) => cache.insert(data.key, data.value),
+                Event::Remove(data) => {
+                    cache.remove(&data.key).ok();
+                }
+                Event::Clear => cache.clear(),
+            }
         }
+        cache
+    }
+
+    /// Creates a new cache for string values, to be used with
+    /// [`insert_str`](Self::insert_str) and [`get_str`](Self::get_str).
+    ///
+    /// It installs an [insert validator](Self::set_insert_validator) that
+    /// rejects any value that is not a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new_strings(10);
+    /// cache.insert_str("greeting", "hello");
+    /// cache.insert("count", 1);
+    ///
+    /// assert_eq!(cache.get_str("greeting"), Some("hello"));
+    /// assert!(!cache.contains_key("count"));
+    /// ```
+    pub fn new_strings(capacity: usize) -> Self {
+        let mut cache = Self::new(capacity);
+        cache.set_insert_validator(Some(|_, value| match value {
+            Value::String(_) => Ok(()),
+            _ => Err("expected a string value".to_string()),
+        }));
+        cache
     }
 
     /// Creates a new cache with event notifications.
@@ -280,9 +779,39 @@ impl Cache {
             map: IndexMap::with_capacity(capacity),
             capacity,
             default_ttl: None,
-            sender: Some(sender),
+            ttl_jitter: Duration::ZERO,
+            stale_grace: Duration::ZERO,
+            sender: Some(EventSender::Unbounded(sender)),
+            dropped_events: 0,
+            observers: Vec::new(),
+            key_watchers: HashMap::new(),
+            eviction_policy: EvictionPolicy::Lru,
+            clock_hand: 0,
+            sorted_keys: Vec::new(),
+            sorted_keys_dirty: true,
+            recent_events: VecDeque::new(),
+            recent_events_capacity: 0,
+            key_separator: DEFAULT_KEY_SEPARATOR,
+            key_normalizer: None,
+            value_transform: None,
+            insert_validator: None,
+            track_access_counts: false,
+            clock: system_clock,
+            eviction_batch_size: 1,
+            on_evict: None,
+            eviction_guard: None,
+            resize_policy: ResizePolicy::Fixed,
+            watermarks: None,
+            last_modified: None,
+            backing_store: None,
             #[cfg(feature = "persist")]
             persist_path: None,
+            #[cfg(feature = "persist")]
+            persist_errors: None,
+            #[cfg(feature = "persist")]
+            persist_writer: None,
+            #[cfg(feature = "persist")]
+            persist_compress_over: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -306,9 +835,39 @@ impl Cache {
             map: IndexMap::with_capacity(capacity),
             capacity,
             default_ttl: Some(default_ttl),
+            ttl_jitter: Duration::ZERO,
+            stale_grace: Duration::ZERO,
             sender: None,
+            dropped_events: 0,
+            observers: Vec::new(),
+            key_watchers: HashMap::new(),
+            eviction_policy: EvictionPolicy::Lru,
+            clock_hand: 0,
+            sorted_keys: Vec::new(),
+            sorted_keys_dirty: true,
+            recent_events: VecDeque::new(),
+            recent_events_capacity: 0,
+            key_separator: DEFAULT_KEY_SEPARATOR,
+            key_normalizer: None,
+            value_transform: None,
+            insert_validator: None,
+            track_access_counts: false,
+            clock: system_clock,
+            eviction_batch_size: 1,
+            on_evict: None,
+            eviction_guard: None,
+            resize_policy: ResizePolicy::Fixed,
+            watermarks: None,
+            last_modified: None,
+            backing_store: None,
             #[cfg(feature = "persist")]
             persist_path: None,
+            #[cfg(feature = "persist")]
+            persist_errors: None,
+            #[cfg(feature = "persist")]
+            persist_writer: None,
+            #[cfg(feature = "persist")]
+            persist_compress_over: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -340,13 +899,96 @@ impl Cache {
             map: IndexMap::with_capacity(capacity),
             capacity,
             default_ttl: Some(default_ttl),
-            sender: Some(sender),
+            ttl_jitter: Duration::ZERO,
+            stale_grace: Duration::ZERO,
+            sender: Some(EventSender::Unbounded(sender)),
+            dropped_events: 0,
+            observers: Vec::new(),
+            key_watchers: HashMap::new(),
+            eviction_policy: EvictionPolicy::Lru,
+            clock_hand: 0,
+            sorted_keys: Vec::new(),
+            sorted_keys_dirty: true,
+            recent_events: VecDeque::new(),
+            recent_events_capacity: 0,
+            key_separator: DEFAULT_KEY_SEPARATOR,
+            key_normalizer: None,
+            value_transform: None,
+            insert_validator: None,
+            track_access_counts: false,
+            clock: system_clock,
+            eviction_batch_size: 1,
+            on_evict: None,
+            eviction_guard: None,
+            resize_policy: ResizePolicy::Fixed,
+            watermarks: None,
+            last_modified: None,
+            backing_store: None,
             #[cfg(feature = "persist")]
             persist_path: None,
+            #[cfg(feature = "persist")]
+            persist_errors: None,
+            #[cfg(feature = "persist")]
+            persist_writer: None,
+            #[cfg(feature = "persist")]
+            persist_compress_over: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Creates a new cache that mirrors its entries into `store`.
+    ///
+    /// Entries returned by [`BackingStore::load`] are loaded first (in key order,
+    /// skipping expired ones and stopping at `capacity`). Afterwards every insert,
+    /// update, removal, eviction, expiry and clear is written to the store,
+    /// either synchronously or from a background thread depending on `mode`.
+    /// Write failures are logged as warnings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::{Cache, SqliteStore, WriteMode};
+    ///
+    /// let store = SqliteStore::open("data/cache.db").unwrap();
+    /// let mut cache = Cache::with_backing_store(1000, store, WriteMode::WriteBack).unwrap();
+    /// cache.insert("key", "value");
+    /// # }
+    /// ```
+    pub fn with_backing_store<B: BackingStore>(
+        capacity: usize,
+        store: B,
+        mode: WriteMode,
+    ) -> Result<Self, StoreError> {
+        let mut cache = Self::new(capacity);
+
+        let mut items = store.load()?;
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let now = cache.now();
+        for (key, item) in items {
+            if !item.is_expired_at(now) {
+                cache.insert_raw(key, item);
+            }
+        }
+
+        cache.backing_store = Some(match mode {
+            WriteMode::WriteThrough => StoreHandle::WriteThrough(Arc::new(store)),
+            WriteMode::WriteBack => {
+                let (tx, rx) = std::sync::mpsc::channel();
+                std::thread::spawn(move || {
+                    crate::backing_store::run_writer(&store, rx, |op, e| {
+                        log_warn!("Backing store write failed for {:?}: {}", op, e);
+                    })
+                });
+                StoreHandle::WriteBack(tx)
+            }
+        });
+
+        Ok(cache)
+    }
+
     /// Creates a new cache with SQLite persistence.
     ///
     /// This constructor enables automatic persistence of all cache operations to a SQLite database.
@@ -368,37 +1010,19 @@ impl Cache {
         path: P,
         capacity: usize,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        use crate::sqlite_store::{ensure_db_file, items_from_db, spawn_writer, PersistentEvent};
-
-        let path = path.as_ref().to_path_buf();
-
-        ensure_db_file(&path)?;
+        use crate::sqlite_store::items_from_db;
 
-        let (event_tx, event_rx) = channel();
-        let (persist_tx, persist_rx) = channel();
-
-        spawn_writer(path.clone(), persist_rx);
+        let path = path.as_ref();
 
-        let mut cache = Self::with_sender(capacity, event_tx);
-        cache.persist_path = Some(path.clone());
-
-        std::thread::spawn(move || {
-            while let Ok(event) = event_rx.recv() {
-                let persistent_event = PersistentEvent::new(event.clone());
-                if persist_tx.send(persistent_event).is_err() {
-                    break;
-                }
-            }
-        });
+        let mut cache = Self::new(capacity);
+        cache.backing_store = Some(cache.start_writer(path)?);
 
-        let mut items = items_from_db(&path)?;
+        let mut items = items_from_db(path)?;
 
         items.sort_by(|a, b| a.0.cmp(&b.0));
 
         for (key, item) in items {
-            if cache.map.len() < capacity {
-                cache.map.insert(key, item);
-            }
+            cache.insert_raw(key, item);
         }
 
         Ok(cache)
@@ -440,39 +1064,19 @@ impl Cache {
         capacity: usize,
         external_sender: Sender<Event>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        use crate::sqlite_store::{ensure_db_file, items_from_db, spawn_writer, PersistentEvent};
-
-        let path = path.as_ref().to_path_buf();
+        use crate::sqlite_store::items_from_db;
 
-        ensure_db_file(&path)?;
+        let path = path.as_ref();
 
-        let (event_tx, event_rx) = channel();
-        let (persist_tx, persist_rx) = channel();
-
-        spawn_writer(path.clone(), persist_rx);
-
-        let mut cache = Self::with_sender(capacity, event_tx);
-        cache.persist_path = Some(path.clone());
-
-        std::thread::spawn(move || {
-            while let Ok(event) = event_rx.recv() {
-                let _ = external_sender.send(event.clone());
-
-                let persistent_event = PersistentEvent::new(event);
-                if persist_tx.send(persistent_event).is_err() {
-                    break;
-                }
-            }
-        });
+        let mut cache = Self::with_sender(capacity, external_sender);
+        cache.backing_store = Some(cache.start_writer(path)?);
 
-        let mut items = items_from_db(&path)?;
+        let mut items = items_from_db(path)?;
 
         items.sort_by(|a, b| a.0.cmp(&b.0));
 
         for (key, item) in items {
-            if cache.map.len() < capacity {
-                cache.map.insert(key, item);
-            }
+            cache.insert_raw(key, item);
         }
 
         Ok(cache)
@@ -511,36 +1115,20 @@ impl Cache {
         capacity: usize,
         default_ttl: Duration,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        use crate::sqlite_store::{ensure_db_file, items_from_db, spawn_writer, PersistentEvent};
-
-        let path = path.as_ref().to_path_buf();
-
-        ensure_db_file(&path)?;
-
-        let (event_tx, event_rx) = channel();
-        let (persist_tx, persist_rx) = channel();
-
-        spawn_writer(path.clone(), persist_rx);
+        use crate::sqlite_store::items_from_db;
 
-        let mut cache = Self::with_sender_and_ttl(capacity, event_tx, default_ttl);
-        cache.persist_path = Some(path.clone());
+        let path = path.as_ref();
 
-        std::thread::spawn(move || {
-            while let Ok(event) = event_rx.recv() {
-                let persistent_event = PersistentEvent::new(event.clone());
-                if persist_tx.send(persistent_event).is_err() {
-                    break;
-                }
-            }
-        });
+        let mut cache = Self::with_default_ttl(capacity, default_ttl);
+        cache.backing_store = Some(cache.start_writer(path)?);
 
-        let mut items = items_from_db(&path)?;
+        let mut items = items_from_db(path)?;
 
         items.sort_by(|a, b| a.0.cmp(&b.0));
 
         for (key, item) in items {
-            if !item.is_expired() && cache.map.len() < capacity {
-                cache.map.insert(key, item);
+            if !item.is_expired() {
+                cache.insert_raw(key, item);
             }
         }
 
@@ -592,462 +1180,4910 @@ impl Cache {
         external_sender: Sender<Event>,
         default_ttl: Duration,
     ) -> Result<Self, Box<dyn std::error::Error>> {
-        use crate::sqlite_store::{ensure_db_file, items_from_db, spawn_writer, PersistentEvent};
-
-        let path = path.as_ref().to_path_buf();
-
-        ensure_db_file(&path)?;
-
-        let (event_tx, event_rx) = channel();
-        let (persist_tx, persist_rx) = channel();
-
-        spawn_writer(path.clone(), persist_rx);
+        use crate::sqlite_store::items_from_db;
 
-        let mut cache = Self::with_sender_and_ttl(capacity, event_tx, default_ttl);
-        cache.persist_path = Some(path.clone());
+        let path = path.as_ref();
 
-        std::thread::spawn(move || {
-            while let Ok(event) = event_rx.recv() {
-                let _ = external_sender.send(event.clone());
-
-                let persistent_event = PersistentEvent::new(event);
-                if persist_tx.send(persistent_event).is_err() {
-                    break;
-                }
-            }
-        });
+        let mut cache = Self::with_sender_and_ttl(capacity, external_sender, default_ttl);
+        cache.backing_store = Some(cache.start_writer(path)?);
 
-        let mut items = items_from_db(&path)?;
+        let mut items = items_from_db(path)?;
 
         items.sort_by(|a, b| a.0.cmp(&b.0));
 
         for (key, item) in items {
-            if !item.is_expired() && cache.map.len() < capacity {
-                cache.map.insert(key, item);
+            if !item.is_expired() {
+                cache.insert_raw(key, item);
             }
         }
 
         Ok(cache)
     }
+}
+
+impl<S: BuildHasher> Cache<S> {
+    /// Creates a new cache with the specified capacity that hashes keys with `hash_builder`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut cache = Cache::with_hasher(100, RandomState::new());
+    /// cache.insert("key", "value");
+    /// assert_eq!(cache.get("key"), Some(&"value".to_value()));
+    /// ```
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: IndexMap::with_capacity_and_hasher(capacity, hash_builder),
+            capacity,
+            default_ttl: None,
+            ttl_jitter: Duration::ZERO,
+            stale_grace: Duration::ZERO,
+            sender: None,
+            dropped_events: 0,
+            observers: Vec::new(),
+            key_watchers: HashMap::new(),
+            eviction_policy: EvictionPolicy::Lru,
+            clock_hand: 0,
+            sorted_keys: Vec::new(),
+            sorted_keys_dirty: true,
+            recent_events: VecDeque::new(),
+            recent_events_capacity: 0,
+            key_separator: DEFAULT_KEY_SEPARATOR,
+            key_normalizer: None,
+            value_transform: None,
+            insert_validator: None,
+            track_access_counts: false,
+            clock: system_clock,
+            eviction_batch_size: 1,
+            on_evict: None,
+            eviction_guard: None,
+            resize_policy: ResizePolicy::Fixed,
+            watermarks: None,
+            last_modified: None,
+            backing_store: None,
+            #[cfg(feature = "persist")]
+            persist_path: None,
+            #[cfg(feature = "persist")]
+            persist_errors: None,
+            #[cfg(feature = "persist")]
+            persist_writer: None,
+            #[cfg(feature = "persist")]
+            persist_compress_over: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Enables SQLite persistence at `path` on an already configured cache and
+    /// loads the live entries stored there, up to capacity.
+    ///
+    /// Stored keys go through the key normalizer. When several stored keys
+    /// normalize to the same key, the one created last wins, ties going to the
+    /// greatest stored key. Rows stored under a key that is not normalized are
+    /// rewritten under the normalized key and the losing rows are deleted.
+    #[cfg(feature = "persist")]
+    pub(crate) fn attach_persist(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        use crate::sqlite_store::items_from_db;
+
+        let store = self.start_writer(path)?;
+
+        let mut items: Vec<(Key, Key, CacheItem)> = items_from_db(path)?
+            .into_iter()
+            .map(|(raw_key, item)| (self.normalize_owned_key(raw_key.clone()), raw_key, item))
+            .collect();
+        // The last entry of each run of equal keys is the one that survives
+        items.sort_by(|(a, a_raw, a_item), (b, b_raw, b_item)| {
+            a.cmp(b)
+                .then(a_item.created_at.cmp(&b_item.created_at))
+                .then_with(|| a_raw.cmp(b_raw))
+        });
+
+        let now = self.now();
+        let mut items = items.into_iter().peekable();
+        while let Some((key, raw_key, item)) = items.next() {
+            let rewritten = raw_key != key;
+            if rewritten {
+                store.apply(StoreOp::Delete(raw_key));
+            }
+            if items.peek().is_some_and(|(next, _, _)| *next == key) {
+                continue;
+            }
+            if rewritten {
+                store.apply(StoreOp::Put(key.clone(), Box::new(item.clone())));
+            }
+            if !item.is_expired_at(now) {
+                self.insert_raw(key, item);
+            }
+        }
+
+        self.backing_store = Some(store);
+        Ok(())
+    }
+
+    /// Creates the database at `path` if needed and starts a background writer
+    /// for it, returning the handle that feeds the writer
+    #[cfg(feature = "persist")]
+    fn start_writer(&mut self, path: &Path) -> Result<StoreHandle, Box<dyn std::error::Error>> {
+        use crate::sqlite_store::{ensure_db_file, spawn_writer};
+
+        ensure_db_file(path)?;
+
+        let (persist_tx, persist_rx) = channel();
+        let persist_errors = self
+            .persist_errors
+            .take()
+            .unwrap_or_else(PersistErrorChannel::new);
+        let writer = spawn_writer(
+            path.to_path_buf(),
+            persist_rx,
+            persist_errors.sender(),
+            self.persist_compress_over,
+        );
+
+        self.persist_path = Some(path.to_path_buf());
+        self.persist_errors = Some(persist_errors);
+        self.persist_writer = Some(Arc::new(writer));
+        Ok(StoreHandle::WriteBack(persist_tx))
+    }
+
+    /// Sets the compression threshold of SQLite writers started from now on
+    #[cfg(feature = "compress")]
+    pub(crate) fn set_persist_compression(&mut self, bytes: Option<usize>) {
+        self.persist_compress_over = bytes;
+    }
+
+    /// Returns the path of the SQLite database the cache persists to, or `None`
+    /// if SQLite persistence is off.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    /// use std::path::Path;
+    ///
+    /// let cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// assert_eq!(cache.persist_path(), Some(Path::new("data/cache.db")));
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn persist_path(&self) -> Option<&Path> {
+        self.persist_path.as_deref()
+    }
+
+    /// Stops writing to the SQLite database, e.g. to run memory-only during
+    /// maintenance. The entries in memory are kept.
+    ///
+    /// Writes already queued are flushed before this returns, unless a clone of
+    /// the cache still shares the background writer. Undrained
+    /// [`persist_errors`](Self::persist_errors) are kept. Does nothing if SQLite
+    /// persistence is off; other [backing stores](Self::with_backing_store) are
+    /// not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// cache.disable_persistence();
+    /// cache.insert("scratch", "not written to disk");
+    /// assert_eq!(cache.persist_path(), None);
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn disable_persistence(&mut self) {
+        if self.persist_path.take().is_none() {
+            return;
+        }
+        // Dropping the last sender lets the writer drain its queue and exit
+        self.backing_store = None;
+
+        if let Some(writer) = self.persist_writer.take() {
+            if let Ok(writer) = Arc::try_unwrap(writer) {
+                if writer.join().is_err() {
+                    log_warn!("Persistence writer thread panicked");
+                }
+            }
+        }
+    }
+
+    /// Starts persisting to the SQLite database at `path` again, e.g. after
+    /// [`disable_persistence`](Self::disable_persistence).
+    ///
+    /// Changes made while persistence was off were never written, so the
+    /// database is rewritten to hold exactly the live entries in memory; nothing
+    /// is loaded from it. Any current SQLite persistence is disabled first, and
+    /// another backing store is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// cache.disable_persistence();
+    /// cache.insert("key", "value");
+    ///
+    /// cache.enable_persistence("data/cache.db").unwrap(); // "key" is written now
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn enable_persistence<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.disable_persistence();
+        let store = self.start_writer(path.as_ref())?;
+
+        store.apply(StoreOp::Clear);
+        let now = self.now();
+        for (key, item) in &self.map {
+            if !item.is_expired_at(now) {
+                store.apply(StoreOp::Put(key.clone(), Box::new(item.clone())));
+            }
+        }
+
+        self.backing_store = Some(store);
+        Ok(())
+    }
+
+    /// Drains the persistence failures reported since the last call.
+    ///
+    /// The background SQLite writer does not print failures to stderr; instead every
+    /// failed write (for example a full disk or a permission error) is queued here.
+    /// Returns an empty list for caches without persistence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// cache.insert("key", "value");
+    ///
+    /// for error in cache.persist_errors() {
+    ///     eprintln!("{}", error);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn persist_errors(&self) -> Vec<PersistError> {
+        self.persist_errors
+            .as_ref()
+            .map(|errors| errors.drain())
+            .unwrap_or_default()
+    }
+
+    /// Reports whether SQLite persistence is still working, e.g. for a readiness
+    /// probe.
+    ///
+    /// Returns `false` if the background writer thread has stopped (for example
+    /// because it could not open the database) or if the database's write lock
+    /// can't be taken within 100 ms (read-only or missing file, another writer
+    /// holding it). The lock is released right away and nothing is written.
+    /// Returns `true` for caches without SQLite persistence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// if !cache.persistence_healthy() {
+    ///     eprintln!("persistence is degraded");
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn persistence_healthy(&self) -> bool {
+        let Some(path) = &self.persist_path else {
+            return true;
+        };
+
+        if let Some(writer) = &self.persist_writer {
+            if writer.is_finished() {
+                log_debug!("Persistence writer thread has stopped");
+                return false;
+            }
+        }
+
+        match crate::sqlite_store::check_writable(path) {
+            Ok(()) => true,
+            Err(e) => {
+                log_debug!("Persistence health check failed: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Compares the live entries in memory with the rows stored in SQLite.
+    ///
+    /// Writes reach the database asynchronously, so a report taken right after
+    /// an insert may list keys that are still in flight. Keys that have expired
+    /// in memory are ignored on both sides. The database is read through a
+    /// read-only connection and never modified. Returns an empty report for
+    /// caches without persistence.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// cache.insert("key", "value");
+    ///
+    /// let report = cache.verify_persistence().unwrap();
+    /// for key in &report.in_memory_only {
+    ///     eprintln!("'{}' has not been written yet", key);
+    /// }
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn verify_persistence(&self) -> Result<PersistReport, Error> {
+        use crate::sqlite_store::read_items_from_db;
+
+        let mut report = PersistReport::default();
+        let path = match &self.persist_path {
+            Some(path) => path,
+            None => return Ok(report),
+        };
+
+        let stored = read_items_from_db(path)
+            .map_err(|e| Error::Persist(PersistError::new(PersistOperation::Verify, None, e)))?;
+        let now = self.now();
+        let mut stored_keys = std::collections::HashSet::with_capacity(stored.len());
+
+        for (key, item) in stored {
+            match self.map.get(&key) {
+                Some(live) if live.is_expired_at(now) => {}
+                Some(live) if live.value != item.value => report.value_mismatches.push(key.clone()),
+                Some(_) => {}
+                None => report.db_only.push(key.clone()),
+            }
+            stored_keys.insert(key);
+        }
+
+        for (key, item) in &self.map {
+            if !item.is_expired_at(now) && !stored_keys.contains(key) {
+                report.in_memory_only.push(key.clone());
+            }
+        }
+
+        report.in_memory_only.sort();
+        report.db_only.sort();
+        report.value_mismatches.sort();
+        Ok(report)
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), then writes it to
+    /// SQLite before returning, instead of waiting for the background writer.
+    ///
+    /// Use this for critical keys that must survive a crash right after the
+    /// insert. Only the in-memory insert happens for caches without persistence,
+    /// and nothing is written if the insert is dropped (capacity 0 or every entry
+    /// pinned). Returns [`Error::Rejected`] without writing anything if the
+    /// insert validator rejects the value, or [`Error::Persist`] if the write
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "persist")]
+    /// # {
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+    /// cache.insert_durable("balance:42", 100).unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "persist")]
+    pub fn insert_durable<T, V>(&mut self, key: T, value: V) -> Result<(), Error>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+    {
+        let key = self.normalize_owned_key(key.into());
+
+        if self.capacity == 0 {
+            log_debug!("Dropped key '{}': cache capacity is 0", key);
+            return Ok(());
+        }
+
+        let value = self.transform_value(value);
+        self.validate_insert(&key, &value)?;
+        if !self.insert_validated(key.clone(), value, None) {
+            return Ok(());
+        }
+
+        let (Some(path), Some(item)) = (&self.persist_path, self.map.get(&key)) else {
+            return Ok(());
+        };
+        crate::sqlite_store::persist_item(path, &key, item, self.persist_compress_over)
+            .map_err(|e| Error::Persist(PersistError::new(PersistOperation::Insert, Some(key), e)))
+    }
+
+    #[inline]
+    pub fn set_event(&mut self, sender: Sender<Event>) {
+        self.sender = Some(EventSender::Unbounded(sender));
+    }
+
+    /// Sends cache events to a bounded channel, handling a full channel per `overflow`.
+    ///
+    /// With [`EventOverflow::Block`] a mutation waits until the receiver makes
+    /// room. With [`EventOverflow::Drop`] the event is discarded instead and
+    /// counted in [`dropped_events_count`](Self::dropped_events_count), so a slow
+    /// consumer never stalls the cache. Replaces any sender set with
+    /// [`set_event`](Self::set_event).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, EventOverflow};
+    /// use std::sync::mpsc::sync_channel;
+    ///
+    /// let (tx, rx) = sync_channel(2);
+    /// let mut cache = Cache::new(10);
+    /// cache.set_bounded_event(tx, EventOverflow::Drop);
+    ///
+    /// for i in 0..5 {
+    ///     cache.insert(format!("key{}", i), i);
+    /// }
+    /// assert_eq!(cache.len(), 5);
+    /// assert_eq!(cache.dropped_events_count(), 3);
+    /// assert_eq!(rx.try_iter().count(), 2);
+    /// ```
+    #[inline]
+    pub fn set_bounded_event(&mut self, sender: SyncSender<Event>, overflow: EventOverflow) {
+        self.sender = Some(EventSender::Bounded(sender, overflow));
+    }
+
+    /// Returns how many events were discarded because the bounded event channel
+    /// was full.
+    ///
+    /// Only grows under [`EventOverflow::Drop`] (see
+    /// [`set_bounded_event`](Self::set_bounded_event)).
+    #[inline]
+    pub fn dropped_events_count(&self) -> u64 {
+        self.dropped_events
+    }
+
+    #[inline]
+    pub fn remove_event(&mut self) {
+        self.sender = None;
+    }
+
+    /// Keeps the last `capacity` events in memory for inspection with
+    /// [`recent_events`](Self::recent_events).
+    ///
+    /// A capacity of 0 (the default) disables the buffer. Shrinking the buffer
+    /// drops the oldest events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Event};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_recent_events_capacity(2);
+    ///
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    /// cache.insert("c", 3);
+    ///
+    /// let keys: Vec<String> = cache
+    ///     .recent_events()
+    ///     .into_iter()
+    ///     .filter_map(|event| match event {
+    ///         Event::Insert(data) => Some(data.key),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(keys, vec!["b", "c"]);
+    /// ```
+    pub fn set_recent_events_capacity(&mut self, capacity: usize) {
+        self.recent_events_capacity = capacity;
+        while self.recent_events.len() > capacity {
+            self.recent_events.pop_front();
+        }
+        self.recent_events.shrink_to(capacity);
+    }
+
+    #[inline(always)]
+    pub fn get_recent_events_capacity(&self) -> usize {
+        self.recent_events_capacity
+    }
+
+    /// Returns the buffered events, oldest first.
+    ///
+    /// The buffer is filled independently of any event sender, so it does not
+    /// consume events meant for a receiver. It stays empty unless enabled with
+    /// [`set_recent_events_capacity`](Self::set_recent_events_capacity).
+    pub fn recent_events(&self) -> Vec<Event> {
+        self.recent_events.iter().cloned().collect()
+    }
+
+    /// Returns a receiver that first gets an `Insert` event for every live entry,
+    /// then every event the cache sends from now on.
+    ///
+    /// This lets a late subscriber, such as a replica, bootstrap from the current
+    /// contents and then stay in sync. The replay is taken in insertion order
+    /// while the cache is borrowed, so no change can slip in between. Any number
+    /// of observers can be attached alongside the
+    /// [`set_event`](Self::set_event) sender; an observer is dropped once its
+    /// receiver is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Event};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("existing", 1);
+    ///
+    /// let events = cache.observe();
+    /// cache.insert("new", 2);
+    ///
+    /// assert_eq!(
+    ///     events.try_iter().collect::<Vec<_>>(),
+    ///     vec![
+    ///         Event::insert("existing".to_string(), 1.to_value()),
+    ///         Event::insert("new".to_string(), 2.to_value()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn observe(&mut self) -> Receiver<Event> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let now = self.now();
+
+        for (key, item) in &self.map {
+            if !item.is_expired_at(now) {
+                // The receiver is still in scope, so sending can't fail
+                let _ = tx.send(Event::insert(key.clone(), item.value.clone()));
+            }
+        }
+
+        self.observers.push(tx);
+        rx
+    }
+
+    /// Returns a receiver that gets the events for `key` only: its inserts and
+    /// removals (including evictions and expirations), plus every `Clear`.
+    ///
+    /// Useful for waiting on one key, e.g. a lock being released, without
+    /// filtering the whole event stream. Unlike [`observe`](Self::observe),
+    /// nothing is replayed. Dropping the returned [`KeyWatcher`] unregisters it;
+    /// dead watchers are swept here as new ones are added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Event};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("lock", "worker-1");
+    ///
+    /// let lock_events = cache.watch("lock");
+    /// cache.insert("other", 1);
+    /// cache.remove("lock").unwrap();
+    ///
+    /// assert_eq!(
+    ///     lock_events.try_iter().collect::<Vec<_>>(),
+    ///     vec![Event::remove("lock".to_string(), "worker-1".to_value())]
+    /// );
+    /// ```
+    pub fn watch(&mut self, key: &str) -> KeyWatcher {
+        let (tx, alive, watcher) = KeyWatcher::channel();
+        let key = self.normalize_key(key).into_owned();
+
+        // Sweeping whenever a count reaches a power of two keeps the cost
+        // amortized O(1) per call while bounding how many dead watchers pile up
+        if (self.key_watchers.len() + 1).is_power_of_two() {
+            self.key_watchers.retain(|_, watchers| {
+                watchers.retain(|(_, alive)| alive.strong_count() > 0);
+                !watchers.is_empty()
+            });
+        }
+
+        let watchers = self.key_watchers.entry(key).or_default();
+        if (watchers.len() + 1).is_power_of_two() {
+            watchers.retain(|(_, alive)| alive.strong_count() > 0);
+        }
+        watchers.push((tx, alive));
+        watcher
+    }
+
+    #[cfg(test)]
+    pub(crate) fn watcher_count(&self) -> usize {
+        self.key_watchers.values().map(Vec::len).sum()
+    }
+
+    /// Sends `event` to every observer and to the watchers of its key, dropping
+    /// those whose receiver is gone
+    #[inline]
+    fn notify_observers(&mut self, event: &Event) {
+        if !self.observers.is_empty() {
+            self.observers
+                .retain(|observer| observer.send(event.clone()).is_ok());
+        }
+
+        if self.key_watchers.is_empty() {
+            return;
+        }
+
+        match event {
+            Event::Insert(data) | Event::Remove(data) => {
+                if let Some(watchers) = self.key_watchers.get_mut(&data.key) {
+                    watchers.retain(|(watcher, _)| watcher.send(event.clone()).is_ok());
+                    if watchers.is_empty() {
+                        self.key_watchers.remove(&data.key);
+                    }
+                }
+            }
+            Event::Clear => self.key_watchers.retain(|_, watchers| {
+                watchers.retain(|(watcher, _)| watcher.send(Event::Clear).is_ok());
+                !watchers.is_empty()
+            }),
+        }
+    }
+
+    #[inline]
+    fn record_event(&mut self, event: &Event) {
+        if self.recent_events_capacity == 0 {
+            return;
+        }
+
+        if self.recent_events.len() == self.recent_events_capacity {
+            self.recent_events.pop_front();
+        }
+        self.recent_events.push_back(event.clone());
+    }
+
+    #[inline]
+    fn send_event(&mut self, event: Event) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let kind = match event {
+            Event::Insert(_) => "insert",
+            Event::Remove(_) => "remove",
+            Event::Clear => "clear",
+        };
+        match sender.send(event) {
+            SendStatus::Sent => {}
+            SendStatus::Full => {
+                self.dropped_events += 1;
+                log_debug!("Dropped {} event: the event channel is full", kind);
+            }
+            SendStatus::Disconnected => {
+                log_debug!("Failed to send {} event: the receiver is gone", kind)
+            }
+        }
+    }
+
+    #[inline]
+    fn send_insert(&mut self, key: Key, value: Value) {
+        if let Some(store) = &self.backing_store {
+            if let Some(item) = self.map.get(&key) {
+                store.apply(StoreOp::Put(key.clone(), Box::new(item.clone())));
+            }
+        }
+
+        let event = Event::insert(key, value);
+        self.record_event(&event);
+        self.notify_observers(&event);
+        self.send_event(event);
+
+        self.last_modified = Some(self.now());
+        self.check_watermarks();
+    }
+
+    #[inline]
+    fn send_remove(&mut self, key: Key, value: Value) {
+        if let Some(store) = &self.backing_store {
+            store.apply(StoreOp::Delete(key.clone()));
+        }
+
+        let event = Event::remove(key, value);
+        self.record_event(&event);
+        self.notify_observers(&event);
+        self.send_event(event);
+
+        self.last_modified = Some(self.now());
+        self.check_watermarks();
+    }
+
+    #[inline]
+    fn send_clear(&mut self) {
+        if let Some(store) = &self.backing_store {
+            store.apply(StoreOp::Clear);
+        }
+
+        let event = Event::clear();
+        self.record_event(&event);
+        self.notify_observers(&event);
+        self.send_event(event);
+
+        self.last_modified = Some(self.now());
+        self.check_watermarks();
+    }
 
-    #[inline]
-    pub fn set_event(&mut self, sender: Sender<Event>) {
-        self.sender = Some(sender);
-    }
-
-    #[inline]
-    pub fn remove_event(&mut self) {
-        self.sender = None;
-    }
-
-    #[inline]
-    fn send_insert(&self, key: Key, value: Value) {
-        if let Some(sender) = &self.sender {
-            let event = Event::insert(key, value);
-            sender.send(event).unwrap();
-        }
-    }
-
-    #[inline]
-    fn send_remove(&self, key: Key, value: Value) {
-        if let Some(sender) = &self.sender {
-            let event = Event::remove(key, value);
-            sender.send(event).unwrap();
-        }
-    }
-
-    #[inline]
-    fn send_clear(&self) {
-        if let Some(sender) = &self.sender {
-            let event = Event::clear();
-            sender.send(event).unwrap();
-        }
-    }
-
     /// Inserts a key-value pair into the cache.
     ///
-    /// If the cache is at capacity, the least recently used item will be evicted.
-    /// If a default TTL is set, the item will inherit that TTL.
+    /// If the cache is at capacity, the least recently used item will be evicted.
+    /// If a default TTL is set, the item will inherit that TTL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(2);
+    /// cache.insert("key1", "value1");
+    /// cache.insert("key2", "value2");
+    /// cache.insert("key3", "value3");  
+    ///
+    /// assert_eq!(cache.get("key1"), None);  
+    /// assert_eq!(cache.get("key2"), Some(&"value2".to_value()));
+    /// assert_eq!(cache.get("key3"), Some(&"value3".to_value()));
+    /// ```
+    pub fn insert<T, V>(&mut self, key: T, value: V)
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+    {
+        let _ = self.try_insert(key, value);
+    }
+
+    /// Inserts a string value, like [`insert`](Self::insert).
+    ///
+    /// Read it back as `&str` with [`get_str`](Self::get_str).
+    pub fn insert_str<T>(&mut self, key: T, value: &str)
+    where
+        T: Into<String>,
+    {
+        self.insert(key, value);
+    }
+
+    /// Inserts a key-value pair like [`insert`](Self::insert), returning an
+    /// error if the insert validator rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rejected`] with the validator's reason. Nothing is
+    /// stored, sent or persisted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_insert_validator(Some(|key: &str, _: &quickleaf::Value| {
+    ///     if key.starts_with("user:") {
+    ///         Ok(())
+    ///     } else {
+    ///         Err(format!("unexpected key '{}'", key))
+    ///     }
+    /// }));
+    ///
+    /// assert!(cache.try_insert("user:1", "alice").is_ok());
+    /// assert_eq!(
+    ///     cache.try_insert("admin", "bob"),
+    ///     Err(Error::Rejected("unexpected key 'admin'".to_string()))
+    /// );
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn try_insert<T, V>(&mut self, key: T, value: V) -> Result<(), Error>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+    {
+        let key = self.normalize_owned_key(key.into());
+
+        if self.capacity == 0 {
+            log_debug!("Dropped key '{}': cache capacity is 0", key);
+            return Ok(());
+        }
+
+        let value = self.transform_value(value);
+        self.validate_insert(&key, &value)?;
+        self.insert_validated(key, value, None);
+        Ok(())
+    }
+
+    /// Inserts a batch of key-value pairs only if every one of them can be stored.
+    ///
+    /// Each pair is normalized, transformed and validated like
+    /// [`try_insert`](Self::try_insert), and the room for the batch's new keys is
+    /// checked, before anything is stored. Room is made up front, growing per the
+    /// resize policy or evicting per the eviction policy, and never at the
+    /// expense of pinned entries or entries the batch updates. The pairs are then
+    /// inserted in order, so a key repeated in the batch keeps its last value.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Error::Rejected`] in batch order, or
+    /// [`Error::CapacityExceeded`] if the new keys would not fit. Nothing is
+    /// stored, evicted, sent or persisted in either case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_insert_validator(Some(|key: &str, _: &quickleaf::Value| {
+    ///     if key.starts_with("user:") {
+    ///         Ok(())
+    ///     } else {
+    ///         Err(format!("unexpected key '{}'", key))
+    ///     }
+    /// }));
+    ///
+    /// let batch = vec![("user:1".to_string(), "alice"), ("admin".to_string(), "bob")];
+    /// assert_eq!(
+    ///     cache.insert_transaction(batch),
+    ///     Err(Error::Rejected("unexpected key 'admin'".to_string()))
+    /// );
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn insert_transaction<I, V>(&mut self, items: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (String, V)>,
+        V: ToValueBehavior,
+    {
+        let items = items
+            .into_iter()
+            .map(|(key, value)| {
+                let key = self.normalize_owned_key(key);
+                let value = self.transform_value(value);
+                self.validate_insert(&key, &value)?;
+                Ok((key, value))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let batch: std::collections::HashSet<&Key> = items.iter().map(|(key, _)| key).collect();
+        let new_keys = batch
+            .iter()
+            .filter(|key| !self.map.contains_key(key.as_str()))
+            .count();
+        let evictable = self
+            .map
+            .iter()
+            .filter(|(key, item)| !item.pinned && !batch.contains(key))
+            .count();
+        let max_capacity = match self.resize_policy {
+            ResizePolicy::Grow { max, .. } => max.max(self.capacity),
+            ResizePolicy::Fixed => self.capacity,
+        };
+        if (self.map.len() + new_keys).saturating_sub(max_capacity) > evictable {
+            log_debug!(
+                "Rejected insert transaction: {} new keys do not fit",
+                new_keys
+            );
+            return Err(Error::CapacityExceeded);
+        }
+
+        while self.map.len() + new_keys > self.capacity && self.grow() {}
+        let excess = (self.map.len() + new_keys).saturating_sub(self.capacity);
+        if excess > 0 {
+            let batch: Vec<Key> = batch.into_iter().cloned().collect();
+            self.evict_outside(excess, &batch);
+        }
+
+        for (key, value) in items {
+            self.insert_validated(key, value, None);
+        }
+        Ok(())
+    }
+
+    /// Evicts `count` entries per the eviction policy, sparing those for `keys`
+    /// by pinning them for the duration
+    fn evict_outside(&mut self, count: usize, keys: &[Key]) {
+        let shielded: Vec<&Key> = keys
+            .iter()
+            .filter(|key| match self.map.get_mut(key.as_str()) {
+                Some(item) if !item.pinned => {
+                    item.pinned = true;
+                    true
+                }
+                _ => false,
+            })
+            .collect();
+        let evicted = self.evict(count);
+        for key in shielded {
+            if let Some(item) = self.map.get_mut(key.as_str()) {
+                item.pinned = false;
+            }
+        }
+
+        for (evicted_key, evicted_item) in evicted {
+            log_debug!("Evicted key '{}' to make room for a batch", evicted_key);
+            self.call_on_evict(&evicted_key, &evicted_item.value);
+            self.send_remove(evicted_key, evicted_item.value);
+        }
+    }
+
+    /// Stores a normalized, transformed and validated value with `ttl`, or the
+    /// default TTL if `None`. Returns whether there was room for it.
+    fn insert_validated(&mut self, key: Key, value: Value, ttl: Option<Duration>) -> bool {
+        let mut item = match ttl.or(self.default_ttl) {
+            Some(ttl) => CacheItem::with_ttl(value, self.jittered(ttl)),
+            None => CacheItem::new(value),
+        };
+        item.created_at = self.now();
+        item.modified_at = item.created_at;
+
+        if let Some(existing_item) = self.map.get(&key) {
+            if existing_item.value == item.value && !existing_item.is_expired_at(item.created_at) {
+                return true;
+            }
+            item.pinned = existing_item.pinned;
+        }
+
+        if !self.make_room_for(&key) {
+            return false;
+        }
+
+        if self.map.insert(key.clone(), item.clone()).is_none() {
+            self.sorted_keys_dirty = true;
+        }
+
+        self.send_insert(key, item.value);
+        true
+    }
+
+    /// Makes room for `key` if it is new and the cache is full, returning whether
+    /// it fits. It doesn't if every entry is pinned.
+    fn make_room_for(&mut self, key: &str) -> bool {
+        if self.map.len() >= self.capacity && !self.map.contains_key(key) {
+            self.make_room(key);
+            if self.map.len() >= self.capacity {
+                log_debug!("Dropped key '{}': every entry is pinned", key);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Inserts a key-value pair with a specific TTL.
+    ///
+    /// The TTL overrides any default TTL set for the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    /// use std::thread;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("session", "user123", Duration::from_millis(100));
+    ///
+    /// assert!(cache.contains_key("session"));
+    /// thread::sleep(Duration::from_millis(150));
+    /// assert!(!cache.contains_key("session"));  
+    /// ```
+    pub fn insert_with_ttl<T, V>(&mut self, key: T, value: V, ttl: Duration)
+    where
+        T: Into<String> + Clone + AsRef<str>,
+        V: ToValueBehavior,
+    {
+        let _ = self.try_insert_with_ttl(key, value, ttl);
+    }
+
+    /// Inserts a key-value pair like [`insert_with_ttl`](Self::insert_with_ttl),
+    /// returning an error if the insert validator rejects it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rejected`] with the validator's reason. Nothing is
+    /// stored, sent or persisted in that case.
+    pub fn try_insert_with_ttl<T, V>(
+        &mut self,
+        key: T,
+        value: V,
+        ttl: Duration,
+    ) -> Result<(), Error>
+    where
+        T: Into<String> + Clone + AsRef<str>,
+        V: ToValueBehavior,
+    {
+        let key = self.normalize_owned_key(key.into());
+
+        if self.capacity == 0 {
+            log_debug!("Dropped key '{}': cache capacity is 0", key);
+            return Ok(());
+        }
+
+        let value = self.transform_value(value);
+        self.validate_insert(&key, &value)?;
+        self.insert_validated(key, value, Some(ttl));
+        Ok(())
+    }
+
+    /// Inserts a key-value pair with an optional TTL.
+    ///
+    /// `Some(ttl)` behaves like [`insert_with_ttl`](Self::insert_with_ttl), and
+    /// `None` like [`insert`](Self::insert): the default TTL applies if one is set,
+    /// otherwise the item never expires.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::with_default_ttl(10, Duration::from_secs(60));
+    /// cache.insert_opt_ttl("custom", 1, Some(Duration::from_secs(5)));
+    /// cache.insert_opt_ttl("default", 2, None);
+    ///
+    /// assert_eq!(cache.get_ttl("custom"), Some(Duration::from_secs(5)));
+    /// assert_eq!(cache.get_ttl("default"), Some(Duration::from_secs(60)));
+    /// ```
+    pub fn insert_opt_ttl<T, V>(&mut self, key: T, value: V, ttl: Option<Duration>)
+    where
+        T: Into<String> + Clone + AsRef<str>,
+        V: ToValueBehavior,
+    {
+        match ttl {
+            Some(ttl) => self.insert_with_ttl(key, value, ttl),
+            None => self.insert(key, value),
+        }
+    }
+
+    /// Stores `item` under `key` as is, without sending events or writing to
+    /// persistence, and returns whether it was stored.
+    ///
+    /// Meant for bulk loading from a trusted source, such as the entries the
+    /// persistent constructors read back from SQLite: the item keeps its
+    /// `created_at`, TTL and pin, and no transform or validator runs. The key is
+    /// still normalized. Never evicts: an item for a new key is skipped once the
+    /// cache is full, while one for a key already present replaces it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, CacheItem};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::sync::mpsc::channel;
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = channel();
+    /// let mut cache = Cache::with_sender(1, tx);
+    ///
+    /// let item = CacheItem::with_ttl("data".to_value(), Duration::from_secs(60));
+    /// assert!(cache.insert_raw("session", item));
+    /// assert!(!cache.insert_raw("other", CacheItem::new(1.to_value())));
+    ///
+    /// assert_eq!(cache.get_ttl("session"), Some(Duration::from_secs(60)));
+    /// assert!(rx.try_recv().is_err());
+    /// ```
+    pub fn insert_raw<T>(&mut self, key: T, item: CacheItem) -> bool
+    where
+        T: Into<String>,
+    {
+        let key = self.normalize_owned_key(key.into());
+
+        if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
+            return false;
+        }
+
+        if self.map.insert(key, item).is_none() {
+            self.sorted_keys_dirty = true;
+        }
+        true
+    }
+
+    /// Bulk-populates the cache with the pairs returned by `loader`.
+    ///
+    /// Warming never evicts: pairs for new keys are skipped once the cache is full,
+    /// while pairs for keys already present update them in place.
+    /// Returns the number of pairs that were inserted, which excludes pairs the
+    /// insert validator rejects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(2);
+    /// let loaded = cache.warm_up(|| {
+    ///     vec![
+    ///         ("a".to_string(), 1),
+    ///         ("b".to_string(), 2),
+    ///         ("c".to_string(), 3),
+    ///     ]
+    /// });
+    ///
+    /// assert_eq!(loaded, 2);
+    /// assert_eq!(cache.get("a"), Some(&1.to_value()));
+    /// assert_eq!(cache.get("c"), None);
+    /// ```
+    pub fn warm_up<F, V>(&mut self, loader: F) -> usize
+    where
+        F: Fn() -> Vec<(String, V)>,
+        V: ToValueBehavior,
+    {
+        let mut loaded = 0;
+
+        for (key, value) in loader() {
+            let key = self.normalize_owned_key(key);
+            if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
+                continue;
+            }
+
+            if self.try_insert(key, value).is_ok() {
+                loaded += 1;
+            }
+        }
+
+        loaded
+    }
+
+    /// Loads the given keys that are not already cached.
+    ///
+    /// `loader` is only called for keys that are missing (or expired), and keys for
+    /// which it returns `None` are skipped. Loading stops once the cache is full.
+    /// Returns the number of keys that were loaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:1", "cached");
+    ///
+    /// let loaded = cache.warm_up_keys(&["user:1", "user:2"], |key| Some(format!("loaded {}", key)));
+    ///
+    /// assert_eq!(loaded, 1);
+    /// assert_eq!(cache.get("user:1"), Some(&"cached".to_value()));
+    /// assert_eq!(cache.get("user:2"), Some(&"loaded user:2".to_value()));
+    /// ```
+    pub fn warm_up_keys<F, V>(&mut self, keys: &[&str], loader: F) -> usize
+    where
+        F: Fn(&str) -> Option<V>,
+        V: ToValueBehavior,
+    {
+        let mut loaded = 0;
+
+        for key in keys {
+            if self.contains_key(key) {
+                continue;
+            }
+
+            if self.map.len() >= self.capacity {
+                break;
+            }
+
+            if let Some(value) = loader(key) {
+                self.insert(*key, value);
+                loaded += 1;
+            }
+        }
+
+        loaded
+    }
+
+    /// Applies `f` to the value of every live entry in place.
+    ///
+    /// Expired entries are skipped. Entries whose value actually changed fire an
+    /// insert event (and are therefore persisted when persistence is enabled).
+    /// Returns the number of changed entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("greeting", "hello");
+    /// cache.insert("name", "world");
+    ///
+    /// let changed = cache.map_values(|key, value| {
+    ///     if key == "greeting" {
+    ///         *value = "hi".to_value();
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(changed, 1);
+    /// assert_eq!(cache.get("greeting"), Some(&"hi".to_value()));
+    /// assert_eq!(cache.get("name"), Some(&"world".to_value()));
+    /// ```
+    pub fn map_values<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&str, &mut Value),
+    {
+        let now = self.now();
+        let mut changed = Vec::new();
+
+        for (key, item) in self.map.iter_mut() {
+            if item.is_expired_at(now) {
+                continue;
+            }
+
+            let previous = item.value.clone();
+            f(key, &mut item.value);
+
+            if item.value != previous {
+                item.modified_at = now;
+                changed.push((key.clone(), item.value.clone()));
+            }
+        }
+
+        let changed_count = changed.len();
+
+        for (key, value) in changed {
+            self.send_insert(key, value);
+        }
+
+        changed_count
+    }
+
+    /// Retrieves a value from the cache by key.
+    ///
+    /// Returns `None` if the key doesn't exist or if the item has expired.
+    /// Expired items are automatically removed during this operation (lazy cleanup),
+    /// unless they are still within the [stale grace period](Self::set_stale_grace).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("existing", "data");
+    ///
+    /// assert_eq!(cache.get("existing"), Some(&"data".to_value()));
+    /// assert_eq!(cache.get("nonexistent"), None);
+    /// ```
+    #[inline]
+    pub fn get(&mut self, key: &str) -> Option<&Value> {
+        let key = self.normalize_key(key);
+        let key = key.as_ref();
+        let now = self.now();
+        let is_expired = match self.map.get(key) {
+            Some(item) => item.is_expired_at(now),
+            None => return None,
+        };
+
+        if is_expired {
+            if self
+                .map
+                .get(key)
+                .is_some_and(|item| self.is_past_grace(item, now))
+            {
+                if let Some(expired_item) = self.map.shift_remove(key) {
+                    self.sorted_keys_dirty = true;
+                    self.send_remove(key.to_string(), expired_item.value);
+                }
+            }
+            None
+        } else {
+            let track_access_counts = self.track_access_counts;
+            self.map.get_mut(key).map(|item| {
+                item.touch(track_access_counts);
+                &item.value
+            })
+        }
+    }
+
+    /// Retrieves a value, still returning it for a while after it expires.
+    ///
+    /// Returns [`GetResult::Stale`] for an entry that expired less than the
+    /// [stale grace period](Self::set_stale_grace) ago, so callers can serve the
+    /// old value while refreshing it, e.g. by inserting the new one. Entries
+    /// expired past the grace period are removed and reported as
+    /// [`GetResult::Miss`]. With no grace period this behaves like
+    /// [`get`](Self::get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, GetResult};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    /// use std::thread;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_stale_grace(Duration::from_secs(60));
+    /// cache.insert_with_ttl("price", 100, Duration::from_millis(10));
+    /// assert_eq!(cache.get_stale("price"), GetResult::Fresh(&100.to_value()));
+    ///
+    /// thread::sleep(Duration::from_millis(20));
+    /// assert_eq!(cache.get("price"), None);
+    /// assert_eq!(cache.get_stale("price"), GetResult::Stale(&100.to_value()));
+    /// assert_eq!(cache.get_stale("missing"), GetResult::Miss);
+    /// ```
+    pub fn get_stale(&mut self, key: &str) -> GetResult<'_> {
+        let key = self.normalize_key(key).into_owned();
+        let now = self.now();
+        let (expired, past_grace) = match self.map.get(&key) {
+            Some(item) => (item.is_expired_at(now), self.is_past_grace(item, now)),
+            None => return GetResult::Miss,
+        };
+
+        if past_grace {
+            self.remove(&key).ok();
+            return GetResult::Miss;
+        }
+
+        let track_access_counts = self.track_access_counts;
+        match self.map.get_mut(&key) {
+            Some(item) if expired => GetResult::Stale(&item.value),
+            Some(item) => {
+                item.touch(track_access_counts);
+                GetResult::Fresh(&item.value)
+            }
+            None => GetResult::Miss,
+        }
+    }
+
+    /// Returns `true` once `item` expired longer than the stale grace period ago
+    #[inline]
+    fn is_past_grace(&self, item: &CacheItem, now: u64) -> bool {
+        item.is_expired_at(now.saturating_sub(self.stale_grace.as_millis() as u64))
+    }
+
+    /// Inserts the pair only if `key` is missing or expired.
+    ///
+    /// Returns whether the value was inserted. Insertion follows the same rules
+    /// as [`insert`](Self::insert), including the default TTL, eviction and the
+    /// insert event; nothing happens (and no event fires) when the key is live
+    /// or the cache has capacity 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert!(cache.insert_if_absent("config", "initial"));
+    /// assert!(!cache.insert_if_absent("config", "ignored"));
+    /// assert_eq!(cache.get("config"), Some(&"initial".to_value()));
+    /// ```
+    pub fn insert_if_absent<T, V>(&mut self, key: T, value: V) -> bool
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+    {
+        let key = self.normalize_owned_key(key.into());
+
+        if self.capacity == 0 || self.contains_key(&key) {
+            return false;
+        }
+
+        self.insert(key.clone(), value);
+        self.map.contains_key(&key)
+    }
+
+    /// Replaces the value of a live entry while keeping its creation time and TTL.
+    ///
+    /// Unlike [`insert`](Self::insert), which starts a fresh TTL, the entry still
+    /// expires at its original deadline. Returns `false` (and inserts nothing) if
+    /// `key` is missing or expired, or if the insert validator rejects the value.
+    /// An insert event is sent when the value changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("page", "v1", Duration::from_secs(60));
+    ///
+    /// assert!(cache.set_value("page", "v2"));
+    /// assert_eq!(cache.get("page"), Some(&"v2".to_value()));
+    /// assert_eq!(cache.get_ttl("page"), Some(Duration::from_secs(60)));
+    ///
+    /// assert!(!cache.set_value("missing", "v1"));
+    /// ```
+    pub fn set_value<V>(&mut self, key: &str, value: V) -> bool
+    where
+        V: ToValueBehavior,
+    {
+        self.try_set_value(key, value).unwrap_or(false)
+    }
+
+    /// Replaces the value of a live entry like [`set_value`](Self::set_value),
+    /// returning an error if the insert validator rejects it.
+    ///
+    /// Returns `Ok(false)` if `key` is missing or expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Rejected`] with the validator's reason. The entry keeps
+    /// its current value and nothing is sent or persisted in that case.
+    pub fn try_set_value<V>(&mut self, key: &str, value: V) -> Result<bool, Error>
+    where
+        V: ToValueBehavior,
+    {
+        let key = self.normalize_key(key).into_owned();
+        if !self.contains_key(&key) {
+            return Ok(false);
+        }
+
+        let value = self.transform_value(value);
+        self.validate_insert(&key, &value)?;
+        let now = self.now();
+        let item = match self.map.get_mut(&key) {
+            Some(item) if item.value != value => item,
+            _ => return Ok(true),
+        };
+        item.value = value.clone();
+        item.modified_at = now;
+
+        self.send_insert(key, value);
+
+        Ok(true)
+    }
+
+    /// Returns the value for `key`, inserting the one produced by `f` on a miss.
+    ///
+    /// `f` is only called if the key is missing or expired. The inserted item
+    /// follows the same rules as [`insert`](Self::insert), including the default
+    /// TTL and eviction. A cached value is returned as `Cow::Borrowed`. A value
+    /// that can't be stored, because the cache has capacity 0, every entry is
+    /// [pinned](Self::pin) or the insert validator rejects it, is returned as
+    /// `Cow::Owned` without being cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert_eq!(*cache.get_or_insert_with("user:1", || "Alice"), "Alice".to_value());
+    /// assert_eq!(*cache.get_or_insert_with("user:1", || "Bob"), "Alice".to_value());
+    /// ```
+    pub fn get_or_insert_with<T, V, F>(&mut self, key: T, f: F) -> Cow<'_, Value>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+        F: FnOnce() -> V,
+    {
+        match self.get_or_try_insert_with(key, || Ok::<V, Infallible>(f())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Returns the value for `key`, inserting the one produced by the fallible
+    /// loader `f` on a miss.
+    ///
+    /// `f` is only called if the key is missing or expired. If it fails, nothing
+    /// is inserted and its error is returned. A value that can't be stored is
+    /// returned as `Cow::Owned` without being cached, as with
+    /// [`get_or_insert_with`](Self::get_or_insert_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// let result = cache.get_or_try_insert_with("user:1", || Err::<&str, _>("db unavailable"));
+    /// assert_eq!(result, Err("db unavailable"));
+    /// assert!(!cache.contains_key("user:1"));
+    ///
+    /// let result = cache.get_or_try_insert_with("user:1", || Ok::<_, &str>("Alice"));
+    /// assert_eq!(result.map(|value| value.into_owned()), Ok("Alice".to_value()));
+    /// ```
+    pub fn get_or_try_insert_with<T, V, F, E>(&mut self, key: T, f: F) -> Result<Cow<'_, Value>, E>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+        F: FnOnce() -> Result<V, E>,
+    {
+        self.get_or_try_insert_with_opt_ttl(key, || f().map(|value| (value, None)))
+    }
+
+    /// Returns the value for `key`, inserting the value produced by `f` with the
+    /// TTL it returns on a miss.
+    ///
+    /// Useful when freshness depends on what was loaded, e.g. an API response
+    /// with its own cache-control. `f` is only called if the key is missing or
+    /// expired. A `None` TTL falls back to the default TTL, or no TTL, like
+    /// [`insert_opt_ttl`](Self::insert_opt_ttl). A value that can't be stored is
+    /// returned as `Cow::Owned` without being cached, as with
+    /// [`get_or_insert_with`](Self::get_or_insert_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// let value = cache.get_or_insert_with_dyn_ttl("user:1", || {
+    ///     ("Alice", Some(Duration::from_secs(30)))
+    /// });
+    /// assert_eq!(*value, "Alice".to_value());
+    /// assert_eq!(cache.get_ttl("user:1"), Some(Duration::from_secs(30)));
+    /// ```
+    pub fn get_or_insert_with_dyn_ttl<T, V, F>(&mut self, key: T, f: F) -> Cow<'_, Value>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+        F: FnOnce() -> (V, Option<Duration>),
+    {
+        match self.get_or_try_insert_with_opt_ttl(key, || Ok::<_, Infallible>(f())) {
+            Ok(value) => value,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Shared body of the `get_or_*insert_with*` loaders: on a miss, inserts the
+    /// value produced by `f` with the TTL it returns (see
+    /// [`insert_opt_ttl`](Self::insert_opt_ttl))
+    fn get_or_try_insert_with_opt_ttl<T, V, F, E>(
+        &mut self,
+        key: T,
+        f: F,
+    ) -> Result<Cow<'_, Value>, E>
+    where
+        T: Into<String>,
+        V: ToValueBehavior,
+        F: FnOnce() -> Result<(V, Option<Duration>), E>,
+    {
+        let key = self.normalize_owned_key(key.into());
+        let hit = self.contains_key(&key);
+
+        if !hit {
+            let (value, ttl) = f()?;
+            let value = self.transform_value(value);
+            if self.capacity == 0
+                || self.validate_insert(&key, &value).is_err()
+                || !self.make_room_for(&key)
+            {
+                return Ok(Cow::Owned(value));
+            }
+            self.insert_validated(key.clone(), value, ttl);
+        }
+
+        let track_access_counts = self.track_access_counts;
+        let item = self
+            .map
+            .get_mut(&key)
+            .expect("key is present after lookup or insert");
+        if hit {
+            item.touch(track_access_counts);
+        }
+
+        Ok(Cow::Borrowed(&item.value))
+    }
+
+    /// Returns a mutable reference to the value for `key`, inserting
+    /// `Value::Null` on a miss.
+    ///
+    /// The inserted item follows the same rules as [`insert`](Self::insert),
+    /// including the default TTL and eviction, except that the `Value::Null`
+    /// placeholder skips the value transform and the insert validator: the real
+    /// value is written through the returned reference. As with
+    /// [`get_mut`](Self::get_mut), changes made through the reference send no
+    /// events. Returns `None` if the placeholder can't be stored because the
+    /// cache has capacity 0 or every entry is [pinned](Self::pin).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Value};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// let value = cache.get_or_insert_default("greeting").unwrap();
+    /// assert_eq!(value, &Value::Null);
+    /// *value = "hello".to_value();
+    ///
+    /// assert_eq!(cache.get_or_insert_default("greeting"), Some(&mut "hello".to_value()));
+    /// ```
+    pub fn get_or_insert_default<T>(&mut self, key: T) -> Option<&mut Value>
+    where
+        T: Into<String>,
+    {
+        let key = self.normalize_owned_key(key.into());
+        let hit = self.contains_key(&key);
+
+        if !hit && (self.capacity == 0 || !self.insert_validated(key.clone(), Value::Null, None)) {
+            return None;
+        }
+
+        let track_access_counts = self.track_access_counts;
+        let item = self
+            .map
+            .get_mut(&key)
+            .expect("key is present after lookup or insert");
+        if hit {
+            item.touch(track_access_counts);
+        }
+
+        Some(&mut item.value)
+    }
+
+    /// Retrieves a value converted to `T`.
+    ///
+    /// Returns `None` if the key doesn't exist or has expired, and
+    /// `Some(Err(Error::TypeMismatch))` if the stored value can't be converted.
+    /// Numbers convert to any width they fit, so the `i32` stored by
+    /// `insert(key, 42)` reads as an `i64` or a `u8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("count", 42);
+    ///
+    /// assert_eq!(cache.try_get::<i64>("count"), Some(Ok(42)));
+    /// assert_eq!(cache.try_get::<String>("count"), Some(Err(Error::TypeMismatch)));
+    /// assert_eq!(cache.try_get::<i64>("missing"), None);
+    /// ```
+    pub fn try_get<T>(&mut self, key: &str) -> Option<Result<T, Error>>
+    where
+        T: FromValueBehavior<Item = T>,
+    {
+        let value = self.get(key)?.clone();
+        let converted = match T::from_value(value.clone()) {
+            Some(converted) => Some(converted),
+            None => numeric_widths(&value).into_iter().find_map(T::from_value),
+        };
+        Some(converted.ok_or(Error::TypeMismatch))
+    }
+
+    /// Retrieves a value converted to `T`, treating a type mismatch as a miss.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("name", "Alice");
+    ///
+    /// assert_eq!(cache.get_as::<String>("name"), Some("Alice".to_string()));
+    /// assert_eq!(cache.get_as::<i64>("name"), None);
+    /// ```
+    pub fn get_as<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: FromValueBehavior<Item = T>,
+    {
+        self.try_get(key)?.ok()
+    }
+
+    /// Retrieves a string value as `&str`, treating any other type as a miss.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_str("name", "Alice");
+    /// cache.insert("age", 30);
+    ///
+    /// assert_eq!(cache.get_str("name"), Some("Alice"));
+    /// assert_eq!(cache.get_str("age"), None);
+    /// ```
+    pub fn get_str(&mut self, key: &str) -> Option<&str> {
+        match self.get(key)? {
+            Value::String(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Adds `by` to the integer stored at `key` and returns the new value.
+    ///
+    /// A missing or expired key counts as `0` and is created like
+    /// [`insert`](Self::insert) does, so it gets the default TTL. An existing
+    /// counter keeps its original expiry, as with [`set_value`](Self::set_value),
+    /// which makes fixed-window rate limiting straightforward. Integers of any
+    /// width are read; the sum saturates at the `i64` bounds and is stored as an
+    /// `i64`. A stored value that is not an integer is left untouched and
+    /// `Error::TypeMismatch` is returned, and a sum the insert validator rejects
+    /// is not stored and `Error::Rejected` is returned. A cache with capacity 0
+    /// stores nothing, so the result is returned without being kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert_eq!(cache.increment("hits", 1), Ok(1));
+    /// assert_eq!(cache.increment("hits", 5), Ok(6));
+    /// assert_eq!(cache.decrement("hits", 2), Ok(4));
+    ///
+    /// cache.insert("name", "Alice");
+    /// assert_eq!(cache.increment("name", 1), Err(Error::TypeMismatch));
+    /// ```
+    pub fn increment(&mut self, key: &str, by: i64) -> Result<i64, Error> {
+        // Read through `Number` so integers stored at any width (e.g. the `i32`
+        // from `insert(key, 5)`) count, not just exact `i64`s
+        let current = self
+            .get(key)
+            .map(|value| value.to_i64().ok_or(Error::TypeMismatch));
+
+        match current {
+            Some(current) => {
+                let next = current?.saturating_add(by);
+                self.try_set_value(key, next)?;
+                Ok(next)
+            }
+            None => {
+                self.try_insert(key, by)?;
+                Ok(by)
+            }
+        }
+    }
+
+    /// Subtracts `by` from the integer stored at `key` and returns the new value.
+    ///
+    /// See [`increment`](Self::increment) for how missing keys, TTLs,
+    /// non-integer values and rejected results are handled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert_eq!(cache.decrement("stock", 3), Ok(-3));
+    /// assert_eq!(cache.get_as::<i64>("stock"), Some(-3));
+    /// ```
+    pub fn decrement(&mut self, key: &str, by: i64) -> Result<i64, Error> {
+        self.increment(key, by.saturating_neg())
+    }
+
+    /// Combines `value` with the live value stored at `key`, storing
+    /// `combine(existing, value)`.
+    ///
+    /// A missing or expired key is set to `value` like [`insert`](Self::insert)
+    /// does. A merged entry keeps its original expiry, as with
+    /// [`set_value`](Self::set_value). Either way an insert event is sent with
+    /// the stored value. A result the insert validator rejects is dropped, as
+    /// with `insert`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Value};
+    /// use quickleaf::valu3::prelude::NumberBehavior;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let sum = |a: &Value, b: &Value| (a.to_i64().unwrap_or(0) + b.to_i64().unwrap_or(0)).to_value();
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.merge_value("requests", 3, sum);
+    /// cache.merge_value("requests", 4, sum);
+    /// assert_eq!(cache.get_as::<i64>("requests"), Some(7));
+    /// ```
+    pub fn merge_value<V, F>(&mut self, key: &str, value: V, combine: F)
+    where
+        V: ToValueBehavior,
+        F: FnOnce(&Value, &Value) -> Value,
+    {
+        let value = value.to_value();
+        match self.get(key) {
+            Some(existing) => {
+                let merged = combine(existing, &value);
+                self.set_value(key, merged);
+            }
+            None => self.insert(key, value),
+        }
+    }
+
+    /// Appends `item` to the array stored at `key` and returns the new length.
+    ///
+    /// A missing or expired key starts as an empty array and is created like
+    /// [`insert`](Self::insert) does, so it gets the default TTL. An existing
+    /// array keeps its original expiry, as with [`set_value`](Self::set_value).
+    /// A stored value that is not an array is left untouched and
+    /// `Error::TypeMismatch` is returned. An array the insert validator rejects
+    /// is not stored and `Error::Rejected` is returned. A cache with capacity 0
+    /// stores nothing, so the length is returned without the array being kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert_eq!(cache.list_push("log", "started"), Ok(1));
+    /// assert_eq!(cache.list_push("log", "stopped"), Ok(2));
+    /// assert_eq!(cache.get("log"), Some(&vec!["started", "stopped"].to_value()));
+    /// ```
+    pub fn list_push<V>(&mut self, key: &str, item: V) -> Result<usize, Error>
+    where
+        V: ToValueBehavior,
+    {
+        let item = item.to_value();
+        self.update_array(key, |values| {
+            values.push(item);
+            values.len()
+        })
+    }
+
+    /// Adds `item` to the array stored at `key` unless it is already present.
+    ///
+    /// Returns whether the item was added. Missing keys, TTLs, non-array values
+    /// and rejected arrays are handled as in [`list_push`](Self::list_push).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// assert_eq!(cache.set_add("tags", "rust"), Ok(true));
+    /// assert_eq!(cache.set_add("tags", "cache"), Ok(true));
+    /// assert_eq!(cache.set_add("tags", "rust"), Ok(false));
+    /// assert_eq!(cache.get("tags"), Some(&vec!["rust", "cache"].to_value()));
+    /// ```
+    pub fn set_add<V>(&mut self, key: &str, item: V) -> Result<bool, Error>
+    where
+        V: ToValueBehavior,
+    {
+        let item = item.to_value();
+        self.update_array(key, |values| {
+            if values.contains(&item) {
+                false
+            } else {
+                values.push(item);
+                true
+            }
+        })
+    }
+
+    /// Applies `f` to the array stored at `key`, creating an empty one if missing.
+    fn update_array<F, R>(&mut self, key: &str, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut Vec<Value>) -> R,
+    {
+        match self.get(key) {
+            Some(Value::Array(array)) => {
+                let mut values = array.values.clone();
+                let result = f(&mut values);
+                self.try_set_value(key, values)?;
+                Ok(result)
+            }
+            Some(_) => Err(Error::TypeMismatch),
+            None => {
+                let mut values = Vec::new();
+                let result = f(&mut values);
+                self.try_insert(key, values)?;
+                Ok(result)
+            }
+        }
+    }
+
+    /// Retrieves the stored key together with its value.
+    ///
+    /// Mirrors [`HashMap::get_key_value`](std::collections::HashMap::get_key_value):
+    /// the returned key is the owned `Key` held by the cache. Expired items are
+    /// removed and reported as missing, just like [`get`](Self::get).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:1", "Alice");
+    ///
+    /// let (key, value) = cache.get_key_value("user:1").unwrap();
+    /// assert_eq!(key, "user:1");
+    /// assert_eq!(value, &"Alice".to_value());
+    /// assert_eq!(cache.get_key_value("user:2"), None);
+    /// ```
+    pub fn get_key_value(&mut self, key: &str) -> Option<(&Key, &Value)> {
+        let key = self.normalize_key(key);
+        let key = key.as_ref();
+        self.get(key)?;
+        self.map
+            .get_key_value(key)
+            .map(|(key, item)| (key, &item.value))
+    }
+
+    /// Returns when the entry for `key` was created (or last replaced by an insert).
+    ///
+    /// Returns `None` for missing and expired keys. Unlike [`get`](Self::get), this
+    /// does not remove expired entries or count as an access.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::SystemTime;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("key", "value");
+    ///
+    /// assert!(cache.get_created_at("key").unwrap() <= SystemTime::now());
+    /// assert_eq!(cache.get_created_at("missing"), None);
+    /// ```
+    pub fn get_created_at(&self, key: &str) -> Option<SystemTime> {
+        self.live_item(key).map(CacheItem::created_at_time)
+    }
+
+    /// Returns when the value for `key` last changed, e.g. for `Last-Modified`
+    /// headers.
+    ///
+    /// Unlike [`get_created_at`](Self::get_created_at), this also advances when
+    /// the value is updated in place by [`set_value`](Self::set_value) or
+    /// [`map_values`](Self::map_values). Changes made through
+    /// [`get_mut`](Self::get_mut) are not tracked. Returns `None` for missing and
+    /// expired keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("page", "v1");
+    /// cache.set_value("page", "v2");
+    ///
+    /// assert!(cache.get_modified_at("page").unwrap() >= cache.get_created_at("page").unwrap());
+    /// ```
+    pub fn get_modified_at(&self, key: &str) -> Option<SystemTime> {
+        self.live_item(key).map(CacheItem::modified_at_time)
+    }
+
+    /// Returns when the cache last changed: an insert, update, removal,
+    /// eviction, expiry cleanup or clear. `None` if it never changed.
+    ///
+    /// Reads don't count, so this can back `If-Modified-Since` style checks for
+    /// the whole cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// assert_eq!(cache.last_modified(), None);
+    ///
+    /// cache.insert("key", "value");
+    /// let modified = cache.last_modified().unwrap();
+    ///
+    /// cache.get("key");
+    /// assert_eq!(cache.last_modified(), Some(modified));
+    /// ```
+    pub fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+            .map(|millis| std::time::UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    /// Returns the TTL of the entry for `key`.
+    ///
+    /// Returns `None` both for missing or expired keys and for entries without a
+    /// TTL; use [`get_created_at`](Self::get_created_at) to tell them apart. Like
+    /// `get_created_at`, this does not remove expired entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("session", "data", Duration::from_secs(60));
+    /// cache.insert("permanent", "data");
+    ///
+    /// assert_eq!(cache.get_ttl("session"), Some(Duration::from_secs(60)));
+    /// assert_eq!(cache.get_ttl("permanent"), None);
+    /// assert!(cache.get_created_at("permanent").is_some());
+    /// ```
+    pub fn get_ttl(&self, key: &str) -> Option<Duration> {
+        self.live_item(key).and_then(CacheItem::ttl)
+    }
+
+    /// Returns the stored item for `key`, with all of its metadata.
+    ///
+    /// Unlike [`get`](Self::get), this never removes anything and also returns
+    /// expired items that haven't been cleaned up yet; check
+    /// [`CacheItem::is_expired`] (or [`CacheItem::is_expired_at`] with a custom
+    /// [clock](Self::set_clock)) yourself. Reading an item this way doesn't count
+    /// as an access for eviction or [`hot_keys`](Self::hot_keys).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("session", "alice", Duration::from_secs(60));
+    ///
+    /// let item = cache.get_item("session").unwrap();
+    /// assert_eq!(item.value, "alice".to_value());
+    /// assert_eq!(item.ttl(), Some(Duration::from_secs(60)));
+    /// assert!(!item.is_expired());
+    /// ```
+    pub fn get_item(&self, key: &str) -> Option<&CacheItem> {
+        self.map.get(self.normalize_key(key).as_ref())
+    }
+
+    /// Looks up a live entry without removing it when expired.
+    fn live_item(&self, key: &str) -> Option<&CacheItem> {
+        let key = self.normalize_key(key);
+        self.map
+            .get(key.as_ref())
+            .filter(|item| !item.is_expired_at(self.now()))
+    }
+
+    /// Fetches the requested keys and returns owned pairs sorted by key.
+    ///
+    /// Missing and expired keys are omitted. Results carry the stored (normalized)
+    /// key, so keys that normalize to the same entry are returned once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Order};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("b", 2);
+    /// cache.insert("a", 1);
+    /// cache.insert("c", 3);
+    ///
+    /// let results = cache.get_batch_ordered(&["c", "missing", "a"], Order::Asc);
+    /// assert_eq!(
+    ///     results,
+    ///     vec![("a".to_string(), 1.to_value()), ("c".to_string(), 3.to_value())]
+    /// );
+    /// ```
+    pub fn get_batch_ordered(&mut self, keys: &[&str], order: Order) -> Vec<(Key, Value)> {
+        let mut results = Vec::with_capacity(keys.len());
+
+        for key in keys {
+            if let Some((key, value)) = self.get_key_value(key) {
+                results.push((key.clone(), value.clone()));
+            }
+        }
+
+        results.sort_by(|(a, _), (b, _)| a.cmp(b));
+        results.dedup_by(|(a, _), (b, _)| a == b);
+
+        if let Order::Desc = order {
+            results.reverse();
+        }
+
+        results
+    }
+
+    /// Inserts a value under a compound key.
+    ///
+    /// The segments are joined with the key separator (`:` by default). Any
+    /// separator or `\` inside a segment is escaped with a `\`, so different
+    /// segment lists never map to the same key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_tuple(("user", "42"), "alice");
+    /// cache.insert_tuple(["user", "a:b"], "escaped");
+    ///
+    /// assert_eq!(cache.get("user:42"), Some(&"alice".to_value()));
+    /// assert_eq!(cache.get_tuple(("user", "a:b")), Some(&"escaped".to_value()));
+    /// assert_eq!(cache.get_tuple(("user", "a", "b")), None);
+    /// ```
+    pub fn insert_tuple<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<CompoundKey>,
+        V: ToValueBehavior,
+    {
+        let key = self.compound_key(key);
+        self.insert(key, value);
+    }
+
+    /// Retrieves a value stored under a compound key.
+    ///
+    /// See [`insert_tuple`](Self::insert_tuple) for how segments are joined.
+    pub fn get_tuple<K>(&mut self, key: K) -> Option<&Value>
+    where
+        K: Into<CompoundKey>,
+    {
+        let key = self.compound_key(key);
+        self.get(&key)
+    }
+
+    /// Builds the key string for a compound key using this cache's separator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let cache = Cache::new(10);
+    /// assert_eq!(cache.compound_key(("user", "42")), "user:42");
+    /// assert_eq!(cache.compound_key(["a:b", "c"]), "a\\:b:c");
+    /// ```
+    pub fn compound_key<K>(&self, key: K) -> Key
+    where
+        K: Into<CompoundKey>,
+    {
+        key.into().join(self.key_separator)
+    }
+
+    /// Splits a key into its segments, undoing the escaping applied by
+    /// [`compound_key`](Self::compound_key).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let cache = Cache::new(10);
+    /// let key = cache.compound_key(["org", "a:b", "team"]);
+    /// assert_eq!(cache.split_key(&key), vec!["org", "a:b", "team"]);
+    /// ```
+    pub fn split_key(&self, key: &str) -> Vec<String> {
+        compound_key::split_key(key, self.key_separator)
+    }
+
+    /// Sets the separator used to join compound key segments.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `separator` is `\`, which is reserved for escaping.
+    #[inline]
+    pub fn set_key_separator(&mut self, separator: char) {
+        assert_ne!(
+            separator, KEY_ESCAPE,
+            "the escape character cannot be used as key separator"
+        );
+        self.key_separator = separator;
+    }
+
+    #[inline(always)]
+    pub fn get_key_separator(&self) -> char {
+        self.key_separator
+    }
+
+    /// Returns the stored keys in insertion order (oldest first).
+    ///
+    /// Updating an existing key keeps its position; removing a key keeps the
+    /// relative order of the others. This is the order `EvictionPolicy::Lru`
+    /// evicts in. For keys sorted by name use [`list`](Self::list) instead.
+    /// Unlike [`get_map`](Self::get_map), expired items that haven't been
+    /// cleaned up yet are included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("b", 1);
+    /// cache.insert("c", 2);
+    /// cache.insert("a", 3);
+    /// cache.remove("b").unwrap();
+    ///
+    /// assert_eq!(cache.get_list(), vec!["c", "a"]);
+    /// ```
+    #[inline(always)]
+    pub fn get_list(&self) -> Vec<&Key> {
+        self.map.keys().collect()
+    }
+
+    /// Returns the live entries in insertion order (oldest first), like
+    /// [`get_list`](Self::get_list). Expired items are skipped.
+    pub fn get_map(&self) -> IndexMap<Key, &Value> {
+        let now = self.now();
+        self.map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key.clone(), &item.value))
+            .collect()
+    }
+
+    /// Returns the first live entry whose value matches `pred`.
+    ///
+    /// Entries are scanned in insertion order (oldest first), like
+    /// [`get_list`](Self::get_list). Expired items are skipped but not removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("session:1", "token-a");
+    /// cache.insert("session:2", "token-b");
+    ///
+    /// let found = cache.find(|value| *value == "token-b".to_value());
+    /// assert_eq!(found.map(|(key, _)| key.as_str()), Some("session:2"));
+    /// ```
+    pub fn find<F>(&self, pred: F) -> Option<(&Key, &Value)>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let now = self.now();
+        self.map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key, &item.value))
+            .find(|(_, value)| pred(value))
+    }
+
+    /// Returns every live entry whose value matches `pred`, in insertion order.
+    ///
+    /// See [`find`](Self::find).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::valu3::prelude::NumberBehavior;
+    /// use quickleaf::{Cache, Value};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 5);
+    /// cache.insert("b", 50);
+    /// cache.insert("c", 20);
+    ///
+    /// let at_least_20 = |value: &Value| value.to_i64().is_some_and(|n| n >= 20);
+    /// let large = cache.find_all(at_least_20);
+    /// let keys: Vec<_> = large.iter().map(|(key, _)| key.as_str()).collect();
+    /// assert_eq!(keys, vec!["b", "c"]);
+    /// ```
+    pub fn find_all<F>(&self, pred: F) -> Vec<(&Key, &Value)>
+    where
+        F: Fn(&Value) -> bool,
+    {
+        let now = self.now();
+        self.map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key, &item.value))
+            .filter(|(_, value)| pred(value))
+            .collect()
+    }
+
+    /// Returns every live entry whose key starts with `prefix`, sorted by key.
+    ///
+    /// Shorthand for listing with [`Filter::StartWith`] and no limit. Uses the
+    /// sorted key index when it is up to date, so only matching keys are visited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:123:name", "alice");
+    /// cache.insert("user:123:email", "alice@example.com");
+    /// cache.insert("user:456:name", "bob");
+    ///
+    /// let user = cache.get_by_prefix("user:123:");
+    /// assert_eq!(
+    ///     user,
+    ///     vec![
+    ///         ("user:123:email".to_string(), &"alice@example.com".to_value()),
+    ///         ("user:123:name".to_string(), &"alice".to_value()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn get_by_prefix(&self, prefix: &str) -> Vec<(Key, &Value)> {
+        let matching: Vec<(&Key, &CacheItem)> = if self.sorted_keys_dirty {
+            let mut matching: Vec<_> = self
+                .map
+                .iter()
+                .filter(|(key, _)| fast_prefix_match(key, prefix))
+                .collect();
+            matching.sort_unstable_by_key(|(key, _)| *key);
+            matching
+        } else {
+            let start = self
+                .sorted_keys
+                .partition_point(|key| key.as_str() < prefix);
+            self.sorted_keys[start..]
+                .iter()
+                .take_while(|key| fast_prefix_match(key, prefix))
+                .filter_map(|key| self.map.get_key_value(key))
+                .collect()
+        };
+
+        let now = self.now();
+        matching
+            .into_iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key.clone(), &item.value))
+            .collect()
+    }
+
+    /// Compares the live entries of this cache with `other`, e.g. to check that a
+    /// replica or a cache restored from persistence is in sync.
+    ///
+    /// Only keys and values are compared; TTLs and insertion order are ignored.
+    /// Expired entries count as missing but are not removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut primary = Cache::new(10);
+    /// primary.insert("a", 1);
+    /// primary.insert("b", 2);
+    ///
+    /// let mut replica = Cache::new(10);
+    /// replica.insert("b", 20);
+    /// replica.insert("c", 3);
+    ///
+    /// let diff = primary.diff(&replica);
+    /// assert_eq!(diff.only_self, vec!["a"]);
+    /// assert_eq!(diff.only_other, vec!["c"]);
+    /// assert_eq!(diff.value_mismatch, vec!["b"]);
+    /// ```
+    pub fn diff<S2: BuildHasher>(&self, other: &Cache<S2>) -> CacheDiff {
+        let now = self.now();
+        let other_now = other.now();
+        let mut diff = CacheDiff::default();
+
+        for (key, item) in &self.map {
+            if item.is_expired_at(now) {
+                continue;
+            }
+            match other
+                .map
+                .get(key)
+                .filter(|other_item| !other_item.is_expired_at(other_now))
+            {
+                Some(other_item) if other_item.value != item.value => {
+                    diff.value_mismatch.push(key.clone())
+                }
+                Some(_) => {}
+                None => diff.only_self.push(key.clone()),
+            }
+        }
+
+        for (key, item) in &other.map {
+            if item.is_expired_at(other_now) {
+                continue;
+            }
+            let in_self = self.map.get(key).is_some_and(|own| !own.is_expired_at(now));
+            if !in_self {
+                diff.only_other.push(key.clone());
+            }
+        }
+
+        diff.only_self.sort_unstable();
+        diff.only_other.sort_unstable();
+        diff.value_mismatch.sort_unstable();
+        diff
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        let key = self.normalize_key(key);
+        let key = key.as_ref();
+        let now = self.now();
+        let is_expired = match self.map.get(key) {
+            Some(item) => item.is_expired_at(now),
+            None => return None,
+        };
+
+        if is_expired {
+            if self
+                .map
+                .get(key)
+                .is_some_and(|item| self.is_past_grace(item, now))
+            {
+                self.remove(key).ok();
+            }
+            None
+        } else {
+            let track_access_counts = self.track_access_counts;
+            self.map.get_mut(key).map(|item| {
+                item.touch(track_access_counts);
+                &mut item.value
+            })
+        }
+    }
+
+    /// Returns mutable references to the values of several keys at once, in the
+    /// order requested.
+    ///
+    /// Each position is `None` if its key is missing or expired, and expired
+    /// entries are removed as with [`get`](Self::get). A key requested more than
+    /// once is `None` at every position, since it can't be borrowed mutably twice.
+    /// As with [`get_mut`](Self::get_mut), changes send no events.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    ///
+    /// if let [Some(a), Some(b), None] = &mut cache.get_many_mut(&["a", "b", "c"])[..] {
+    ///     std::mem::swap(*a, *b);
+    /// }
+    ///
+    /// assert_eq!(cache.get("a"), Some(&2.to_value()));
+    /// assert_eq!(cache.get("b"), Some(&1.to_value()));
+    /// ```
+    pub fn get_many_mut(&mut self, keys: &[&str]) -> Vec<Option<&mut Value>> {
+        let keys: Vec<Key> = keys
+            .iter()
+            .map(|key| self.normalize_key(key).into_owned())
+            .collect();
+        // Remove expired entries first, since that shifts the indices of later ones
+        for key in &keys {
+            self.contains_key(key);
+        }
+
+        // (position in `keys`, index in the map) of every live key; expired
+        // entries still within the stale grace period were kept above
+        let now = self.now();
+        let mut found: Vec<(usize, usize)> = keys
+            .iter()
+            .enumerate()
+            .filter_map(|(position, key)| {
+                self.map
+                    .get_full(key.as_str())
+                    .filter(|(_, _, item)| !item.is_expired_at(now))
+                    .map(|(index, _, _)| (position, index))
+            })
+            .collect();
+
+        found.sort_unstable_by_key(|&(_, index)| index);
+        let duplicates: Vec<usize> = found
+            .windows(2)
+            .filter(|pair| pair[0].1 == pair[1].1)
+            .map(|pair| pair[0].1)
+            .collect();
+        found.retain(|(_, index)| !duplicates.contains(index));
+
+        let mut values: Vec<Option<&mut Value>> = keys.iter().map(|_| None).collect();
+        let track_access_counts = self.track_access_counts;
+        let mut rest = self.map.as_mut_slice();
+        let mut offset = 0;
+        // Walk the map in index order, splitting off each requested entry
+        for (position, index) in found {
+            let (_, tail) = std::mem::take(&mut rest).split_at_mut(index - offset);
+            let ((_, item), tail) = tail.split_first_mut().expect("index is in bounds");
+            item.touch(track_access_counts);
+            values[position] = Some(&mut item.value);
+            rest = tail;
+            offset = index + 1;
+        }
+        values
+    }
+
+    /// Returns the maximum number of entries, the threshold at which inserts evict.
+    ///
+    /// This is the logical limit, not the number of allocated slots; see
+    /// [`allocated_capacity`](Self::allocated_capacity).
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of entries.
+    ///
+    /// Existing entries are kept even if there are more than `capacity`; the next
+    /// inserts evict down to the new limit. Allocated memory is not changed.
+    #[inline]
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    /// Evicts entries until at most `target` remain and returns them in eviction
+    /// order, e.g. to reclaim memory under pressure and process what was dropped.
+    ///
+    /// Victims are picked by the [eviction policy](Self::set_eviction_policy), so
+    /// the oldest entries go first under [`EvictionPolicy::Lru`]. Each eviction
+    /// runs the [`on_evict`](Self::on_evict) callback and sends a remove event,
+    /// like an eviction on insert. Pinned entries are never evicted, so more than
+    /// `target` entries remain if they are pinned. Expired entries count toward
+    /// the total. [`capacity`](Self::capacity) is not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    /// cache.insert("c", 3);
+    ///
+    /// assert_eq!(
+    ///     cache.shrink_to(1),
+    ///     vec![("a".to_string(), 1.to_value()), ("b".to_string(), 2.to_value())]
+    /// );
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn shrink_to(&mut self, target: usize) -> Vec<(Key, Value)> {
+        let excess = self.map.len().saturating_sub(target);
+
+        self.evict(excess)
+            .into_iter()
+            .map(|(key, item)| {
+                log_debug!("Evicted key '{}' to shrink to {} entries", key, target);
+                self.call_on_evict(&key, &item.value);
+                self.send_remove(key.clone(), item.value.clone());
+                (key, item.value)
+            })
+            .collect()
+    }
+
+    /// Returns how many entries fit without reallocating.
+    ///
+    /// Constructors allocate room for `capacity` entries, unless built with
+    /// [`CacheBuilder::preallocate`](crate::CacheBuilder::preallocate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let cache = Cache::builder(1_000_000).preallocate(64).build();
+    /// assert_eq!(cache.capacity(), 1_000_000);
+    /// assert!(cache.allocated_capacity() < 1_000_000);
+    /// ```
+    #[inline]
+    pub fn allocated_capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Allocates room for at least `additional` more entries, like
+    /// [`HashMap::reserve`](std::collections::HashMap::reserve).
+    ///
+    /// Does not change [`capacity`](Self::capacity), so eviction still happens at
+    /// the same number of entries.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Like [`reserve`](Self::reserve), but returns [`Error::AllocationFailed`]
+    /// instead of aborting the process when the allocation fails or `additional`
+    /// overflows.
+    ///
+    /// Lets servers in memory-constrained environments degrade gracefully. The
+    /// cache is unchanged on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.try_reserve(100).unwrap();
+    /// assert!(cache.allocated_capacity() >= 100);
+    ///
+    /// assert_eq!(cache.try_reserve(usize::MAX), Err(Error::AllocationFailed));
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), Error> {
+        self.map.try_reserve(additional).map_err(|e| {
+            log_debug!("Failed to reserve {} entries: {}", additional, e);
+            Error::AllocationFailed
+        })
+    }
+
+    pub fn remove(&mut self, key: &str) -> Result<(), Error> {
+        let key = self.normalize_key(key);
+        let key = key.as_ref();
+        if let Some(item) = self.map.shift_remove(key) {
+            self.sorted_keys_dirty = true;
+            self.send_remove(key.to_string(), item.value);
+            Ok(())
+        } else {
+            Err(Error::KeyNotFound)
+        }
+    }
+
+    /// Removes each of `keys` and returns the ones that were present, in the
+    /// order given.
+    ///
+    /// Each removal sends a remove event and is persisted like
+    /// [`remove`](Self::remove); absent keys are skipped silently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    ///
+    /// let removed = cache.remove_many(["a", "missing", "b"]);
+    /// assert_eq!(removed, vec!["a".to_string(), "b".to_string()]);
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn remove_many<'a, I>(&mut self, keys: I) -> Vec<Key>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut removed = Vec::new();
+        for key in keys {
+            let key = self.normalize_key(key).into_owned();
+            if let Some(item) = self.map.shift_remove(&key) {
+                self.sorted_keys_dirty = true;
+                self.send_remove(key.clone(), item.value);
+                removed.push(key);
+            }
+        }
+        removed
+    }
+
+    /// Removes every live entry whose key matches `filter` and returns them in
+    /// insertion order, e.g. for bulk invalidation by suffix.
+    ///
+    /// Each removal sends a remove event and is persisted like [`remove`](Self::remove).
+    /// Expired entries are cleaned up first and are not returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Filter};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:1:session", "a");
+    /// cache.insert("user:1:profile", "b");
+    /// cache.insert("user:2:session", "c");
+    ///
+    /// let removed = cache.remove_matching(&Filter::EndWith(":session".to_string()));
+    /// assert_eq!(removed.len(), 2);
+    /// assert_eq!(cache.len(), 1);
+    /// assert!(cache.contains_key("user:1:profile"));
+    /// ```
+    pub fn remove_matching(&mut self, filter: &Filter) -> Vec<(Key, Value)> {
+        self.cleanup_expired();
+
+        let mut removed = Vec::new();
+        self.map.retain(|key, item| {
+            if apply_filter_fast(key, filter) {
+                removed.push((key.clone(), std::mem::replace(&mut item.value, Value::Null)));
+                false
+            } else {
+                true
+            }
+        });
+
+        if !removed.is_empty() {
+            self.sorted_keys_dirty = true;
+        }
+
+        for (key, value) in &removed {
+            self.send_remove(key.clone(), value.clone());
+        }
+
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.sorted_keys_dirty = true;
+        self.send_clear();
+    }
+
+    /// Removes all items, sending a `Remove` event for each one instead of a
+    /// single `Clear` event.
+    ///
+    /// Use this when subscribers track individual keys, e.g. to keep a replica
+    /// in sync; [`clear`](Self::clear) is cheaper otherwise. Events are sent in
+    /// insertion order, and expired items that were not cleaned up yet are
+    /// reported too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Event};
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::sync::mpsc::channel;
+    ///
+    /// let (tx, rx) = channel();
+    /// let mut cache = Cache::with_sender(10, tx);
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    /// rx.try_iter().count();
+    ///
+    /// cache.clear_itemized();
+    ///
+    /// let events: Vec<Event> = rx.try_iter().collect();
+    /// assert_eq!(
+    ///     events,
+    ///     vec![
+    ///         Event::remove("a".to_string(), 1.to_value()),
+    ///         Event::remove("b".to_string(), 2.to_value()),
+    ///     ]
+    /// );
+    /// assert!(cache.is_empty());
+    /// ```
+    pub fn clear_itemized(&mut self) {
+        let removed: Vec<(Key, CacheItem)> = self.map.drain(..).collect();
+        self.sorted_keys_dirty = true;
+
+        for (key, item) in removed {
+            self.send_remove(key, item.value);
+        }
+    }
+
+    /// Removes the entries that have a TTL, keeping permanent ones, and returns
+    /// how many were removed.
+    ///
+    /// Useful for flushing transient data while keeping configuration. A
+    /// `Remove` event is sent for each removed entry, in insertion order,
+    /// including expired entries that were not cleaned up yet. Pinned entries
+    /// with a TTL are removed too; see [`clear_unpinned`](Self::clear_unpinned).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("config", "dark-mode");
+    /// cache.insert_with_ttl("session", "abc", Duration::from_secs(60));
+    ///
+    /// assert_eq!(cache.clear_volatile(), 1);
+    /// assert!(cache.contains_key("config"));
+    /// assert!(!cache.contains_key("session"));
+    /// ```
+    pub fn clear_volatile(&mut self) -> usize {
+        self.remove_items_where(|item| item.ttl_millis.is_some())
+    }
+
+    /// Removes every entry that is not pinned and returns how many were removed.
+    ///
+    /// A `Remove` event is sent for each removed entry, as with
+    /// [`clear_volatile`](Self::clear_volatile).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("config", "dark-mode");
+    /// cache.insert("page", "<html>");
+    /// cache.pin("config");
+    ///
+    /// assert_eq!(cache.clear_unpinned(), 1);
+    /// assert!(cache.contains_key("config"));
+    /// assert!(!cache.contains_key("page"));
+    /// ```
+    pub fn clear_unpinned(&mut self) -> usize {
+        self.remove_items_where(|item| !item.pinned)
+    }
+
+    /// Removes the items matching `f`, sending a `Remove` event for each one.
+    fn remove_items_where<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&CacheItem) -> bool,
+    {
+        let mut removed = Vec::new();
+        self.map.retain(|key, item| {
+            if f(item) {
+                removed.push((key.clone(), std::mem::replace(&mut item.value, Value::Null)));
+                false
+            } else {
+                true
+            }
+        });
+
+        if !removed.is_empty() {
+            self.sorted_keys_dirty = true;
+        }
+
+        let count = removed.len();
+        for (key, value) in removed {
+            self.send_remove(key, value);
+        }
+        count
+    }
+
+    /// Replaces the entire contents of the cache with `items`.
+    ///
+    /// Fires a single `Clear` event followed by one `Insert` event per loaded
+    /// item. Like [`warm_up`](Self::warm_up), replacing never evicts: items for
+    /// new keys are skipped once the cache is full, and repeated keys keep the
+    /// last value. Returns the number of items that were inserted, excluding
+    /// items the insert validator rejects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("old", 1);
+    ///
+    /// let loaded = cache.replace_all(vec![("new1".to_string(), 2), ("new2".to_string(), 3)]);
+    ///
+    /// assert_eq!(loaded, 2);
+    /// assert_eq!(cache.get("old"), None);
+    /// assert_eq!(cache.get("new1"), Some(&2.to_value()));
+    /// ```
+    pub fn replace_all<I, V>(&mut self, items: I) -> usize
+    where
+        I: IntoIterator<Item = (String, V)>,
+        V: ToValueBehavior,
+    {
+        self.clear();
+
+        let mut loaded = 0;
+
+        for (key, value) in items {
+            let key = self.normalize_owned_key(key);
+            if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
+                continue;
+            }
+
+            if self.try_insert(key, value).is_ok() {
+                loaded += 1;
+            }
+        }
+
+        loaded
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns a human-readable report of the cache's configuration and contents,
+    /// for troubleshooting.
+    ///
+    /// Lists the capacity, live and total entry counts, default TTL, eviction
+    /// policy, persistence path, TTL statistics and, when access tracking is on,
+    /// the hottest keys, followed by the first 10 keys in insertion order with
+    /// their remaining TTL. Unlike the derived `Debug` output, no map internals
+    /// are printed. The format is meant for people and may change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(100);
+    /// cache.insert("config", "on");
+    /// cache.insert_with_ttl("session:1", "alice", Duration::from_secs(60));
+    ///
+    /// let report = cache.debug_dump();
+    /// assert!(report.contains("capacity: 100"));
+    /// assert!(report.contains("session:1"));
+    /// println!("{}", report);
+    /// ```
+    pub fn debug_dump(&self) -> String {
+        let now = self.now();
+        let live = self
+            .map
+            .values()
+            .filter(|item| !item.is_expired_at(now))
+            .count();
+        let mut lines = vec![
+            "Cache report".to_string(),
+            format!("  capacity: {}", self.capacity),
+            format!("  entries: {} live / {} total", live, self.map.len()),
+            match self.default_ttl {
+                Some(ttl) => format!("  default TTL: {:?}", ttl),
+                None => "  default TTL: none".to_string(),
+            },
+            format!("  eviction policy: {:?}", self.eviction_policy),
+        ];
+
+        #[cfg(feature = "persist")]
+        lines.push(match &self.persist_path {
+            Some(path) => format!("  persist path: {}", path.display()),
+            None => "  persist path: none".to_string(),
+        });
+
+        let ttl = self.ttl_distribution();
+        let mut ttl_line = format!(
+            "  TTLs: {} permanent, {} expiring",
+            ttl.permanent, ttl.ttl_count
+        );
+        if let (Some(min), Some(max), Some(avg)) =
+            (ttl.min_remaining, ttl.max_remaining, ttl.avg_remaining)
+        {
+            ttl_line.push_str(&format!(" (min {:?}, max {:?}, avg {:?})", min, max, avg));
+        }
+        lines.push(ttl_line);
+
+        if self.track_access_counts {
+            let hot: Vec<String> = self
+                .hot_keys(5)
+                .into_iter()
+                .map(|(key, hits)| format!("{} ({})", key, hits))
+                .collect();
+            lines.push(format!("  hot keys: {}", hot.join(", ")));
+        }
+
+        lines.push(format!(
+            "  keys (first {} of {}, insertion order):",
+            DEBUG_DUMP_SAMPLE.min(self.map.len()),
+            self.map.len()
+        ));
+        for (key, item) in self.map.iter().take(DEBUG_DUMP_SAMPLE) {
+            let state = match item.ttl_millis {
+                _ if item.is_expired_at(now) => "expired".to_string(),
+                Some(ttl_millis) => {
+                    let elapsed = now.saturating_sub(item.created_at);
+                    let left = Duration::from_millis(ttl_millis.saturating_sub(elapsed));
+                    format!("expires in {:?}", left)
+                }
+                None => "permanent".to_string(),
+            };
+            let pinned = if item.pinned { ", pinned" } else { "" };
+            lines.push(format!("    {}: {}{}", key, state, pinned));
+        }
+
+        lines.join("\n")
+    }
+
+    /// Checks if a key exists in the cache and hasn't expired.
+    ///
+    /// This method performs lazy cleanup of expired items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("key", "value");
+    ///
+    /// assert!(cache.contains_key("key"));
+    /// assert!(!cache.contains_key("nonexistent"));
+    ///
+    /// // Test with TTL
+    /// cache.insert_with_ttl("temp", "data", Duration::from_millis(1));
+    /// std::thread::sleep(Duration::from_millis(10));
+    /// assert!(!cache.contains_key("temp"));  
+    /// ```
+    pub fn contains_key(&mut self, key: &str) -> bool {
+        let key = self.normalize_key(key);
+        let key = key.as_ref();
+        let now = self.now();
+        match self.map.get(key) {
+            Some(item) if item.is_expired_at(now) => {
+                if self.is_past_grace(item, now) {
+                    self.remove(key).ok();
+                }
+                false
+            }
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    /// Checks whether every key exists in the cache and hasn't expired.
+    ///
+    /// Stops at the first missing key. Expired items encountered along the way
+    /// are removed, as with [`contains_key`](Self::contains_key).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    ///
+    /// assert!(cache.contains_all(&["a", "b"]));
+    /// assert!(!cache.contains_all(&["a", "c"]));
+    /// ```
+    pub fn contains_all(&mut self, keys: &[&str]) -> bool {
+        keys.iter().all(|key| self.contains_key(key))
+    }
+
+    /// Checks whether at least one key exists in the cache and hasn't expired.
+    ///
+    /// Stops at the first live key. Expired items encountered along the way
+    /// are removed, as with [`contains_key`](Self::contains_key).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", 1);
+    ///
+    /// assert!(cache.contains_any(&["c", "a"]));
+    /// assert!(!cache.contains_any(&["c", "d"]));
+    /// ```
+    pub fn contains_any(&mut self, keys: &[&str]) -> bool {
+        keys.iter().any(|key| self.contains_key(key))
+    }
+
+    /// Counts live entries grouped by key namespace.
+    ///
+    /// Each key is grouped by the substring before the first `separator`.
+    /// Keys without the separator are grouped under the whole key.
+    /// Expired items are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:1", "alice");
+    /// cache.insert("user:2", "bob");
+    /// cache.insert("session:x", "active");
+    ///
+    /// let histogram = cache.prefix_histogram(':');
+    /// assert_eq!(histogram["user"], 2);
+    /// assert_eq!(histogram["session"], 1);
+    /// ```
+    pub fn prefix_histogram(&self, separator: char) -> HashMap<String, usize> {
+        let now = self.now();
+        let mut histogram = HashMap::new();
+
+        for (key, item) in &self.map {
+            if item.is_expired_at(now) {
+                continue;
+            }
+
+            let prefix = key
+                .split_once(separator)
+                .map_or(key.as_str(), |(prefix, _)| prefix);
+            *histogram.entry(prefix.to_string()).or_insert(0) += 1;
+        }
+
+        histogram
+    }
+
+    /// Summarizes the TTLs of live entries, e.g. for monitoring dashboards.
+    ///
+    /// Expired items are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("config", "permanent");
+    /// cache.insert_with_ttl("session:1", "alice", Duration::from_secs(60));
+    /// cache.insert_with_ttl("session:2", "bob", Duration::from_secs(120));
+    ///
+    /// let stats = cache.ttl_distribution();
+    /// assert_eq!(stats.permanent, 1);
+    /// assert_eq!(stats.ttl_count, 2);
+    /// assert!(stats.max_remaining.unwrap() <= Duration::from_secs(120));
+    /// ```
+    pub fn ttl_distribution(&self) -> TtlStats {
+        let now = self.now();
+        let mut stats = TtlStats::default();
+        let mut min_millis = u64::MAX;
+        let mut max_millis = 0;
+        let mut total_millis: u128 = 0;
+
+        for item in self.map.values() {
+            if item.is_expired_at(now) {
+                continue;
+            }
+
+            match item.ttl_millis {
+                Some(ttl_millis) => {
+                    let elapsed = now.saturating_sub(item.created_at);
+                    let remaining = ttl_millis.saturating_sub(elapsed);
+                    stats.ttl_count += 1;
+                    min_millis = min_millis.min(remaining);
+                    max_millis = max_millis.max(remaining);
+                    total_millis += u128::from(remaining);
+                }
+                None => stats.permanent += 1,
+            }
+        }
+
+        if stats.ttl_count > 0 {
+            let avg_millis = total_millis / stats.ttl_count as u128;
+            stats.min_remaining = Some(Duration::from_millis(min_millis));
+            stats.max_remaining = Some(Duration::from_millis(max_millis));
+            stats.avg_remaining = Some(Duration::from_millis(avg_millis as u64));
+        }
+
+        stats
+    }
+
+    /// Returns the live keys with a TTL that expire before `deadline`, soonest first.
+    ///
+    /// Useful for scheduling refreshes. Entries without a TTL are never returned,
+    /// and already expired entries are skipped without being removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("slow", 1, Duration::from_secs(600));
+    /// cache.insert_with_ttl("fast", 2, Duration::from_secs(30));
+    /// cache.insert_with_ttl("medium", 3, Duration::from_secs(60));
+    /// cache.insert("permanent", 4);
+    ///
+    /// let deadline = SystemTime::now() + Duration::from_secs(300);
+    /// assert_eq!(cache.keys_expiring_before(deadline), vec!["fast", "medium"]);
+    /// ```
+    pub fn keys_expiring_before(&self, deadline: SystemTime) -> Vec<Key> {
+        let deadline_millis = deadline
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_millis() as u64);
+        let now = self.now();
+
+        let mut expiring: Vec<(u64, &Key)> = self
+            .map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .filter_map(|(key, item)| {
+                let expires_at = item.created_at.saturating_add(item.ttl_millis?);
+                (expires_at < deadline_millis).then_some((expires_at, key))
+            })
+            .collect();
+        expiring.sort_by_key(|(expires_at, _)| *expires_at);
+
+        expiring.into_iter().map(|(_, key)| key.clone()).collect()
+    }
+
+    /// Returns the live keys sorted in `order`, without their values.
+    ///
+    /// Lighter than [`list`](Self::list) for key-only listings: values are not
+    /// cloned and expired entries are skipped without being removed. Keys compare
+    /// by Unicode code point, like `list` without a collator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Order};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("banana", 2);
+    /// cache.insert("apple", 1);
+    /// cache.insert("cherry", 3);
+    ///
+    /// assert_eq!(cache.keys_sorted(Order::Asc), vec!["apple", "banana", "cherry"]);
+    /// assert_eq!(cache.keys_sorted(Order::Desc), vec!["cherry", "banana", "apple"]);
+    /// ```
+    pub fn keys_sorted(&self, order: Order) -> Vec<Key> {
+        let now = self.now();
+        let mut keys: Vec<Key> = self
+            .map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        keys.sort_unstable();
+        if let Order::Desc = order {
+            keys.reverse();
+        }
+        keys
+    }
+
+    /// Restarts the TTL of every live entry, e.g. to extend everything's life on
+    /// deploy.
+    ///
+    /// Each entry's creation time is set to now and its TTL to `ttl`, with the
+    /// configured [jitter](Self::set_ttl_jitter) applied; `None` makes every
+    /// entry permanent. Expired entries are left alone. The changes are written
+    /// to the backing store, if any, but no events are sent since values don't
+    /// change. Returns the number of entries updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("permanent", 1);
+    /// cache.insert_with_ttl("short", 2, Duration::from_secs(1));
+    ///
+    /// assert_eq!(cache.reset_ttl_all(Some(Duration::from_secs(3600))), 2);
+    /// assert_eq!(cache.get_ttl("permanent"), Some(Duration::from_secs(3600)));
+    /// assert_eq!(cache.get_ttl("short"), Some(Duration::from_secs(3600)));
+    ///
+    /// cache.reset_ttl_all(None);
+    /// assert_eq!(cache.get_ttl("short"), None);
+    /// ```
+    pub fn reset_ttl_all(&mut self, ttl: Option<Duration>) -> usize {
+        let now = self.now();
+        let live: Vec<Key> = self
+            .map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &live {
+            let ttl_millis = ttl.map(|ttl| self.jittered(ttl).as_millis() as u64);
+            if let Some(item) = self.map.get_mut(key) {
+                item.created_at = now;
+                item.ttl_millis = ttl_millis;
+                if let Some(store) = &self.backing_store {
+                    store.apply(StoreOp::Put(key.clone(), Box::new(item.clone())));
+                }
+            }
+        }
+
+        if !live.is_empty() {
+            self.last_modified = Some(now);
+        }
+
+        live.len()
+    }
+
+    /// Restarts the TTL of the live entries under `keys`, e.g. to keep a working
+    /// set alive in one call.
+    ///
+    /// Each entry's creation time is set to now; its TTL duration is kept.
+    /// Missing and expired keys are skipped and a key listed twice is refreshed
+    /// once. The new deadlines are written to the backing store, if any, but no
+    /// events are sent since values don't change. Returns the number of entries
+    /// refreshed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("a", 1, Duration::from_secs(60));
+    /// cache.insert_with_ttl("b", 2, Duration::from_secs(60));
+    ///
+    /// assert_eq!(cache.touch_many(&["a", "a", "missing"]), 1);
+    /// assert_eq!(cache.get_ttl("a"), Some(Duration::from_secs(60)));
+    /// ```
+    pub fn touch_many(&mut self, keys: &[&str]) -> usize {
+        let now = self.now();
+        let mut keys: Vec<Key> = keys
+            .iter()
+            .map(|key| self.normalize_key(key).into_owned())
+            .collect();
+        keys.sort_unstable();
+        keys.dedup();
+
+        let mut refreshed = 0;
+        for key in &keys {
+            let Some(item) = self.map.get_mut(key) else {
+                continue;
+            };
+            if item.is_expired_at(now) {
+                continue;
+            }
+            item.created_at = now;
+            if let Some(store) = &self.backing_store {
+                store.apply(StoreOp::Put(key.clone(), Box::new(item.clone())));
+            }
+            refreshed += 1;
+        }
+
+        if refreshed > 0 {
+            self.last_modified = Some(now);
+        }
+
+        refreshed
+    }
+
+    /// Makes the live entry for `key` expire now, without removing it yet.
+    ///
+    /// Unlike [`remove`](Self::remove), the entry goes through the expiry path:
+    /// the next [`get`](Self::get), [`cleanup_expired`](Self::cleanup_expired)
+    /// or other lazy cleanup drops it and sends the remove event, and it can
+    /// still be served by [`get_stale`](Self::get_stale) within the stale grace
+    /// period. Useful for testing invalidation. The new deadline is written to
+    /// the backing store, if any. Returns `false` if the key is missing or
+    /// already expired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("session", "alice");
+    ///
+    /// assert!(cache.expire_now("session"));
+    /// assert_eq!(cache.len(), 1); // Still stored until the next lookup
+    /// assert_eq!(cache.get("session"), None);
+    /// assert_eq!(cache.len(), 0);
+    /// ```
+    pub fn expire_now(&mut self, key: &str) -> bool {
+        let key = self.normalize_key(key).into_owned();
+        let now = self.now();
+        let Some(item) = self.map.get_mut(&key) else {
+            return false;
+        };
+        if item.is_expired_at(now) {
+            return false;
+        }
+
+        // A zero TTL has expired once at least one millisecond has passed
+        item.ttl_millis = Some(0);
+        item.created_at = item.created_at.min(now.saturating_sub(1));
+        if let Some(store) = &self.backing_store {
+            store.apply(StoreOp::Put(key, Box::new(item.clone())));
+        }
+
+        true
+    }
+
+    /// Manually removes all expired items from the cache.
+    ///
+    /// Returns the number of items that were removed.
+    /// This is useful for proactive cleanup, though the cache also performs lazy cleanup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    /// use std::thread;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("temp1", "data1", Duration::from_millis(10));
+    /// cache.insert_with_ttl("temp2", "data2", Duration::from_millis(10));
+    /// cache.insert("permanent", "data");
+    ///
+    /// thread::sleep(Duration::from_millis(20));
+    ///
+    /// let removed = cache.cleanup_expired();
+    /// assert_eq!(removed, 2);  
+    /// assert_eq!(cache.len(), 1);  
+    /// ```
+    pub fn cleanup_expired(&mut self) -> usize {
+        self.cleanup_expired_with(|_, _| {})
+    }
+
+    /// Removes all expired items, calling `f` with each one right before it is removed.
+    ///
+    /// Returns the number of items that were removed. Remove events are still
+    /// sent after the callbacks have run. Items within the
+    /// [stale grace period](Self::set_stale_grace) are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::time::Duration;
+    /// use std::thread;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert_with_ttl("session:1", "alice", Duration::from_millis(10));
+    /// cache.insert("permanent", "data");
+    ///
+    /// thread::sleep(Duration::from_millis(20));
+    ///
+    /// let mut expired = Vec::new();
+    /// let removed = cache.cleanup_expired_with(|key, value| {
+    ///     expired.push((key.to_string(), value.clone()));
+    /// });
+    ///
+    /// assert_eq!(removed, 1);
+    /// assert_eq!(expired, vec![("session:1".to_string(), "alice".to_value())]);
+    /// ```
+    pub fn cleanup_expired_with<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(&str, &Value),
+    {
+        // Entries within the stale grace period are kept for `get_stale`
+        let cutoff = self
+            .now()
+            .saturating_sub(self.stale_grace.as_millis() as u64);
+        let mut expired = Vec::new();
+
+        // `retain` keeps the insertion order of the remaining entries in one pass
+        self.map.retain(|key, item| {
+            if item.is_expired_at(cutoff) {
+                f(key, &item.value);
+                expired.push((key.clone(), std::mem::replace(&mut item.value, Value::Null)));
+                false
+            } else {
+                true
+            }
+        });
+
+        let removed_count = expired.len();
+
+        if removed_count > 0 {
+            log_debug!("Cleaned up {} expired items", removed_count);
+            self.sorted_keys_dirty = true;
+        }
+
+        for (key, value) in expired {
+            self.send_remove(key, value);
+        }
+
+        removed_count
+    }
+
+    /// Sets a function applied to every key passed to `insert`, `get`, `remove`
+    /// and `contains_key` (and the methods built on them).
+    ///
+    /// Keys are stored in normalized form, so `list` returns normalized keys.
+    /// The normalizer should be idempotent. Keys already in the cache are not
+    /// rewritten, so set it before inserting, e.g. with
+    /// [`CacheBuilder::normalize_keys`](crate::CacheBuilder::normalize_keys).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_key_normalizer(Some(|key: &str| key.trim().to_lowercase()));
+    ///
+    /// cache.insert(" ABC ", 1);
+    /// assert_eq!(cache.get("abc"), Some(&1.to_value()));
+    /// assert!(cache.contains_key("Abc"));
+    /// ```
+    #[inline]
+    pub fn set_key_normalizer(&mut self, normalizer: Option<KeyNormalizer>) {
+        self.key_normalizer = normalizer;
+    }
+
+    #[inline(always)]
+    pub fn get_key_normalizer(&self) -> Option<KeyNormalizer> {
+        self.key_normalizer
+    }
+
+    /// Sets a function applied to every value stored by `insert`,
+    /// `insert_with_ttl` and `set_value` (and the methods built on them), e.g.
+    /// to compress large values.
+    ///
+    /// The transform runs before the value is stored and before the insert
+    /// event is sent, so reads and events both see the transformed value. It
+    /// also runs before an insert is compared with the current value, so it
+    /// should be deterministic. Values already in the cache are not rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Value};
+    /// use quickleaf::valu3::prelude::StringBehavior;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_value_transform(Some(|value: Value| value.as_string().to_uppercase().to_value()));
+    ///
+    /// cache.insert("greeting", "hello");
+    /// assert_eq!(cache.get("greeting"), Some(&"HELLO".to_value()));
+    /// ```
+    #[inline]
+    pub fn set_value_transform(&mut self, transform: Option<ValueTransform>) {
+        self.value_transform = transform;
+    }
+
+    #[inline(always)]
+    pub fn get_value_transform(&self) -> Option<ValueTransform> {
+        self.value_transform
+    }
+
+    /// Sets a function that checks every key and value passed to `insert` and
+    /// `insert_with_ttl` and `set_value` (and the methods built on them), e.g. to
+    /// enforce a key pattern or reject empty values.
+    ///
+    /// The validator sees the normalized key and the transformed value. A
+    /// rejected insert leaves the cache unchanged and sends no event; `insert`
+    /// drops it silently while [`try_insert`](Self::try_insert) returns
+    /// [`Error::Rejected`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Value};
+    /// use quickleaf::valu3::prelude::StringBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_insert_validator(Some(|_: &str, value: &Value| {
+    ///     if value.as_string().is_empty() {
+    ///         Err("empty value".to_string())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// }));
+    ///
+    /// cache.insert("empty", "");
+    /// cache.insert("name", "alice");
+    /// assert!(!cache.contains_key("empty"));
+    /// assert!(cache.contains_key("name"));
+    /// ```
+    #[inline]
+    pub fn set_insert_validator(&mut self, validator: Option<InsertValidator>) {
+        self.insert_validator = validator;
+    }
+
+    #[inline(always)]
+    pub fn get_insert_validator(&self) -> Option<InsertValidator> {
+        self.insert_validator
+    }
+
+    #[inline(always)]
+    fn validate_insert(&self, key: &str, value: &Value) -> Result<(), Error> {
+        match self.insert_validator {
+            Some(validate) => validate(key, value).map_err(|reason| {
+                log_debug!("Rejected key '{}': {}", key, reason);
+                Error::Rejected(reason)
+            }),
+            None => Ok(()),
+        }
+    }
+
+    #[inline(always)]
+    fn transform_value<V: ToValueBehavior>(&self, value: V) -> Value {
+        match self.value_transform {
+            Some(transform) => transform(value.to_value()),
+            None => value.to_value(),
+        }
+    }
+
+    #[inline(always)]
+    fn normalize_key<'a>(&self, key: &'a str) -> Cow<'a, str> {
+        match self.key_normalizer {
+            Some(normalize) => Cow::Owned(normalize(key)),
+            None => Cow::Borrowed(key),
+        }
+    }
+
+    #[inline(always)]
+    fn normalize_owned_key(&self, key: Key) -> Key {
+        match self.key_normalizer {
+            Some(normalize) => normalize(&key),
+            None => key,
+        }
+    }
+
+    /// Enables or disables counting `get` hits per entry, as reported by
+    /// [`hot_keys`](Self::hot_keys).
+    ///
+    /// Disabling tracking keeps the counts collected so far.
+    #[inline]
+    pub fn set_track_access_counts(&mut self, enabled: bool) {
+        self.track_access_counts = enabled;
+    }
+
+    #[inline(always)]
+    pub fn get_track_access_counts(&self) -> bool {
+        self.track_access_counts
+    }
+
+    /// Returns up to `n` live keys with the most `get` hits, most accessed first.
+    ///
+    /// Hits are only counted while access tracking is enabled (see
+    /// [`CacheBuilder::track_access_counts`](crate::CacheBuilder::track_access_counts)).
+    /// Keys that were never hit are left out, ties are ordered by key, and
+    /// replacing an entry's value resets its count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::builder(10).track_access_counts(true).build();
+    /// cache.insert("hot", 1);
+    /// cache.insert("warm", 2);
+    /// cache.insert("cold", 3);
+    ///
+    /// for _ in 0..3 {
+    ///     cache.get("hot");
+    /// }
+    /// cache.get("warm");
+    ///
+    /// assert_eq!(
+    ///     cache.hot_keys(5),
+    ///     vec![("hot".to_string(), 3), ("warm".to_string(), 1)]
+    /// );
+    /// ```
+    pub fn hot_keys(&self, n: usize) -> Vec<(Key, u64)> {
+        let now = self.now();
+        let candidates = self
+            .map
+            .iter()
+            .filter(|(_, item)| item.access_count > 0 && !item.is_expired_at(now))
+            .map(|(key, item)| (Reverse(item.access_count), key));
+
+        select_smallest(candidates, n)
+            .into_iter()
+            .map(|(Reverse(count), key)| (key.clone(), count))
+            .collect()
+    }
+
+    /// Sets the clock used for TTL bookkeeping.
+    ///
+    /// The clock returns the current time in milliseconds since the UNIX epoch.
+    /// It defaults to the system clock; a custom clock makes expiry
+    /// deterministic in tests and works where `SystemTime` is unavailable.
+    /// Items already in the cache keep the timestamps taken from the previous
+    /// clock, so set it before inserting, e.g. with
+    /// [`CacheBuilder::clock`](crate::CacheBuilder::clock).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_clock(|| 1_000);
+    ///
+    /// cache.insert_with_ttl("key", "value", Duration::from_secs(1));
+    /// assert!(cache.contains_key("key"));
+    /// ```
+    #[inline]
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+    }
+
+    #[inline(always)]
+    fn now(&self) -> u64 {
+        (self.clock)()
+    }
+
+    #[inline]
+    pub fn set_default_ttl(&mut self, ttl: Option<Duration>) {
+        self.default_ttl = ttl;
+    }
+
+    #[inline(always)]
+    pub fn get_default_ttl(&self) -> Option<Duration> {
+        self.default_ttl
+    }
+
+    /// Randomizes the TTL of inserted items within `ttl ± jitter`.
+    ///
+    /// Keys inserted together with the same TTL would otherwise all expire at
+    /// once and be refreshed in a stampede. The jitter applies to both explicit
+    /// and default TTLs, is drawn independently for each insert, and never makes
+    /// a TTL negative. `Duration::ZERO` (the default) disables it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = Cache::with_default_ttl(10, Duration::from_secs(60));
+    /// cache.set_ttl_jitter(Duration::from_secs(5));
+    /// cache.insert("key", "value");
+    ///
+    /// let ttl = cache.get_ttl("key").unwrap();
+    /// assert!(ttl >= Duration::from_secs(55) && ttl <= Duration::from_secs(65));
+    /// ```
+    pub fn set_ttl_jitter(&mut self, jitter: Duration) {
+        self.ttl_jitter = jitter;
+    }
+
+    #[inline(always)]
+    pub fn get_ttl_jitter(&self) -> Duration {
+        self.ttl_jitter
+    }
+
+    /// Keeps expired entries for `grace` so [`get_stale`](Self::get_stale) can
+    /// still serve them.
+    ///
+    /// Other reads treat these entries as expired, but don't remove them until
+    /// the grace period is over; they keep counting towards
+    /// [`len`](Self::len) and the capacity until then. `Duration::ZERO` (the
+    /// default) removes entries as soon as they expire.
+    pub fn set_stale_grace(&mut self, grace: Duration) {
+        self.stale_grace = grace;
+    }
+
+    #[inline(always)]
+    pub fn get_stale_grace(&self) -> Duration {
+        self.stale_grace
+    }
+
+    /// Applies the configured jitter to `ttl`
+    fn jittered(&self, ttl: Duration) -> Duration {
+        let jitter = self.ttl_jitter.as_millis() as u64;
+        if jitter == 0 {
+            return ttl;
+        }
+
+        let offset = random_u64() % jitter.saturating_mul(2).saturating_add(1);
+        let ttl = ttl.as_millis() as u64;
+        Duration::from_millis(ttl.saturating_add(offset).saturating_sub(jitter))
+    }
+
+    /// Sets the policy used to pick a victim when the cache is full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, EvictionPolicy};
+    ///
+    /// let mut cache = Cache::new(100);
+    /// assert_eq!(cache.get_eviction_policy(), EvictionPolicy::Lru);
+    ///
+    /// cache.set_eviction_policy(EvictionPolicy::Clock);
+    /// assert_eq!(cache.get_eviction_policy(), EvictionPolicy::Clock);
+    /// ```
+    #[inline]
+    pub fn set_eviction_policy(&mut self, policy: EvictionPolicy) {
+        self.eviction_policy = policy;
+        self.clock_hand = 0;
+    }
+
+    #[inline(always)]
+    pub fn get_eviction_policy(&self) -> EvictionPolicy {
+        self.eviction_policy
+    }
+
+    /// Sets how many entries are evicted at once when an insert finds the cache full.
+    ///
+    /// With the default of 1, every insert into a full cache evicts one entry.
+    /// A larger batch frees several slots at once so the following inserts
+    /// don't evict, at the cost of temporarily holding fewer items. A size of 0
+    /// is treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(4);
+    /// cache.set_eviction_batch_size(2);
+    ///
+    /// for i in 0..5 {
+    ///     cache.insert(format!("key{}", i), i);
+    /// }
+    ///
+    /// // Inserting "key4" evicted "key0" and "key1" together
+    /// assert_eq!(cache.get_list(), vec!["key2", "key3", "key4"]);
+    /// ```
+    #[inline]
+    pub fn set_eviction_batch_size(&mut self, batch_size: usize) {
+        self.eviction_batch_size = batch_size.max(1);
+    }
+
+    #[inline(always)]
+    pub fn get_eviction_batch_size(&self) -> usize {
+        self.eviction_batch_size
+    }
+
+    /// Sets whether a full cache grows its capacity instead of evicting.
+    ///
+    /// With [`ResizePolicy::Grow`], an insert that would evict multiplies the
+    /// capacity by `factor` instead, until `max` is reached. Capacity never
+    /// shrinks automatically.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Grow` factor is not greater than 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, ResizePolicy};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_resize_policy(ResizePolicy::Grow { factor: 1.5, max: 100 });
+    ///
+    /// for i in 0..11 {
+    ///     cache.insert(format!("key{}", i), i);
+    /// }
+    /// assert_eq!(cache.capacity(), 15);
+    /// ```
+    pub fn set_resize_policy(&mut self, policy: ResizePolicy) {
+        if let ResizePolicy::Grow { factor, .. } = policy {
+            assert!(factor > 1.0, "resize factor must be greater than 1");
+        }
+        self.resize_policy = policy;
+    }
+
+    #[inline(always)]
+    pub fn get_resize_policy(&self) -> ResizePolicy {
+        self.resize_policy
+    }
+
+    /// Returns the fraction of the capacity in use, from 0.0 (empty) to 1.0 (full).
+    ///
+    /// A zero-capacity cache reports 0.0.
+    #[inline]
+    pub fn capacity_utilization(&self) -> f64 {
+        if self.capacity == 0 {
+            return 0.0;
+        }
+        self.map.len() as f64 / self.capacity as f64
+    }
+
+    /// Registers a callback invoked when [utilization](Self::capacity_utilization)
+    /// crosses a watermark, e.g. to resize the cache or raise an alert.
+    ///
+    /// The callback receives [`Watermark::High`] when utilization rises to `high`,
+    /// and then [`Watermark::Low`] once it falls back to `low`, so it doesn't fire
+    /// on every insert into a full cache. It runs synchronously after the change,
+    /// with the new utilization. Registering a new callback replaces the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low` is greater than `high`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Watermark};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let crossings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&crossings);
+    ///
+    /// let mut cache = Cache::new(4);
+    /// cache.on_utilization(0.25, 0.75, move |mark, _| sink.lock().unwrap().push(mark));
+    ///
+    /// for key in ["a", "b", "c", "d"] {
+    ///     cache.insert(key, 1);
+    /// }
+    /// cache.remove("a").unwrap();
+    /// cache.remove("b").unwrap();
+    /// cache.remove("c").unwrap();
+    ///
+    /// assert_eq!(*crossings.lock().unwrap(), vec![Watermark::High, Watermark::Low]);
+    /// ```
+    pub fn on_utilization<F>(&mut self, low: f64, high: f64, callback: F)
+    where
+        F: FnMut(Watermark, f64) + Send + 'static,
+    {
+        assert!(
+            low <= high,
+            "low watermark must not exceed the high watermark"
+        );
+        self.watermarks = Some(Watermarks {
+            low,
+            high,
+            callback: Arc::new(Mutex::new(callback)),
+            above: self.capacity_utilization() >= high,
+        });
+    }
+
+    /// Unregisters the utilization callback.
+    #[inline]
+    pub fn remove_on_utilization(&mut self) {
+        self.watermarks = None;
+    }
+
+    /// Registers a callback invoked with each entry evicted to respect the capacity.
+    ///
+    /// The callback runs synchronously, before the entry's remove event is sent,
+    /// so a write-back cache can save evicted data to its backing store. It is
+    /// not called for expired, removed or cleared entries. Registering a new
+    /// callback replaces the previous one.
     ///
     /// # Examples
     ///
     /// ```
     /// use quickleaf::Cache;
     /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let evicted = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&evicted);
     ///
     /// let mut cache = Cache::new(2);
-    /// cache.insert("key1", "value1");
-    /// cache.insert("key2", "value2");
-    /// cache.insert("key3", "value3");  
+    /// cache.on_evict(move |key, value| {
+    ///     sink.lock().unwrap().push((key.to_string(), value.clone()));
+    /// });
     ///
-    /// assert_eq!(cache.get("key1"), None);  
-    /// assert_eq!(cache.get("key2"), Some(&"value2".to_value()));
-    /// assert_eq!(cache.get("key3"), Some(&"value3".to_value()));
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    /// cache.insert("c", 3);
+    ///
+    /// assert_eq!(*evicted.lock().unwrap(), vec![("a".to_string(), 1.to_value())]);
     /// ```
-    pub fn insert<T, V>(&mut self, key: T, value: V)
+    pub fn on_evict<F>(&mut self, callback: F)
     where
-        T: Into<String>,
-        V: ToValueBehavior,
+        F: FnMut(&str, &Value) + Send + 'static,
+    {
+        self.on_evict = Some(EvictionCallback(Arc::new(Mutex::new(callback))));
+    }
+
+    /// Removes the callback registered with [`on_evict`](Self::on_evict).
+    pub fn remove_on_evict(&mut self) {
+        self.on_evict = None;
+    }
+
+    /// Registers a guard that can veto the eviction of an entry, e.g. to protect
+    /// entries that are currently in use.
+    ///
+    /// Before an entry is evicted to make room, by [`shrink_to`](Self::shrink_to)
+    /// or by [`evict_from_prefix`](Self::evict_from_prefix), the guard is called
+    /// with its key and value. [`EvictDecision::Keep`] skips the entry, as if it
+    /// were pinned, and the next candidate per the eviction policy is tried. If
+    /// the guard keeps every candidate, capacity evictions and `shrink_to` fall
+    /// back to the policy's victims so the cache never exceeds its capacity, while
+    /// `evict_from_prefix` returns `None`. Replaces any previous guard; the guard
+    /// must not call back into the cache.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, EvictDecision};
+    ///
+    /// let mut cache = Cache::new(2);
+    /// cache.guard_eviction(|key, _| {
+    ///     if key == "session" {
+    ///         EvictDecision::Keep
+    ///     } else {
+    ///         EvictDecision::Evict
+    ///     }
+    /// });
+    ///
+    /// cache.insert("session", 1);
+    /// cache.insert("a", 2);
+    /// cache.insert("b", 3);
+    ///
+    /// assert!(cache.contains_key("session"));
+    /// assert!(!cache.contains_key("a"));
+    /// ```
+    pub fn guard_eviction<F>(&mut self, guard: F)
+    where
+        F: FnMut(&str, &Value) -> EvictDecision + Send + 'static,
     {
-        let key = key.into();
+        self.eviction_guard = Some(EvictionGuard(Arc::new(Mutex::new(guard))));
+    }
+
+    /// Removes the guard registered with [`guard_eviction`](Self::guard_eviction).
+    pub fn remove_eviction_guard(&mut self) {
+        self.eviction_guard = None;
+    }
+
+    /// Evicts the oldest live entry whose key starts with `prefix` and returns it,
+    /// e.g. to make room at the expense of a low-priority tenant.
+    ///
+    /// "Oldest" follows insertion order, like [`EvictionPolicy::Lru`]. Pinned and
+    /// expired entries are skipped, as are entries the
+    /// [`guard_eviction`](Self::guard_eviction) guard keeps. The eviction is reported like a capacity
+    /// eviction: the [`on_evict`](Self::on_evict) callback runs, then a remove
+    /// event is sent and persisted. Returns `None` if no entry qualifies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("premium:1", "a");
+    /// cache.insert("free:1", "b");
+    /// cache.insert("free:2", "c");
+    ///
+    /// assert_eq!(
+    ///     cache.evict_from_prefix("free:"),
+    ///     Some(("free:1".to_string(), "b".to_value()))
+    /// );
+    /// assert!(cache.contains_key("premium:1"));
+    /// assert!(cache.contains_key("free:2"));
+    /// ```
+    pub fn evict_from_prefix(&mut self, prefix: &str) -> Option<(Key, Value)> {
+        let now = self.now();
+        let index = self.map.iter().position(|(key, item)| {
+            !item.pinned
+                && !item.is_expired_at(now)
+                && fast_prefix_match(key, prefix)
+                && self.allows_eviction(key, &item.value)
+        })?;
+        let (key, item) = self.map.shift_remove_index(index)?;
+        self.sorted_keys_dirty = true;
+
+        log_debug!("Evicted key '{}' from prefix '{}'", key, prefix);
+        self.call_on_evict(&key, &item.value);
+        self.send_remove(key.clone(), item.value.clone());
+
+        Some((key, item.value))
+    }
+
+    /// Exempts the entry for `key` from capacity eviction, e.g. for configuration
+    /// that must stay cached.
+    ///
+    /// Pinned entries still expire with their TTL, and the pin survives inserts
+    /// that replace the value. When every entry is pinned, inserts of new keys are
+    /// dropped. The pin is persisted along with the entry. Returns `false` if the
+    /// key is missing or expired.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(2);
+    /// cache.insert("config", "v1");
+    /// assert!(cache.pin("config"));
+    ///
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
+    ///
+    /// assert!(cache.contains_key("config"));
+    /// assert!(!cache.contains_key("a"));
+    /// ```
+    pub fn pin(&mut self, key: &str) -> bool {
+        self.set_pinned(key, true)
+    }
+
+    /// Makes a pinned entry evictable again.
+    ///
+    /// Returns `false` if the key is missing or expired.
+    pub fn unpin(&mut self, key: &str) -> bool {
+        self.set_pinned(key, false)
+    }
+
+    /// Returns `true` if the live entry for `key` is pinned.
+    pub fn is_pinned(&self, key: &str) -> bool {
+        self.live_item(key).is_some_and(|item| item.pinned)
+    }
+
+    fn set_pinned(&mut self, key: &str, pinned: bool) -> bool {
+        let key = self.normalize_key(key).into_owned();
+        if !self.contains_key(&key) {
+            return false;
+        }
+
+        let item = match self.map.get_mut(&key) {
+            Some(item) if item.pinned != pinned => item,
+            _ => return true,
+        };
+        item.pinned = pinned;
+
+        if let Some(store) = &self.backing_store {
+            store.apply(StoreOp::Put(key, Box::new(item.clone())));
+        }
+
+        true
+    }
+
+    /// Grows the capacity if the resize policy allows it, otherwise evicts entries
+    /// according to the eviction policy to make room for `incoming`, freeing up to
+    /// `eviction_batch_size` slots at once.
+    fn make_room(&mut self, incoming: &str) {
+        if self.grow() {
+            return;
+        }
+
+        let target = self.capacity.saturating_sub(self.eviction_batch_size);
+        let excess = self.map.len().saturating_sub(target);
+
+        for (evicted_key, evicted_item) in self.evict(excess) {
+            log_debug!(
+                "Evicted key '{}' to make room for '{}'",
+                evicted_key,
+                incoming
+            );
+            self.call_on_evict(&evicted_key, &evicted_item.value);
+            self.send_remove(evicted_key, evicted_item.value);
+        }
+    }
+
+    /// Removes up to `count` entries according to the eviction policy, skipping
+    /// pinned ones and, while others remain, ones the eviction guard keeps
+    fn evict(&mut self, count: usize) -> Vec<(Key, CacheItem)> {
+        let evicted: Vec<(Key, CacheItem)> = match self.eviction_policy {
+            EvictionPolicy::Lru if self.eviction_guard.is_some() => self.evict_guarded(count),
+            EvictionPolicy::Lru if self.map.values().take(count).any(|item| item.pinned) => {
+                self.evict_unpinned(count)
+            }
+            EvictionPolicy::Lru => self.map.drain(..count).collect(),
+            EvictionPolicy::Clock => (0..count).filter_map(|_| self.evict_clock()).collect(),
+        };
+
+        if !evicted.is_empty() {
+            self.sorted_keys_dirty = true;
+        }
+
+        evicted
+    }
 
-        let item = if let Some(default_ttl) = self.default_ttl {
-            CacheItem::with_ttl(value.to_value(), default_ttl)
+    /// Passes an evicted entry to the [`on_evict`](Self::on_evict) callback, if any
+    fn call_on_evict(&self, key: &str, value: &Value) {
+        if let Some(EvictionCallback(callback)) = &self.on_evict {
+            let mut callback = callback.lock().unwrap_or_else(PoisonError::into_inner);
+            callback(key, value);
+        }
+    }
+
+    /// Asks the eviction guard, if any, whether the entry may be evicted
+    fn allows_eviction(&self, key: &str, value: &Value) -> bool {
+        let Some(EvictionGuard(guard)) = &self.eviction_guard else {
+            return true;
+        };
+        let mut guard = guard.lock().unwrap_or_else(PoisonError::into_inner);
+        guard(key, value) == EvictDecision::Evict
+    }
+
+    /// Raises the capacity per a [`ResizePolicy::Grow`] policy. Returns `false` if
+    /// the policy is fixed or the maximum is reached.
+    fn grow(&mut self) -> bool {
+        let ResizePolicy::Grow { factor, max } = self.resize_policy else {
+            return false;
+        };
+        if self.capacity >= max {
+            return false;
+        }
+
+        let grown = (self.capacity as f64 * factor).ceil() as usize;
+        let capacity = grown.max(self.capacity + 1).min(max);
+        log_debug!("Grew capacity from {} to {}", self.capacity, capacity);
+        self.capacity = capacity;
+        true
+    }
+
+    /// Reports a watermark crossing to the utilization callback, if any
+    fn check_watermarks(&mut self) {
+        let utilization = self.capacity_utilization();
+        let Some(watermarks) = &mut self.watermarks else {
+            return;
+        };
+
+        let crossed = if !watermarks.above && utilization >= watermarks.high {
+            Watermark::High
+        } else if watermarks.above && utilization <= watermarks.low {
+            Watermark::Low
         } else {
-            CacheItem::new(value.to_value())
+            return;
         };
 
-        if let Some(existing_item) = self.map.get(&key) {
-            if existing_item.value == item.value {
-                return;
+        watermarks.above = crossed == Watermark::High;
+        let mut callback = watermarks
+            .callback
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        callback(crossed, utilization);
+    }
+
+    /// Removes the `count` oldest entries that are not pinned
+    fn evict_unpinned(&mut self, count: usize) -> Vec<(Key, CacheItem)> {
+        let mut evicted = Vec::with_capacity(count);
+        self.map.retain(|key, item| {
+            if evicted.len() < count && !item.pinned {
+                let item = std::mem::replace(item, CacheItem::new(Value::Null));
+                evicted.push((key.clone(), item));
+                false
+            } else {
+                true
+            }
+        });
+        evicted
+    }
+
+    /// Removes the `count` oldest unpinned entries the eviction guard allows,
+    /// topping up with the oldest kept ones if too few are allowed
+    fn evict_guarded(&mut self, count: usize) -> Vec<(Key, CacheItem)> {
+        let mut allowed = Vec::with_capacity(count);
+        let mut kept = Vec::new();
+        for (index, (key, item)) in self.map.iter().enumerate() {
+            if allowed.len() == count {
+                break;
+            }
+            if item.pinned {
+                continue;
+            }
+            if self.allows_eviction(key, &item.value) {
+                allowed.push(index);
+            } else if kept.len() < count {
+                kept.push(index);
             }
         }
 
-        if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
-            if let Some((first_key, first_item)) = self.map.shift_remove_index(0) {
-                self.send_remove(first_key, first_item.value);
+        if allowed.len() < count {
+            log_debug!("Eviction guard kept every candidate, evicting kept entries");
+            let missing = count - allowed.len();
+            allowed.extend(kept.into_iter().take(missing));
+            allowed.sort_unstable();
+        }
+
+        let mut evicted: Vec<(Key, CacheItem)> = allowed
+            .into_iter()
+            .rev()
+            .filter_map(|index| self.map.shift_remove_index(index))
+            .collect();
+        evicted.reverse();
+        evicted
+    }
+
+    /// Removes the entry under the CLOCK hand once it finds one with no recent
+    /// reads that the eviction guard allows. After the guard has kept as many
+    /// candidates as there are entries, the next candidate is evicted regardless.
+    fn evict_clock(&mut self) -> Option<(Key, CacheItem)> {
+        let mut kept = 0;
+        let index = loop {
+            let index = self.advance_clock_hand()?;
+            let (key, item) = self.map.get_index(index)?;
+            if kept >= self.map.len() || self.allows_eviction(key, &item.value) {
+                break index;
+            }
+            kept += 1;
+            self.clock_hand = index + 1;
+        };
+        let evicted = self.map.shift_remove_index(index);
+        // The next entry shifts into this slot, so the hand stays put,
+        // unless it would land on the slot the incoming entry takes
+        self.clock_hand = if index < self.map.len() { index } else { 0 };
+        evicted
+    }
+
+    /// Sweeps the CLOCK hand, decaying access counters, until it rests on an
+    /// unpinned entry whose counter is zero. Returns that entry's index, or `None`
+    /// if every entry is pinned.
+    fn advance_clock_hand(&mut self) -> Option<usize> {
+        // Every unpinned entry reaches frequency 0 within this many steps
+        let max_steps = (usize::from(CLOCK_MAX_FREQUENCY) + 1) * self.map.len() + 1;
+
+        for _ in 0..max_steps {
+            if self.clock_hand >= self.map.len() {
+                self.clock_hand = 0;
+            }
+
+            let (_, item) = self.map.get_index_mut(self.clock_hand)?;
+
+            if item.pinned {
+                self.clock_hand += 1;
+                continue;
+            }
+
+            if item.frequency == 0 {
+                return Some(self.clock_hand);
             }
+
+            item.frequency -= 1;
+            self.clock_hand += 1;
         }
 
-        self.map.insert(key.clone(), item.clone());
+        None
+    }
 
-        self.send_insert(key, item.value);
+    /// Consumes the cache and splits its live entries into two caches by predicate.
+    ///
+    /// Entries for which `f` returns `true` go to the first cache, the rest to the
+    /// second. Both caches keep the original capacity, default TTL and eviction
+    /// policy, and every entry keeps its TTL and creation time. Expired entries are
+    /// dropped. The event sender and persistence are not carried over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("user:1", "Alice");
+    /// cache.insert("session:1", "active");
+    ///
+    /// let (mut users, mut others) = cache.partition(|key, _| key.starts_with("user:"));
+    ///
+    /// assert_eq!(users.get("user:1"), Some(&"Alice".to_value()));
+    /// assert_eq!(others.get("session:1"), Some(&"active".to_value()));
+    /// assert_eq!(users.capacity(), 10);
+    /// ```
+    pub fn partition<F>(self, f: F) -> (Self, Self)
+    where
+        F: Fn(&str, &Value) -> bool,
+        S: Clone,
+    {
+        let mut matching = self.empty_like();
+        let mut rest = self.empty_like();
+
+        let now = self.now();
+
+        for (key, item) in self.map {
+            if item.is_expired_at(now) {
+                continue;
+            }
+
+            if f(&key, &item.value) {
+                matching.map.insert(key, item);
+            } else {
+                rest.map.insert(key, item);
+            }
+        }
+
+        (matching, rest)
+    }
+
+    /// Consumes the cache and returns its live entries sorted by key.
+    ///
+    /// Keys are compared byte-wise, like [`Order::Asc`] in [`list`](Self::list).
+    /// The values are owned, so the result outlives the cache. Expired entries are
+    /// dropped and nothing is removed from persistence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("b", 2);
+    /// cache.insert("a", 1);
+    ///
+    /// assert_eq!(
+    ///     cache.into_sorted_vec(),
+    ///     vec![("a".to_string(), 1.to_value()), ("b".to_string(), 2.to_value())]
+    /// );
+    /// ```
+    pub fn into_sorted_vec(self) -> Vec<(Key, Value)> {
+        let now = self.now();
+        let mut entries: Vec<(Key, Value)> = self
+            .map
+            .into_iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key, item.value))
+            .collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+
+    /// Creates an empty cache with the same capacity, default TTL and eviction policy.
+    fn empty_like(&self) -> Self
+    where
+        S: Clone,
+    {
+        let mut cache = Self::with_hasher(self.capacity, self.map.hasher().clone());
+        cache.default_ttl = self.default_ttl;
+        cache.ttl_jitter = self.ttl_jitter;
+        cache.stale_grace = self.stale_grace;
+        cache.eviction_policy = self.eviction_policy;
+        cache.key_separator = self.key_separator;
+        cache.key_normalizer = self.key_normalizer;
+        cache.value_transform = self.value_transform;
+        cache.insert_validator = self.insert_validator;
+        cache.track_access_counts = self.track_access_counts;
+        cache.clock = self.clock;
+        cache.eviction_batch_size = self.eviction_batch_size;
+        cache.on_evict = self.on_evict.clone();
+        cache.eviction_guard = self.eviction_guard.clone();
+        cache.resize_policy = self.resize_policy;
+        cache.watermarks = self.watermarks.clone();
+        cache
+    }
+
+    /// Returns a read-only copy of the live entries.
+    ///
+    /// The snapshot is unaffected by later inserts, removals and expirations,
+    /// so it can be read at leisure, e.g. after releasing a
+    /// [`SyncCache`](crate::SyncCache) lock. Copying is O(n) in the number of
+    /// entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("key", "before");
+    ///
+    /// let snapshot = cache.snapshot();
+    /// cache.insert("key", "after");
+    ///
+    /// assert_eq!(snapshot.get("key"), Some(&"before".to_value()));
+    /// assert_eq!(cache.get("key"), Some(&"after".to_value()));
+    /// ```
+    pub fn snapshot(&self) -> CacheSnapshot {
+        let now = self.now();
+        let entries = self
+            .map
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key.clone(), item.value.clone()))
+            .collect();
+
+        CacheSnapshot::new(entries, self.key_normalizer)
     }
 
-    /// Inserts a key-value pair with a specific TTL.
+    /// Lists cache entries with filtering, ordering, and pagination support.
     ///
-    /// The TTL overrides any default TTL set for the cache.
+    /// This method automatically cleans up expired items before returning results.
+    /// Results are always sorted by key (byte-wise, which for UTF-8 is Unicode code
+    /// point order, per `props.order`), regardless of insertion order, unless
+    /// [`ListProps::collate`] provides another ordering. The sorted key order is
+    /// cached between calls and only rebuilt after keys are added or removed, so
+    /// repeated listing of a stable cache is cheap.
     ///
     /// # Examples
     ///
     /// ```
     /// use quickleaf::Cache;
+    /// use quickleaf::{ListProps, Order};
+    /// use quickleaf::Filter;
     /// use quickleaf::valu3::traits::ToValueBehavior;
-    /// use std::time::Duration;
-    /// use std::thread;
     ///
     /// let mut cache = Cache::new(10);
-    /// cache.insert_with_ttl("session", "user123", Duration::from_millis(100));
+    /// cache.insert("apple", 1);
+    /// cache.insert("banana", 2);
+    /// cache.insert("apricot", 3);
     ///
-    /// assert!(cache.contains_key("session"));
-    /// thread::sleep(Duration::from_millis(150));
-    /// assert!(!cache.contains_key("session"));  
+    /// // List all items in ascending order
+    /// let props = ListProps::default().order(Order::Asc);
+    /// let items = cache.list(props).unwrap();
+    /// assert_eq!(items.len(), 3);
+    ///
+    /// // Filter items starting with "ap"
+    /// let props = ListProps::default()
+    ///     .filter(Filter::StartWith("ap".to_string()));
+    /// let filtered = cache.list(props).unwrap();
+    /// assert_eq!(filtered.len(), 2);  
     /// ```
-    pub fn insert_with_ttl<T, V>(&mut self, key: T, value: V, ttl: Duration)
+    pub fn list<T>(&mut self, props: T) -> Result<Vec<(Key, &Value)>, Error>
     where
-        T: Into<String> + Clone + AsRef<str>,
-        V: ToValueBehavior,
+        T: Into<ListProps>,
     {
-        let key = key.into();
-        let item = CacheItem::with_ttl(value.to_value(), ttl);
+        let props = props.into();
 
-        if let Some(existing_item) = self.map.get(&key) {
-            if existing_item.value == item.value {
-                return;
-            }
+        self.cleanup_expired();
+
+        if let Some(collator) = props.collator {
+            return self.list_collated(props, collator);
         }
 
-        if self.map.len() >= self.capacity && !self.map.contains_key(&key) {
-            if let Some((first_key, first_item)) = self.map.shift_remove_index(0) {
-                self.send_remove(first_key, first_item.value);
+        if self.sorted_keys_dirty {
+            if props.limit.saturating_mul(PARTIAL_SELECT_FACTOR) < self.map.len() {
+                return self.list_partial(props).map(|(page, _)| page);
             }
+            self.rebuild_sorted_keys();
         }
 
-        self.map.insert(key.clone(), item.clone());
+        let start = self.sorted_start(&props.start_after_key)?;
+        let keys = &self.sorted_keys;
 
-        self.send_insert(key.clone(), item.value.clone());
-
-        #[cfg(feature = "persist")]
-        if let Some(persist_path) = &self.persist_path {
-            if let Some(ttl_millis) = item.ttl_millis {
-                let _ = crate::sqlite_store::persist_item_with_ttl(
-                    persist_path,
-                    &key,
-                    &item.value,
-                    ttl_millis / 1000,
-                );
+        match props.order {
+            Order::Asc => {
+                let from = start.map_or(0, |index| index + 1);
+                self.resolve_order(keys[from..].iter(), props)
+            }
+            Order::Desc => {
+                let to = start.unwrap_or(keys.len());
+                self.resolve_order(keys[..to].iter().rev(), props)
             }
         }
     }
 
-    /// Retrieves a value from the cache by key.
+    /// Calls `f` with each entry [`list`](Self::list) would return, in the same
+    /// order, without collecting them into a `Vec`.
     ///
-    /// Returns `None` if the key doesn't exist or if the item has expired.
-    /// Expired items are automatically removed during this operation (lazy cleanup).
+    /// Filtering, ordering, `start_after_key` and `limit` apply as in `list`.
+    /// Returning [`ControlFlow::Break`] from `f` stops the iteration early. Useful
+    /// for walking very large result sets, e.g. with
+    /// [`ListProps::unlimited`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SortKeyNotFound`] if `props` starts after a missing key.
     ///
     /// # Examples
     ///
     /// ```
-    /// use quickleaf::Cache;
-    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use quickleaf::{Cache, ListProps};
+    /// use std::ops::ControlFlow;
     ///
-    /// let mut cache = Cache::new(10);
-    /// cache.insert("existing", "data");
+    /// let mut cache = Cache::new(100);
+    /// for i in 0..50 {
+    ///     cache.insert(format!("key{:02}", i), i);
+    /// }
     ///
-    /// assert_eq!(cache.get("existing"), Some(&"data".to_value()));
-    /// assert_eq!(cache.get("nonexistent"), None);
+    /// let mut visited = Vec::new();
+    /// cache
+    ///     .for_each_listed(ListProps::default().unlimited(), |key, _| {
+    ///         visited.push(key.clone());
+    ///         if visited.len() == 3 {
+    ///             ControlFlow::Break(())
+    ///         } else {
+    ///             ControlFlow::Continue(())
+    ///         }
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(visited, vec!["key00", "key01", "key02"]);
     /// ```
-    #[inline]
-    pub fn get(&mut self, key: &str) -> Option<&Value> {
-        let is_expired = match self.map.get(key) {
-            Some(item) => {
-                if let Some(ttl) = item.ttl_millis {
-                    (current_time_millis() - item.created_at) > ttl
-                } else {
-                    false
-                }
-            }
-            None => return None,
-        };
+    pub fn for_each_listed<T, F>(&mut self, props: T, mut f: F) -> Result<(), Error>
+    where
+        T: Into<ListProps>,
+        F: FnMut(&Key, &Value) -> ControlFlow<()>,
+    {
+        let props = props.into();
 
-        if is_expired {
-            if let Some(expired_item) = self.map.swap_remove(key) {
-                self.send_remove(key.to_string(), expired_item.value);
-            }
-            None
-        } else {
-            self.map.get(key).map(|item| &item.value)
-        }
-    }
+        self.cleanup_expired();
 
-    #[inline(always)]
-    pub fn get_list(&self) -> Vec<&Key> {
-        self.map.keys().collect()
-    }
+        if let Some(collator) = props.collator {
+            let keys = self.collated_keys(&props, collator)?;
+            self.visit_listed(keys.into_iter(), &props, f);
+            return Ok(());
+        }
 
-    pub fn get_map(&self) -> IndexMap<Key, &Value> {
-        self.map
-            .iter()
-            .filter(|(_, item)| !item.is_expired())
-            .map(|(key, item)| (key.clone(), &item.value))
-            .collect()
-    }
+        if self.sorted_keys_dirty {
+            self.rebuild_sorted_keys();
+        }
 
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
-        let should_remove = self.map.get(key).map_or(false, |item| item.is_expired());
+        let start = self.sorted_start(&props.start_after_key)?;
+        let keys = &self.sorted_keys;
 
-        if should_remove {
-            self.remove(key).ok();
-            None
-        } else {
-            self.map.get_mut(key).map(|item| &mut item.value)
+        match props.order {
+            Order::Asc => {
+                let from = start.map_or(0, |index| index + 1);
+                self.visit_listed(keys[from..].iter(), &props, &mut f);
+            }
+            Order::Desc => {
+                let to = start.unwrap_or(keys.len());
+                self.visit_listed(keys[..to].iter().rev(), &props, &mut f);
+            }
         }
-    }
-
-    #[inline(always)]
-    pub fn capacity(&self) -> usize {
-        self.capacity
-    }
 
-    #[inline]
-    pub fn set_capacity(&mut self, capacity: usize) {
-        self.capacity = capacity;
+        Ok(())
     }
 
-    pub fn remove(&mut self, key: &str) -> Result<(), Error> {
-        if let Some(item) = self.map.swap_remove(key) {
-            self.send_remove(key.to_string(), item.value);
-            Ok(())
-        } else {
-            Err(Error::KeyNotFound)
+    /// Position of `start_after_key` in the sorted key index, which must be up to date
+    fn sorted_start(&self, start_after_key: &StartAfter) -> Result<Option<usize>, Error> {
+        match start_after_key {
+            StartAfter::Key(key) => self
+                .sorted_keys
+                .binary_search(key)
+                .map(Some)
+                .map_err(|_| Error::SortKeyNotFound),
+            StartAfter::None => Ok(None),
         }
     }
 
-    pub fn clear(&mut self) {
-        self.map.clear();
-        self.send_clear();
-    }
-
-    #[inline(always)]
-    pub fn len(&self) -> usize {
-        self.map.len()
-    }
+    /// Calls `f` with the live entries among `keys` that pass the filter, up to the limit
+    fn visit_listed<'a, I, F>(&self, keys: I, props: &ListProps, mut f: F)
+    where
+        I: Iterator<Item = &'a Key>,
+        F: FnMut(&Key, &Value) -> ControlFlow<()>,
+    {
+        let now = self.now();
+        let mut fits = within_byte_budget(props.byte_budget);
+        let matching = keys
+            .filter_map(|key| self.map.get_key_value(key))
+            .filter(|(key, item)| !item.is_expired_at(now) && apply_filter_fast(key, &props.filter))
+            .take(props.limit)
+            .take_while(|(_, item)| fits(&item.value));
 
-    #[inline(always)]
-    pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+        for (key, item) in matching {
+            if f(key, &item.value).is_break() {
+                break;
+            }
+        }
     }
 
-    /// Checks if a key exists in the cache and hasn't expired.
+    /// Lists entries like [`list`](Self::list), cloning the values so the result
+    /// does not borrow the cache.
     ///
-    /// This method performs lazy cleanup of expired items.
+    /// Use this when the page must be kept while the cache is mutated again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SortKeyNotFound`] if `props` starts after a missing key.
     ///
     /// # Examples
     ///
     /// ```
-    /// use quickleaf::Cache;
+    /// use quickleaf::{Cache, ListProps};
     /// use quickleaf::valu3::traits::ToValueBehavior;
-    /// use std::time::Duration;
     ///
     /// let mut cache = Cache::new(10);
-    /// cache.insert("key", "value");
+    /// cache.insert("a", 1);
+    /// cache.insert("b", 2);
     ///
-    /// assert!(cache.contains_key("key"));
-    /// assert!(!cache.contains_key("nonexistent"));
+    /// let page = cache.list_owned(ListProps::default()).unwrap();
+    /// cache.clear();
     ///
-    /// // Test with TTL
-    /// cache.insert_with_ttl("temp", "data", Duration::from_millis(1));
-    /// std::thread::sleep(Duration::from_millis(10));
-    /// assert!(!cache.contains_key("temp"));  
+    /// assert_eq!(
+    ///     page,
+    ///     vec![("a".to_string(), 1.to_value()), ("b".to_string(), 2.to_value())]
+    /// );
     /// ```
-    pub fn contains_key(&mut self, key: &str) -> bool {
-        match self.map.get(key) {
-            Some(item) if item.is_expired() => {
-                self.remove(key).ok();
-                false
-            }
-            Some(_) => true,
-            None => false,
-        }
+    pub fn list_owned<T>(&mut self, props: T) -> Result<Vec<(Key, Value)>, Error>
+    where
+        T: Into<ListProps>,
+    {
+        let page = self.list(props)?;
+        Ok(page
+            .into_iter()
+            .map(|(key, value)| (key, value.clone()))
+            .collect())
     }
 
-    /// Manually removes all expired items from the cache.
+    /// Lists a page of entries like [`list`](Self::list), along with the total
+    /// number of live entries matching `props.filter`.
     ///
-    /// Returns the number of items that were removed.
-    /// This is useful for proactive cleanup, though the cache also performs lazy cleanup.
+    /// The total ignores `limit` and `start_after_key`, so it can back a
+    /// "showing 10 of 142" display. The page and the total come from the same scan.
     ///
     /// # Examples
     ///
     /// ```
-    /// use quickleaf::Cache;
-    /// use quickleaf::valu3::traits::ToValueBehavior;
-    /// use std::time::Duration;
-    /// use std::thread;
-    ///
-    /// let mut cache = Cache::new(10);
-    /// cache.insert_with_ttl("temp1", "data1", Duration::from_millis(10));
-    /// cache.insert_with_ttl("temp2", "data2", Duration::from_millis(10));
-    /// cache.insert("permanent", "data");
+    /// use quickleaf::{Cache, Filter, ListProps};
     ///
-    /// thread::sleep(Duration::from_millis(20));
+    /// let mut cache = Cache::new(100);
+    /// for i in 0..30 {
+    ///     cache.insert(format!("user:{:02}", i), i);
+    /// }
+    /// cache.insert("session:1", "x");
     ///
-    /// let removed = cache.cleanup_expired();
-    /// assert_eq!(removed, 2);  
-    /// assert_eq!(cache.len(), 1);  
+    /// let props = ListProps::default()
+    ///     .filter(Filter::StartWith("user:".to_string()))
+    ///     .limit(10);
+    /// let (page, total) = cache.list_with_count(props).unwrap();
+    /// assert_eq!(page.len(), 10);
+    /// assert_eq!(total, 30);
     /// ```
-    pub fn cleanup_expired(&mut self) -> usize {
-        let current_time = current_time_millis();
-        let mut expired_keys = Vec::with_capacity(self.map.len() / 4);
+    pub fn list_with_count<T>(&mut self, props: T) -> Result<CountedPage<'_>, Error>
+    where
+        T: Into<ListProps>,
+    {
+        let props = props.into();
 
-        for (key, item) in &self.map {
-            if let Some(ttl) = item.ttl_millis {
-                if (current_time - item.created_at) > ttl {
-                    expired_keys.push(key.clone());
-                }
-            }
-        }
+        self.cleanup_expired();
 
-        let removed_count = expired_keys.len();
+        if let Some(collator) = props.collator {
+            let (keys, from) = self.collated_order(&props, collator)?;
+            return Ok(self.page_and_count(keys.into_iter(), from, &props));
+        }
 
-        for key in expired_keys {
-            if let Some(item) = self.map.swap_remove(&key) {
-                self.send_remove(key, item.value);
+        if self.sorted_keys_dirty {
+            if props.limit.saturating_mul(PARTIAL_SELECT_FACTOR) < self.map.len() {
+                return self.list_partial(props);
             }
+            self.rebuild_sorted_keys();
         }
 
-        removed_count
-    }
+        let start = self.sorted_start(&props.start_after_key)?;
+        let keys = &self.sorted_keys;
 
-    #[inline]
-    pub fn set_default_ttl(&mut self, ttl: Option<Duration>) {
-        self.default_ttl = ttl;
+        Ok(match props.order {
+            Order::Asc => {
+                let from = start.map_or(0, |index| index + 1);
+                self.page_and_count(keys.iter(), from, &props)
+            }
+            Order::Desc => {
+                let from = start.map_or(0, |index| keys.len() - index);
+                self.page_and_count(keys.iter().rev(), from, &props)
+            }
+        })
     }
 
-    #[inline(always)]
-    pub fn get_default_ttl(&self) -> Option<Duration> {
-        self.default_ttl
+    /// Walks `keys` (in list order) once, counting every live match and collecting
+    /// the page from position `from` on, for [`list_with_count`](Self::list_with_count)
+    fn page_and_count<'a, I>(&self, keys: I, from: usize, props: &ListProps) -> CountedPage<'_>
+    where
+        I: Iterator<Item = &'a Key>,
+    {
+        let now = self.now();
+        let mut fits = within_byte_budget(props.byte_budget);
+        let mut filling = props.limit > 0;
+        let mut page = Vec::new();
+        let mut total = 0;
+
+        for (index, key) in keys.enumerate() {
+            let Some((key, item)) = self.map.get_key_value(key) else {
+                continue;
+            };
+            if item.is_expired_at(now) || !apply_filter_fast(key, &props.filter) {
+                continue;
+            }
+
+            total += 1;
+            if filling && index >= from {
+                filling = fits(&item.value);
+                if filling {
+                    page.push((key.clone(), &item.value));
+                    filling = page.len() < props.limit;
+                }
+            }
+        }
+
+        (page, total)
     }
 
-    /// Lists cache entries with filtering, ordering, and pagination support.
+    /// Counts the live entries whose key matches `filter`.
     ///
-    /// This method automatically cleans up expired items before returning results.
+    /// Scans the entries once, without sorting, allocating or removing expired
+    /// items. `Filter::None` counts every live entry.
     ///
     /// # Examples
     ///
     /// ```
-    /// use quickleaf::Cache;
-    /// use quickleaf::{ListProps, Order};
-    /// use quickleaf::Filter;
-    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use quickleaf::{Cache, Filter};
     ///
     /// let mut cache = Cache::new(10);
-    /// cache.insert("apple", 1);
-    /// cache.insert("banana", 2);
-    /// cache.insert("apricot", 3);
+    /// cache.insert("user:1", "alice");
+    /// cache.insert("user:2", "bob");
+    /// cache.insert("session:1", "x");
     ///
-    /// // List all items in ascending order
-    /// let props = ListProps::default().order(Order::Asc);
-    /// let items = cache.list(props).unwrap();
-    /// assert_eq!(items.len(), 3);
-    ///
-    /// // Filter items starting with "ap"
-    /// let props = ListProps::default()
-    ///     .filter(Filter::StartWith("ap".to_string()));
-    /// let filtered = cache.list(props).unwrap();
-    /// assert_eq!(filtered.len(), 2);  
+    /// assert_eq!(cache.count(&Filter::StartWith("user:".to_string())), 2);
+    /// assert_eq!(cache.count(&Filter::None), 3);
     /// ```
-    pub fn list<T>(&mut self, props: T) -> Result<Vec<(Key, &Value)>, Error>
-    where
-        T: Into<ListProps>,
-    {
-        let props = props.into();
+    pub fn count(&self, filter: &Filter) -> usize {
+        let now = self.now();
+        self.map
+            .iter()
+            .filter(|(key, item)| !item.is_expired_at(now) && apply_filter_fast(key, filter))
+            .count()
+    }
 
-        self.cleanup_expired();
+    /// Rebuilds the cached sorted key order used by `list`.
+    fn rebuild_sorted_keys(&mut self) {
+        self.sorted_keys.clear();
+        self.sorted_keys.extend(self.map.keys().cloned());
+        self.sorted_keys.sort_unstable();
+        self.sorted_keys_dirty = false;
+    }
 
-        let mut keys: Vec<String> = self.map.keys().cloned().collect();
-        keys.sort();
+    /// Lists entries sorted by a custom collator, without the cached key index
+    fn list_collated(
+        &self,
+        props: ListProps,
+        collator: Collator,
+    ) -> Result<Vec<(Key, &Value)>, Error> {
+        let keys = self.collated_keys(&props, collator)?;
+        self.resolve_order(keys.into_iter(), props)
+    }
 
-        match props.order {
-            Order::Asc => self.resolve_order(keys.iter(), props),
-            Order::Desc => self.resolve_order(keys.iter().rev(), props),
-        }
+    /// Keys sorted by `collator` per `props.order`, starting after `props.start_after_key`
+    fn collated_keys(&self, props: &ListProps, collator: Collator) -> Result<Vec<&Key>, Error> {
+        let (mut keys, from) = self.collated_order(props, collator)?;
+        keys.drain(..from);
+        Ok(keys)
     }
 
-    fn resolve_order<'a, I>(
+    /// Every key sorted by `collator` per `props.order`, with the position just
+    /// after `props.start_after_key`
+    fn collated_order(
         &self,
-        mut list_iter: I,
-        props: ListProps,
-    ) -> Result<Vec<(Key, &Value)>, Error>
-    where
-        I: Iterator<Item = &'a String>,
-    {
-        if let StartAfter::Key(ref key) = props.start_after_key {
-            list_iter
-                .find(|k| k == &key)
-                .ok_or(Error::SortKeyNotFound)?;
+        props: &ListProps,
+        collator: Collator,
+    ) -> Result<(Vec<&Key>, usize), Error> {
+        let mut keys: Vec<&Key> = self.map.keys().collect();
+        keys.sort_by(|a, b| collator(a, b).then_with(|| a.cmp(b)));
+        if let Order::Desc = props.order {
+            keys.reverse();
         }
 
-        let mut list = Vec::new();
-        
-        // Early return if limit is 0
-        if props.limit == 0 {
-            return Ok(list);
-        }
+        let from = match props.start_after_key {
+            StartAfter::Key(ref key) => {
+                keys.iter()
+                    .position(|candidate| *candidate == key)
+                    .ok_or(Error::SortKeyNotFound)?
+                    + 1
+            }
+            StartAfter::None => 0,
+        };
 
-        let mut count = 0;
+        Ok((keys, from))
+    }
 
-        for k in list_iter {
-            if let Some(item) = self.map.get(k) {
-                if item.is_expired() {
-                    continue;
+    /// Fast path for `list` when only a small page of a large cache is requested:
+    /// filters first and keeps the best `limit` keys instead of sorting every key.
+    /// Also returns how many live entries match the filter, counted in the same scan.
+    fn list_partial(&self, props: ListProps) -> Result<CountedPage<'_>, Error> {
+        let start_after = match props.start_after_key {
+            StartAfter::Key(ref key) => {
+                if !self.map.contains_key(key.as_str()) {
+                    return Err(Error::SortKeyNotFound);
                 }
+                Some(key.as_str())
+            }
+            StartAfter::None => None,
+        };
 
-                let filtered = if apply_filter_fast(k, &props.filter) {
-                    Some((k.clone(), &item.value))
-                } else {
-                    None
-                };
-
-                if let Some(item) = filtered {
-                    list.push(item);
-                    count += 1;
-                    if count >= props.limit {
-                        break;
-                    }
-                }
+        let now = self.now();
+        let mut total = 0;
+        let candidates = self.map.iter().filter(|(key, item)| {
+            if item.is_expired_at(now) || !apply_filter_fast(key, &props.filter) {
+                return false;
             }
-        }
+            total += 1;
 
-        Ok(list)
+            match (start_after, &props.order) {
+                (Some(start), Order::Asc) => key.as_str() > start,
+                (Some(start), Order::Desc) => key.as_str() < start,
+                (None, _) => true,
+            }
+        });
+
+        let keys: Vec<&Key> = match props.order {
+            Order::Asc => select_smallest(candidates.map(|(key, _)| key), props.limit),
+            Order::Desc => select_smallest(candidates.map(|(key, _)| Reverse(key)), props.limit)
+                .into_iter()
+                .map(|Reverse(key)| key)
+                .collect(),
+        };
+
+        let mut fits = within_byte_budget(props.byte_budget);
+        let page = keys
+            .into_iter()
+            .map(|key| (key.clone(), &self.map[key].value))
+            .take_while(|(_, value)| fits(value))
+            .collect();
+        Ok((page, total))
+    }
+
+    fn resolve_order<'a, I>(
+        &self,
+        list_iter: I,
+        props: ListProps,
+    ) -> Result<Vec<(Key, &Value)>, Error>
+    where
+        I: Iterator<Item = &'a String>,
+    {
+        let now = self.now();
+        let mut fits = within_byte_budget(props.byte_budget);
+
+        // `take` stops pulling keys once `limit` entries matched, and pulls none for 0
+        Ok(list_iter
+            .filter_map(|key| self.map.get_key_value(key))
+            .filter(|(key, item)| !item.is_expired_at(now) && apply_filter_fast(key, &props.filter))
+            .take(props.limit)
+            .take_while(|(_, item)| fits(&item.value))
+            .map(|(key, item)| (key.clone(), &item.value))
+            .collect())
     }
 }
@@ -0,0 +1,37 @@
+//! Time source for TTL bookkeeping.
+//!
+//! This module defines the [`Clock`] type a cache uses to timestamp items and
+//! decide when they expire.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Function returning the current time in milliseconds since the UNIX epoch.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, Clock};
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::time::Duration;
+///
+/// static NOW: AtomicU64 = AtomicU64::new(0);
+///
+/// let clock: Clock = || NOW.load(Ordering::Relaxed);
+/// let mut cache = Cache::builder(10).clock(clock).build();
+///
+/// cache.insert_with_ttl("key", "value", Duration::from_millis(100));
+/// assert!(cache.contains_key("key"));
+///
+/// NOW.store(101, Ordering::Relaxed);
+/// assert!(!cache.contains_key("key"));
+/// ```
+pub type Clock = fn() -> u64;
+
+/// The default clock, backed by `SystemTime`.
+#[inline(always)]
+pub fn system_clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
@@ -0,0 +1,125 @@
+//! Compound keys built from multiple segments.
+//!
+//! This module provides the [`CompoundKey`] type used by
+//! [`Cache::insert_tuple`](crate::Cache::insert_tuple) and friends, along with
+//! the escaping rules that keep joined keys unambiguous.
+
+use crate::cache::Key;
+
+/// Character used to escape separators (and itself) inside key segments.
+pub(crate) const KEY_ESCAPE: char = '\\';
+
+/// A key made of ordered segments, joined with the cache's key separator.
+///
+/// Occurrences of the separator or of `\` inside a segment are escaped with a
+/// `\`, so segments containing the separator never produce ambiguous keys.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, CompoundKey};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+///
+/// let mut cache = Cache::new(10);
+///
+/// cache.insert_tuple(("user", "42"), "alice");
+/// cache.insert_tuple(["org", "acme", "team"], "core");
+///
+/// assert_eq!(cache.get("user:42"), Some(&"alice".to_value()));
+/// let key = CompoundKey::from(vec!["org", "acme", "team"]);
+/// assert_eq!(key.segments(), ["org", "acme", "team"]);
+/// assert_eq!(cache.get_tuple(key), Some(&"core".to_value()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundKey {
+    segments: Vec<String>,
+}
+
+impl CompoundKey {
+    /// Returns the key segments.
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// Joins the segments with `separator`, escaping it inside each segment.
+    pub(crate) fn join(&self, separator: char) -> Key {
+        let mut key = String::new();
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            if index > 0 {
+                key.push(separator);
+            }
+
+            for ch in segment.chars() {
+                if ch == separator || ch == KEY_ESCAPE {
+                    key.push(KEY_ESCAPE);
+                }
+                key.push(ch);
+            }
+        }
+
+        key
+    }
+}
+
+/// Splits a key produced by [`CompoundKey::join`] back into its segments.
+pub(crate) fn split_key(key: &str, separator: char) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = key.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == KEY_ESCAPE {
+            current.push(chars.next().unwrap_or(KEY_ESCAPE));
+        } else if ch == separator {
+            segments.push(std::mem::take(&mut current));
+        } else {
+            current.push(ch);
+        }
+    }
+
+    segments.push(current);
+    segments
+}
+
+impl From<(&str, &str)> for CompoundKey {
+    fn from((first, second): (&str, &str)) -> Self {
+        Self {
+            segments: vec![first.to_string(), second.to_string()],
+        }
+    }
+}
+
+impl From<(&str, &str, &str)> for CompoundKey {
+    fn from((first, second, third): (&str, &str, &str)) -> Self {
+        Self {
+            segments: vec![first.to_string(), second.to_string(), third.to_string()],
+        }
+    }
+}
+
+impl From<&[&str]> for CompoundKey {
+    fn from(segments: &[&str]) -> Self {
+        Self {
+            segments: segments.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for CompoundKey {
+    fn from(segments: [&str; N]) -> Self {
+        Self::from(&segments[..])
+    }
+}
+
+impl From<Vec<&str>> for CompoundKey {
+    fn from(segments: Vec<&str>) -> Self {
+        Self::from(segments.as_slice())
+    }
+}
+
+impl From<Vec<String>> for CompoundKey {
+    fn from(segments: Vec<String>) -> Self {
+        Self { segments }
+    }
+}
@@ -2,10 +2,16 @@
 //!
 //! This module defines the error types that can occur during cache operations.
 
+#[cfg(feature = "persist")]
+use crate::sqlite_store::PersistError;
 use std::fmt::{Debug, Display};
 
 /// Errors that can occur during cache operations.
 ///
+/// Some variants only exist with a feature enabled, such as `Persist` with
+/// `persist`, so the enum is non-exhaustive and matches on it need a wildcard
+/// arm.
+///
 /// # Examples
 ///
 /// ```
@@ -22,6 +28,7 @@ use std::fmt::{Debug, Display};
 /// }
 /// ```
 #[derive(PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     /// The specified sort key was not found during list operations.
     ///
@@ -85,6 +92,83 @@ pub enum Error {
     /// }
     /// ```
     KeyNotFound,
+
+    /// The stored value could not be converted to the requested type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Error;
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("name", "Alice");
+    ///
+    /// match cache.try_get::<i64>("name") {
+    ///     Some(Err(Error::TypeMismatch)) => println!("Not a number"),
+    ///     _ => panic!("Expected TypeMismatch error"),
+    /// }
+    /// ```
+    TypeMismatch,
+
+    /// The insert validator rejected the key or value, for the given reason.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error, Value};
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.set_insert_validator(Some(|_: &str, _: &Value| Err("read-only".to_string())));
+    ///
+    /// match cache.try_insert("key", "value") {
+    ///     Err(Error::Rejected(reason)) => println!("Rejected: {}", reason),
+    ///     _ => panic!("Expected Rejected error"),
+    /// }
+    /// ```
+    Rejected(String),
+
+    /// Memory for more entries could not be allocated, or the requested size
+    /// overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(10);
+    ///
+    /// match cache.try_reserve(usize::MAX) {
+    ///     Err(Error::AllocationFailed) => println!("Out of memory"),
+    ///     _ => panic!("Expected AllocationFailed error"),
+    /// }
+    /// ```
+    AllocationFailed,
+
+    /// A batch needs more room than the cache can free without evicting pinned
+    /// entries or entries the batch itself updates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::{Cache, Error};
+    ///
+    /// let mut cache = Cache::new(1);
+    ///
+    /// let batch = vec![("a".to_string(), 1), ("b".to_string(), 2)];
+    /// match cache.insert_transaction(batch) {
+    ///     Err(Error::CapacityExceeded) => println!("Batch too large"),
+    ///     _ => panic!("Expected CapacityExceeded error"),
+    /// }
+    /// ```
+    CapacityExceeded,
+
+    /// Reading or writing the SQLite database failed.
+    ///
+    /// Returned by [`Cache::verify_persistence`](crate::Cache::verify_persistence);
+    /// the [`PersistError`] says which operation failed and why.
+    #[cfg(feature = "persist")]
+    Persist(PersistError),
 }
 
 impl Display for Error {
@@ -95,6 +179,12 @@ impl Display for Error {
             Error::SortKeyExists => write!(f, "Sort key exists"),
             Error::TableAlreadyExists => write!(f, "Table already exists"),
             Error::KeyNotFound => write!(f, "Key not found"),
+            Error::TypeMismatch => write!(f, "Value type mismatch"),
+            Error::Rejected(reason) => write!(f, "Insert rejected: {}", reason),
+            Error::AllocationFailed => write!(f, "Allocation failed"),
+            Error::CapacityExceeded => write!(f, "Capacity exceeded"),
+            #[cfg(feature = "persist")]
+            Error::Persist(error) => write!(f, "{}", error),
         }
     }
 }
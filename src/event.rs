@@ -4,6 +4,9 @@
 //! when cache operations occur, such as insertions, removals, or cache clearing.
 
 use crate::cache::Key;
+use std::ops::Deref;
+use std::sync::mpsc::{IntoIter, Iter, Receiver, Sender};
+use std::sync::{Arc, Weak};
 use valu3::value::Value;
 
 /// Represents different types of cache events.
@@ -123,6 +126,105 @@ pub struct EventData {
     pub value: Value,
 }
 
+/// What a cache does when its bounded event channel is full.
+///
+/// Only applies to senders registered with
+/// [`Cache::set_bounded_event`](crate::Cache::set_bounded_event); an unbounded
+/// [`Sender`](std::sync::mpsc::Sender) never fills up.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, EventOverflow};
+/// use std::sync::mpsc::sync_channel;
+///
+/// let (tx, _rx) = sync_channel(1);
+/// let mut cache = Cache::new(10);
+/// cache.set_bounded_event(tx, EventOverflow::Drop);
+///
+/// cache.insert("a", 1);
+/// cache.insert("b", 2); // the channel is full, so this event is dropped
+/// assert_eq!(cache.dropped_events_count(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventOverflow {
+    /// Wait until the receiver makes room.
+    #[default]
+    Block,
+    /// Discard the event and count it in
+    /// [`Cache::dropped_events_count`](crate::Cache::dropped_events_count).
+    Drop,
+}
+
+/// Receiver returned by [`Cache::watch`](crate::Cache::watch).
+///
+/// Dereferences to the underlying [`Receiver`], so `recv`, `try_recv` and
+/// `try_iter` work as usual. Dropping it unregisters the watcher.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, Event};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+///
+/// let mut cache = Cache::new(10);
+/// let watcher = cache.watch("job");
+/// cache.insert("job", "queued");
+///
+/// assert_eq!(
+///     watcher.try_recv().unwrap(),
+///     Event::insert("job".to_string(), "queued".to_value())
+/// );
+/// ```
+#[derive(Debug)]
+pub struct KeyWatcher {
+    receiver: Receiver<Event>,
+    _alive: Arc<()>,
+}
+
+impl KeyWatcher {
+    /// Creates a watcher and the sender/liveness pair the cache keeps for it
+    pub(crate) fn channel() -> (Sender<Event>, Weak<()>, Self) {
+        let (tx, receiver) = std::sync::mpsc::channel();
+        let alive = Arc::new(());
+        let weak = Arc::downgrade(&alive);
+        (
+            tx,
+            weak,
+            Self {
+                receiver,
+                _alive: alive,
+            },
+        )
+    }
+}
+
+impl Deref for KeyWatcher {
+    type Target = Receiver<Event>;
+
+    fn deref(&self) -> &Receiver<Event> {
+        &self.receiver
+    }
+}
+
+impl IntoIterator for KeyWatcher {
+    type Item = Event;
+    type IntoIter = IntoIter<Event>;
+
+    fn into_iter(self) -> IntoIter<Event> {
+        self.receiver.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a KeyWatcher {
+    type Item = Event;
+    type IntoIter = Iter<'a, Event>;
+
+    fn into_iter(self) -> Iter<'a, Event> {
+        self.receiver.iter()
+    }
+}
+
 impl Event {
     /// Creates a new insert event.
     ///
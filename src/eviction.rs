@@ -0,0 +1,89 @@
+//! Eviction policies for bounded caches.
+//!
+//! This module defines the strategies a cache can use to pick a victim when an
+//! insert would exceed its capacity, or to grow its capacity instead.
+
+/// Enum for specifying how entries are evicted when the cache is full.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, EvictionPolicy};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+///
+/// let mut cache = Cache::new(2);
+/// cache.set_eviction_policy(EvictionPolicy::Clock);
+///
+/// cache.insert("hot", 1);
+/// cache.insert("cold", 2);
+///
+/// // Accessing "hot" gives it a second chance
+/// cache.get("hot");
+///
+/// cache.insert("new", 3);
+/// assert_eq!(cache.get("hot"), Some(&1.to_value()));
+/// assert_eq!(cache.get("cold"), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Evict the oldest entry in insertion order.
+    #[default]
+    Lru,
+    /// Approximate LRU using the CLOCK algorithm.
+    ///
+    /// Each entry keeps a small saturating access counter that is bumped on
+    /// reads. A rotating hand sweeps the entries, decaying counters as it passes
+    /// and evicting the first entry whose counter has reached zero. Reads never
+    /// reorder the underlying map, so `get` stays O(1).
+    Clock,
+}
+
+/// Enum for specifying whether a full cache grows instead of evicting.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, ResizePolicy};
+///
+/// let mut cache = Cache::new(2);
+/// cache.set_resize_policy(ResizePolicy::Grow { factor: 2.0, max: 4 });
+///
+/// for i in 0..6 {
+///     cache.insert(format!("key{}", i), i);
+/// }
+///
+/// assert_eq!(cache.capacity(), 4);
+/// assert_eq!(cache.len(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ResizePolicy {
+    /// Keep the capacity and evict when full.
+    #[default]
+    Fixed,
+    /// Multiply the capacity by `factor` (at least +1) whenever an insert would
+    /// evict, up to `max` entries. Once `max` is reached, entries are evicted.
+    Grow {
+        /// Growth factor, must be greater than 1.
+        factor: f64,
+        /// Capacity the cache never grows past.
+        max: usize,
+    },
+}
+
+/// Utilization threshold reported to a [`Cache::on_utilization`](crate::Cache::on_utilization) callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Watermark {
+    /// Utilization rose to the high watermark.
+    High,
+    /// Utilization fell back to the low watermark.
+    Low,
+}
+
+/// Decision returned by a [`Cache::guard_eviction`](crate::Cache::guard_eviction) guard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictDecision {
+    /// Let the entry be evicted.
+    Evict,
+    /// Keep the entry and try the next candidate.
+    Keep,
+}
@@ -77,6 +77,18 @@ pub enum Filter {
     /// ```
     StartAndEndWith(String, String),
 
+    /// Filter keys whose length in bytes is between `min` and `max`, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Filter;
+    ///
+    /// let filter = Filter::KeyLenRange { min: 1, max: 64 };
+    /// // This will match keys like "a" or "user_123", but not "" or very long keys
+    /// ```
+    KeyLenRange { min: usize, max: usize },
+
     /// No filtering applied - returns all items.
     ///
     /// # Examples
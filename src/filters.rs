@@ -22,6 +22,7 @@ pub fn apply_filter_fast(key: &str, filter: &Filter) -> bool {
         Filter::StartWith(prefix) => key.starts_with(prefix),
         Filter::EndWith(suffix) => key.ends_with(suffix),
         Filter::StartAndEndWith(prefix, suffix) => key.starts_with(prefix) && key.ends_with(suffix),
+        Filter::KeyLenRange { min, max } => (*min..=*max).contains(&key.len()),
     }
 }
 
@@ -66,4 +67,27 @@ mod tests {
             &Filter::StartWith("goodbye".to_string())
         ));
     }
+
+    #[test]
+    fn test_apply_filter_fast_key_len_range() {
+        let filter = Filter::KeyLenRange { min: 3, max: 5 };
+        assert!(!apply_filter_fast("ab", &filter));
+        assert!(apply_filter_fast("abc", &filter));
+        assert!(apply_filter_fast("abcd", &filter));
+        assert!(apply_filter_fast("abcde", &filter));
+        assert!(!apply_filter_fast("abcdef", &filter));
+
+        assert!(apply_filter_fast(
+            "",
+            &Filter::KeyLenRange { min: 0, max: 0 }
+        ));
+        assert!(!apply_filter_fast(
+            "a",
+            &Filter::KeyLenRange { min: 0, max: 0 }
+        ));
+        assert!(!apply_filter_fast(
+            "abc",
+            &Filter::KeyLenRange { min: 5, max: 3 }
+        ));
+    }
 }
@@ -12,6 +12,7 @@
 //! - **Persistent storage** using SQLite (optional feature)
 //! - Custom error handling
 //! - Event notifications for cache operations
+//! - Optional `tracing` instrumentation for evictions, expiry cleanup and persistence failures
 //! - Support for generic values using [valu3](https:
 //!
 //! ## Installation
@@ -324,9 +325,17 @@
 //! - **Efficient Storage**: Uses SQLite with optimized indexes for performance
 //! - **Seamless Integration**: Works with all existing Quickleaf features
 
+#[macro_use]
+mod logging;
+
+mod backing_store;
+mod builder;
 mod cache;
+mod clock;
+mod compound_key;
 mod error;
 mod event;
+mod eviction;
 mod filter;
 pub mod filters;
 mod list_props;
@@ -335,19 +344,38 @@ mod list_props;
 mod persist_tests;
 pub mod prelude;
 mod quickleaf;
+mod snapshot;
 #[cfg(feature = "persist")]
 mod sqlite_store;
+mod sync_cache;
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
+#[cfg(feature = "tracing")]
+mod tracing_tests;
+#[cfg(test)]
 mod ttl_tests;
+#[cfg(feature = "persist")]
+mod value_json;
 
-pub use cache::{Cache, CacheItem};
+pub use backing_store::{BackingStore, StoreError, WriteMode};
+pub use builder::CacheBuilder;
+pub use cache::{
+    Cache, CacheDiff, CacheItem, GetResult, InsertValidator, KeyNormalizer, SimReport, TtlStats,
+    ValueTransform,
+};
+pub use clock::Clock;
+pub use compound_key::CompoundKey;
 pub use error::Error;
-pub use event::{Event, EventData};
+pub use event::{Event, EventData, EventOverflow, KeyWatcher};
+pub use eviction::{EvictDecision, EvictionPolicy, ResizePolicy, Watermark};
 pub use filter::Filter;
-pub use list_props::{ListProps, Order, StartAfter};
+pub use list_props::{Collator, ListProps, Order, StartAfter};
 pub use quickleaf::Quickleaf;
+pub use snapshot::CacheSnapshot;
+#[cfg(feature = "persist")]
+pub use sqlite_store::{PersistError, PersistOperation, PersistReport, SqliteStore};
 pub use std::time::Duration;
+pub use sync_cache::SyncCache;
 pub use valu3;
 pub use valu3::value::Value;
@@ -4,9 +4,18 @@
 //! are retrieved, ordered, filtered, and paginated.
 
 use crate::filter::Filter;
+use std::cmp::Ordering;
+use valu3::value::Value;
+
+/// Comparison function used to sort keys in [`ListProps::collate`].
+pub type Collator = fn(&str, &str) -> Ordering;
 
 /// Enum for specifying sort order when listing cache entries.
 ///
+/// Keys are compared byte-wise by default, which for UTF-8 strings is the same
+/// as Unicode code point order: `"Z"` sorts before `"a"`, and `"é"` after `"z"`.
+/// Use [`ListProps::collate`] for any other ordering.
+///
 /// # Examples
 ///
 /// ```
@@ -143,8 +152,13 @@ pub struct ListProps {
     pub filter: Filter,
     /// Sort order for results.
     pub order: Order,
-    /// Maximum number of results to return.
+    /// Maximum number of results to return (`usize::MAX` for no limit).
     pub limit: usize,
+    /// Custom key ordering; `None` sorts by Unicode code point.
+    pub collator: Option<Collator>,
+    /// Maximum total serialized size of the returned values, in bytes (`None`
+    /// for no budget).
+    pub byte_budget: Option<usize>,
 }
 
 impl Default for ListProps {
@@ -154,6 +168,8 @@ impl Default for ListProps {
             filter: Filter::None,
             order: Order::Asc,
             limit: 10,
+            collator: None,
+            byte_budget: None,
         }
     }
 }
@@ -176,6 +192,8 @@ impl ListProps {
             filter: Filter::None,
             order: Order::Asc,
             limit: 10,
+            collator: None,
+            byte_budget: None,
         }
     }
 
@@ -273,6 +291,86 @@ impl ListProps {
         self.limit = limit;
         self
     }
+
+    /// Removes the limit so every matching entry is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::ListProps;
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    ///
+    /// let mut cache = Cache::new(50);
+    /// for i in 0..30 {
+    ///     cache.insert(format!("key_{:02}", i), i);
+    /// }
+    ///
+    /// let props = ListProps::default().unlimited();
+    /// let results = cache.list(props).unwrap();
+    /// assert_eq!(results.len(), 30);
+    /// ```
+    pub fn unlimited(mut self) -> Self {
+        self.limit = usize::MAX;
+        self
+    }
+
+    /// Stops the listing before the serialized size of the returned values
+    /// exceeds `bytes`, e.g. to cap an RPC response.
+    ///
+    /// A value's size is the length of its inline JSON form. Entries are returned
+    /// in order while their running total fits; the listing ends at the first one
+    /// that does not, so the next page can start after the last returned key. The
+    /// first entry is always returned, even if it alone is over budget, so paging
+    /// never stalls. Combined with [`limit`](Self::limit), whichever is reached
+    /// first ends the listing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::ListProps;
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("a", "x".repeat(100));
+    /// cache.insert("b", "x".repeat(100));
+    /// cache.insert("c", "x".repeat(100));
+    ///
+    /// let props = ListProps::default().byte_budget(250);
+    /// let results = cache.list(props).unwrap();
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    pub fn byte_budget(mut self, bytes: usize) -> Self {
+        self.byte_budget = Some(bytes);
+        self
+    }
+
+    /// Sorts keys with `collator` instead of by Unicode code point.
+    ///
+    /// `start_after_key` and `order` follow the collated order. Listing with a
+    /// collator sorts the matching keys on every call instead of using the
+    /// cache's cached key index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::ListProps;
+    /// use quickleaf::Cache;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("Banana", 1);
+    /// cache.insert("apple", 2);
+    /// cache.insert("cherry", 3);
+    ///
+    /// let props = ListProps::default().collate(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+    /// let results = cache.list(props).unwrap();
+    /// let keys: Vec<_> = results.iter().map(|(k, _)| k.as_str()).collect();
+    /// assert_eq!(keys, vec!["apple", "Banana", "cherry"]);
+    /// ```
+    pub fn collate(mut self, collator: Collator) -> Self {
+        self.collator = Some(collator);
+        self
+    }
 }
 
 impl From<Filter> for ListProps {
@@ -282,6 +380,8 @@ impl From<Filter> for ListProps {
             filter,
             order: Order::Asc,
             limit: 10,
+            collator: None,
+            byte_budget: None,
         }
     }
 }
@@ -293,6 +393,8 @@ impl From<Order> for ListProps {
             filter: Filter::None,
             order,
             limit: 10,
+            collator: None,
+            byte_budget: None,
         }
     }
 }
@@ -304,6 +406,30 @@ impl From<StartAfter> for ListProps {
             filter: Filter::None,
             order: Order::Asc,
             limit: 10,
+            collator: None,
+            byte_budget: None,
         }
     }
 }
+
+/// Length of the inline JSON form of `value`, the size counted by [`ListProps::byte_budget`]
+fn value_size_bytes(value: &Value) -> usize {
+    use valu3::prelude::*;
+
+    value.to_json(JsonMode::Inline).len()
+}
+
+/// Predicate that accepts values while their running serialized size fits in
+/// `budget`, always accepting the first one (see [`ListProps::byte_budget`])
+pub(crate) fn within_byte_budget(budget: Option<usize>) -> impl FnMut(&Value) -> bool {
+    let mut used: Option<usize> = None;
+    move |value| {
+        let Some(budget) = budget else {
+            return true;
+        };
+        let total = used.unwrap_or(0).saturating_add(value_size_bytes(value));
+        let fits = used.is_none() || total <= budget;
+        used = Some(total);
+        fits
+    }
+}
@@ -0,0 +1,25 @@
+//! Internal logging macros.
+//!
+//! When the `tracing` feature is enabled these forward to the `tracing` crate.
+//! Otherwise debug messages are compiled out and warnings fall back to stderr.
+
+/// Emits a debug-level message (no-op without the `tracing` feature).
+macro_rules! log_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+/// Emits a warning (printed to stderr without the `tracing` feature).
+#[allow(unused_macros)]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+        #[cfg(not(feature = "tracing"))]
+        eprintln!($($arg)*);
+    }};
+}
@@ -5,6 +5,7 @@
 mod tests {
     use crate::cache::Cache;
     use crate::event::Event;
+    use crate::sqlite_store::PersistOperation;
     use crate::valu3::traits::ToValueBehavior;
     use std::fs;
     use std::path::Path;
@@ -375,7 +376,7 @@ mod tests {
             );
             assert_eq!(
                 cache.get("key\"with\"double"),
-                Some(&"value\\\"with\\\"double".to_value())
+                Some(&"value\"with\"double".to_value())
             );
         }
 
@@ -436,4 +437,831 @@ mod tests {
 
         cleanup_test_db(&db_path);
     }
+
+    #[test]
+    fn test_persist_errors_surface_write_failures() {
+        let db_path = test_db_path("persist_errors");
+        cleanup_test_db(&db_path);
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert!(cache.persist_errors().is_empty());
+
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TRIGGER reject_writes BEFORE INSERT ON cache_items
+                 BEGIN SELECT RAISE(FAIL, 'simulated write failure'); END;",
+            )
+            .unwrap();
+            drop(conn);
+
+            cache.insert("key1", "value1");
+
+            thread::sleep(Duration::from_millis(200));
+
+            let errors = cache.persist_errors();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].operation, PersistOperation::Insert);
+            assert_eq!(errors[0].key.as_deref(), Some("key1"));
+            assert!(errors[0].message.contains("simulated write failure"));
+
+            assert!(cache.persist_errors().is_empty());
+            assert_eq!(cache.get("key1"), Some(&"value1".to_value()));
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persistence_healthy() {
+        assert!(Cache::new(10).persistence_healthy());
+
+        let db_path = test_db_path("persistence_healthy");
+        cleanup_test_db(&db_path);
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+        cache.insert("key1", "value1");
+        thread::sleep(Duration::from_millis(100));
+        assert!(cache.persistence_healthy());
+
+        // The database disappears from under the cache
+        cleanup_test_db(&db_path);
+        assert!(!cache.persistence_healthy());
+        assert!(!Path::new(&db_path).exists());
+    }
+
+    #[test]
+    fn test_persistence_unhealthy_when_write_lock_is_held() {
+        let db_path = test_db_path("persistence_write_lock");
+        cleanup_test_db(&db_path);
+
+        {
+            let cache = Cache::with_persist(&db_path, 10).unwrap();
+            thread::sleep(Duration::from_millis(100));
+
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch("BEGIN EXCLUSIVE;").unwrap();
+
+            let started = std::time::Instant::now();
+            assert!(!cache.persistence_healthy());
+            assert!(started.elapsed() < Duration::from_secs(1));
+
+            conn.execute_batch("ROLLBACK;").unwrap();
+            assert!(cache.persistence_healthy());
+
+            let rows: i64 = conn
+                .query_row("SELECT COUNT(*) FROM cache_items", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(rows, 0);
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persistence_toggle() {
+        let db_path = test_db_path("persistence_toggle");
+        cleanup_test_db(&db_path);
+
+        let stored_keys = || {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            let mut stmt = conn
+                .prepare("SELECT key FROM cache_items ORDER BY key")
+                .unwrap();
+            let keys: Vec<String> = stmt
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .map(|key| key.unwrap())
+                .collect();
+            keys
+        };
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.persist_path(), Some(Path::new(&db_path)));
+            cache.insert("a", 1);
+            cache.insert("b", 2);
+
+            // Queued writes are flushed before disable_persistence returns
+            cache.disable_persistence();
+            assert_eq!(cache.persist_path(), None);
+            assert_eq!(stored_keys(), vec!["a", "b"]);
+
+            cache.insert("c", 3);
+            cache.remove("a").unwrap();
+            thread::sleep(Duration::from_millis(100));
+            assert_eq!(stored_keys(), vec!["a", "b"]);
+            assert_eq!(cache.len(), 2);
+
+            cache.enable_persistence(&db_path).unwrap();
+            assert_eq!(cache.persist_path(), Some(Path::new(&db_path)));
+            cache.insert("d", 4);
+            cache.disable_persistence();
+            assert_eq!(stored_keys(), vec!["b", "c", "d"]);
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_map_values() {
+        let db_path = test_db_path("persist_map_values");
+        cleanup_test_db(&db_path);
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            cache.insert("a", 1);
+            cache.insert("b", 2);
+
+            let changed = cache.map_values(|_, value| {
+                let n: i64 = value.to_string().parse().unwrap();
+                *value = (n * 2).to_value();
+            });
+            assert_eq!(changed, 2);
+
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.get("a"), Some(&2.to_value()));
+            assert_eq!(cache.get("b"), Some(&4.to_value()));
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_purges_expired_rows_under_constant_load() {
+        use crate::backing_store::CLEANUP_EVENT_INTERVAL;
+
+        let db_path = test_db_path("persist_purge_under_load");
+        cleanup_test_db(&db_path);
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.busy_timeout(Duration::from_secs(5)).unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        conn.execute(
+            "INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+             VALUES ('expired', '\"gone\"', ?1, 1, ?2)",
+            rusqlite::params![now - 10, now - 5],
+        )
+        .unwrap();
+
+        let count_expired = || -> i64 {
+            conn.query_row(
+                "SELECT COUNT(*) FROM cache_items WHERE key = 'expired'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(count_expired(), 1);
+
+        // Keep the writer busy (well under its 100ms idle timeout) so only the
+        // event-count purge can remove the row
+        let mut purged_after = None;
+        for i in 0..CLEANUP_EVENT_INTERVAL * 4 {
+            cache.insert("counter", i as i64);
+            thread::sleep(Duration::from_millis(1));
+
+            if i % 16 == 0 && count_expired() == 0 {
+                purged_after = Some(i);
+                break;
+            }
+        }
+
+        assert!(purged_after.is_some(), "expired row was never purged");
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_verify_persistence_reports_discrepancies() {
+        let db_path = test_db_path("verify_persistence");
+        cleanup_test_db(&db_path);
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+        cache.insert("kept", "same");
+        cache.insert("changed", "original");
+        cache.insert("lost", "value");
+
+        let wait_until_clean = |cache: &Cache| {
+            for _ in 0..50 {
+                if cache.verify_persistence().unwrap().is_clean() {
+                    return true;
+                }
+                thread::sleep(Duration::from_millis(20));
+            }
+            false
+        };
+        assert!(
+            wait_until_clean(&cache),
+            "writes never reached the database"
+        );
+
+        // Tamper with the database behind the cache's back
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.busy_timeout(Duration::from_secs(5)).unwrap();
+        conn.execute_batch(
+            "UPDATE cache_items SET value = '\"tampered\"' WHERE key = 'changed';
+             DELETE FROM cache_items WHERE key = 'lost';
+             INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+             VALUES ('ghost', '\"boo\"', 0, NULL, NULL);",
+        )
+        .unwrap();
+
+        let report = cache.verify_persistence().unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.in_memory_only, vec!["lost".to_string()]);
+        assert_eq!(report.db_only, vec!["ghost".to_string()]);
+        assert_eq!(report.value_mismatches, vec!["changed".to_string()]);
+
+        // Writing the entries again brings the database back in sync (inserting
+        // an unchanged value is a no-op, so remove them first)
+        for key in ["changed", "lost"] {
+            let value = cache.get(key).cloned().unwrap();
+            cache.remove(key).unwrap();
+            cache.insert(key, value);
+        }
+        cache.insert("ghost", "boo");
+        assert!(wait_until_clean(&cache), "report never became clean");
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_verify_persistence_leaves_database_untouched() {
+        let db_path = test_db_path("verify_read_only");
+        cleanup_test_db(&db_path);
+
+        let cache = Cache::with_persist(&db_path, 10).unwrap();
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.busy_timeout(Duration::from_secs(5)).unwrap();
+        conn.execute(
+            "INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+             VALUES ('expired', '\"old\"', 0, 1, 1)",
+            [],
+        )
+        .unwrap();
+
+        let report = cache.verify_persistence().unwrap();
+        assert!(report.is_clean());
+
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM cache_items", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(rows, 1, "verify_persistence deleted the expired row");
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_verify_persistence_returns_persist_error() {
+        let db_path = test_db_path("verify_missing_db");
+        cleanup_test_db(&db_path);
+
+        let cache = Cache::with_persist(&db_path, 10).unwrap();
+        thread::sleep(Duration::from_millis(100));
+        cleanup_test_db(&db_path);
+
+        match cache.verify_persistence() {
+            Err(crate::Error::Persist(error)) => {
+                assert_eq!(error.operation, PersistOperation::Verify)
+            }
+            other => panic!("expected a persist error, got {:?}", other.map(|_| ())),
+        }
+        assert!(!Path::new(&db_path).exists(), "verify created the database");
+    }
+
+    #[test]
+    fn test_verify_persistence_without_persistence_is_clean() {
+        let mut cache = Cache::new(10);
+        cache.insert("key", "value");
+
+        assert_eq!(
+            cache.verify_persistence().unwrap(),
+            crate::sqlite_store::PersistReport::default()
+        );
+    }
+
+    #[test]
+    fn test_persist_pinned_keys() {
+        let db_path = test_db_path("persist_pinned");
+        cleanup_test_db(&db_path);
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            cache.insert("config", "v1");
+            cache.insert("other", "v2");
+            assert!(cache.pin("config"));
+            cache.insert("config", "v3");
+
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 2).unwrap();
+            assert!(cache.is_pinned("config"));
+            assert!(!cache.is_pinned("other"));
+
+            cache.insert("new", "v4");
+            assert_eq!(cache.get("config"), Some(&"v3".to_value()));
+            assert!(!cache.contains_key("other"));
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_migrates_database_without_pinned_column() {
+        let db_path = test_db_path("persist_pinned_migration");
+        cleanup_test_db(&db_path);
+
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE cache_items (
+                     key TEXT PRIMARY KEY NOT NULL,
+                     value TEXT NOT NULL,
+                     created_at INTEGER NOT NULL,
+                     ttl_seconds INTEGER,
+                     expires_at INTEGER
+                 );
+                 INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+                 VALUES ('old', '42', 0, NULL, NULL);",
+            )
+            .unwrap();
+        }
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+        assert!(cache.contains_key("old"));
+        assert!(!cache.is_pinned("old"));
+        assert!(cache.pin("old"));
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_insert_durable_writes_synchronously() {
+        let db_path = test_db_path("insert_durable");
+        cleanup_test_db(&db_path);
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+        cache.insert_with_ttl("session", "token", Duration::from_secs(60));
+        cache.pin("session");
+        cache.insert_durable("session", "refreshed").unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let (ttl_seconds, pinned): (Option<i64>, bool) = conn
+            .query_row(
+                "SELECT ttl_seconds, pinned FROM cache_items WHERE key = 'session'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(ttl_seconds, None);
+        assert!(pinned);
+
+        cache.insert_durable("balance", 100).unwrap();
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM cache_items WHERE key = 'balance'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+
+        cache.set_insert_validator(Some(|key: &str, _: &crate::Value| match key {
+            "blocked" => Err("read-only".to_string()),
+            _ => Ok(()),
+        }));
+        assert_eq!(
+            cache.insert_durable("blocked", 1),
+            Err(crate::Error::Rejected("read-only".to_string()))
+        );
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM cache_items WHERE key = 'blocked'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 0);
+        assert!(!cache.contains_key("blocked"));
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_round_trips_escaped_characters() {
+        let db_path = test_db_path("persist_escaped_chars");
+        cleanup_test_db(&db_path);
+
+        let entries = [
+            ("line\nbreak \"quoted\"", "multi\nline \"value\""),
+            ("back\\slash\ttab", "back\\slash\ttab"),
+            ("control\u{1}\u{7f}", "null\u{0}byte"),
+        ];
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            for (key, value) in entries {
+                cache.insert(key, value);
+            }
+            cache.insert_with_ttl("ttl\n\"key\"", "ttl\n\"value\"", Duration::from_secs(60));
+
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.len(), 4);
+            for (key, value) in entries {
+                assert_eq!(cache.get(key), Some(&value.to_value()), "key {:?}", key);
+            }
+            assert_eq!(
+                cache.get("ttl\n\"key\""),
+                Some(&"ttl\n\"value\"".to_value())
+            );
+            assert!(cache.verify_persistence().unwrap().is_clean());
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_round_trips_nested_strings() {
+        let db_path = test_db_path("persist_nested_strings");
+        cleanup_test_db(&db_path);
+
+        let mut fields = std::collections::HashMap::new();
+        fields.insert("quote \"name\"".to_string(), "a\nb".to_value());
+        fields.insert("unicode".to_string(), "caf\u{e9} \u{1f600}\u{1}".to_value());
+        let nested = vec![
+            "line\nbreak \"quoted\"".to_value(),
+            vec!["back\\slash\ttab"].to_value(),
+            fields.to_value(),
+            7.to_value(),
+        ]
+        .to_value();
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            cache.insert("nested", nested.clone());
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.get("nested"), Some(&nested));
+            assert!(cache.verify_persistence().unwrap().is_clean());
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_reset_ttl_all() {
+        let db_path = test_db_path("persist_reset_ttl_all");
+        cleanup_test_db(&db_path);
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            cache.insert("permanent", 1);
+            cache.insert_with_ttl("short", 2, Duration::from_secs(5));
+            assert_eq!(cache.reset_ttl_all(Some(Duration::from_secs(3600))), 2);
+
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        {
+            let cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.get_ttl("permanent"), Some(Duration::from_secs(3600)));
+            assert_eq!(cache.get_ttl("short"), Some(Duration::from_secs(3600)));
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_touch_many() {
+        let db_path = test_db_path("persist_touch_many");
+        cleanup_test_db(&db_path);
+
+        {
+            let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+            cache.insert_with_ttl("touched", 1, Duration::from_secs(60));
+            cache.insert_with_ttl("untouched", 2, Duration::from_secs(60));
+
+            // Stored creation times have one-second resolution
+            thread::sleep(Duration::from_millis(1100));
+            assert_eq!(cache.touch_many(&["touched"]), 1);
+            thread::sleep(Duration::from_millis(200));
+        }
+
+        {
+            let cache = Cache::with_persist(&db_path, 10).unwrap();
+            let touched = cache.get_created_at("touched").unwrap();
+            let untouched = cache.get_created_at("untouched").unwrap();
+            assert!(touched > untouched);
+            assert_eq!(cache.get_ttl("touched"), Some(Duration::from_secs(60)));
+        }
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn test_persist_compresses_large_values() {
+        use crate::{SqliteStore, WriteMode};
+
+        let db_path = test_db_path("persist_compress");
+        cleanup_test_db(&db_path);
+
+        let large = "quickleaf ".repeat(10_000);
+        {
+            let store = SqliteStore::open(&db_path).unwrap().compress_values_over(8);
+            let mut cache = Cache::with_backing_store(10, store, WriteMode::WriteThrough).unwrap();
+            cache.insert("large", large.as_str());
+            cache.insert("number", 1_234_567_890);
+            cache.insert("small", "tiny");
+        }
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let stored: Vec<(String, i64, String)> = conn
+            .prepare("SELECT key, length(value), value_format FROM cache_items ORDER BY key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(stored[0].0, "large");
+        assert!(stored[0].1 < large.len() as i64 / 10);
+        assert_eq!(stored[0].2, "text+deflate");
+        assert_eq!(stored[1].2, "json+deflate");
+        assert_eq!(stored[2], ("small".to_string(), 4, "text".to_string()));
+
+        let store = SqliteStore::open(&db_path).unwrap();
+        let mut cache = Cache::with_backing_store(10, store, WriteMode::WriteThrough).unwrap();
+        assert_eq!(cache.get("large"), Some(&large.to_value()));
+        assert_eq!(cache.get("number"), Some(&1_234_567_890.to_value()));
+        assert_eq!(cache.get("small"), Some(&"tiny".to_value()));
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn test_builder_compresses_persisted_values() {
+        let db_path = test_db_path("persist_builder_compress");
+        cleanup_test_db(&db_path);
+
+        let large = "quickleaf ".repeat(10_000);
+        {
+            let mut cache = crate::CacheBuilder::new(10)
+                .compress_values_over(1024)
+                .build_with_persist(&db_path)
+                .unwrap();
+            cache.insert("large", large.as_str());
+            cache.insert("small", "tiny");
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let formats: Vec<(String, String)> = conn
+            .prepare("SELECT key, value_format FROM cache_items ORDER BY key")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            formats,
+            vec![
+                ("large".to_string(), "text+deflate".to_string()),
+                ("small".to_string(), "text".to_string()),
+            ]
+        );
+
+        let mut cache = Cache::with_persist(&db_path, 10).unwrap();
+        assert_eq!(cache.get("large"), Some(&large.to_value()));
+        assert_eq!(cache.get("small"), Some(&"tiny".to_value()));
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    #[cfg(feature = "compress")]
+    fn test_insert_durable_compresses_large_values() {
+        let db_path = test_db_path("insert_durable_compress");
+        cleanup_test_db(&db_path);
+
+        let large = "quickleaf ".repeat(10_000);
+        let mut cache = crate::CacheBuilder::new(10)
+            .compress_values_over(1024)
+            .build_with_persist(&db_path)
+            .unwrap();
+        cache.insert_durable("large", large.as_str()).unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let format: String = conn
+            .query_row(
+                "SELECT value_format FROM cache_items WHERE key = 'large'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(format, "text+deflate");
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_rejects_newer_schema_version() {
+        let db_path = test_db_path("persist_schema_newer");
+        cleanup_test_db(&db_path);
+
+        {
+            let _cache = Cache::with_persist(&db_path, 10).unwrap();
+        }
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch("PRAGMA user_version = 99;").unwrap();
+        }
+
+        let error = Cache::with_persist(&db_path, 10).err().unwrap();
+        assert!(
+            error.to_string().contains("schema version 99"),
+            "unexpected error: {}",
+            error
+        );
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 99);
+
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_migrates_unversioned_database() {
+        let db_path = test_db_path("persist_schema_v0");
+        cleanup_test_db(&db_path);
+
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 3600;
+        {
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE cache_items (
+                     key TEXT PRIMARY KEY NOT NULL,
+                     value TEXT NOT NULL,
+                     created_at INTEGER NOT NULL,
+                     ttl_seconds INTEGER,
+                     expires_at INTEGER
+                 );
+                 INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+                 VALUES ('permanent', '42', 0, NULL, NULL);",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO cache_items (key, value, created_at, ttl_seconds, expires_at)
+                 VALUES ('session', 'true', ?, 7200, ?)",
+                rusqlite::params![expires_at as i64 - 7200, expires_at as i64],
+            )
+            .unwrap();
+        }
+
+        {
+            let cache = Cache::with_persist(&db_path, 10).unwrap();
+            assert_eq!(cache.len(), 2);
+            assert_eq!(cache.get_ttl("permanent"), None);
+            assert_eq!(cache.get_ttl("session"), Some(Duration::from_secs(7200)));
+        }
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 1);
+
+        let rows: Vec<(String, String, bool, String)> = conn
+            .prepare("SELECT key, value, pinned, value_format FROM cache_items ORDER BY key")
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "permanent".to_string(),
+                    "42".to_string(),
+                    false,
+                    "json".to_string()
+                ),
+                (
+                    "session".to_string(),
+                    "true".to_string(),
+                    false,
+                    "json".to_string()
+                ),
+            ]
+        );
+
+        // Reopening an up-to-date database is a no-op
+        drop(conn);
+        let cache = Cache::with_persist(&db_path, 10).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        drop(cache);
+        cleanup_test_db(&db_path);
+    }
+
+    #[test]
+    fn test_persist_load_resolves_normalized_key_collisions() {
+        let db_path = test_db_path("persist_key_collisions");
+        cleanup_test_db(&db_path);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        {
+            let _ = Cache::with_persist(&db_path, 10).unwrap();
+            let conn = rusqlite::Connection::open(&db_path).unwrap();
+            let rows = [
+                ("user:1", "older", now - 20),
+                ("User:1", "old", now - 10),
+                (" USER:1 ", "newest", now),
+                ("B", "upper", now),
+                ("b", "lower", now),
+            ];
+            for (key, value, created_at) in rows {
+                conn.execute(
+                    "INSERT INTO cache_items (key, value, created_at, value_format)
+                     VALUES (?, ?, ?, 'text')",
+                    rusqlite::params![key, value, created_at],
+                )
+                .unwrap();
+            }
+        }
+
+        let open = || {
+            crate::CacheBuilder::new(10)
+                .normalize_keys(|key| key.trim().to_lowercase())
+                .build_with_persist(&db_path)
+                .unwrap()
+        };
+
+        {
+            let mut cache = open();
+            assert_eq!(cache.len(), 2);
+            assert_eq!(cache.get("User:1"), Some(&"newest".to_value()));
+            assert_eq!(cache.get("b"), Some(&"lower".to_value()));
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let keys: Vec<String> = conn
+            .prepare("SELECT key FROM cache_items ORDER BY key")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(keys, vec!["b".to_string(), "user:1".to_string()]);
+
+        {
+            let mut cache = open();
+            assert_eq!(cache.len(), 2);
+            assert_eq!(cache.get("user:1"), Some(&"newest".to_value()));
+            assert_eq!(cache.get("b"), Some(&"lower".to_value()));
+        }
+
+        cleanup_test_db(&db_path);
+    }
 }
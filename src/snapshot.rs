@@ -0,0 +1,136 @@
+//! Point-in-time views of a cache.
+//!
+//! This module provides [`CacheSnapshot`], a read-only copy of the live entries
+//! of a [`Cache`](crate::Cache) returned by
+//! [`Cache::snapshot`](crate::Cache::snapshot).
+
+use crate::cache::{Key, KeyNormalizer};
+use crate::error::Error;
+use crate::filters::apply_filter_fast;
+use crate::list_props::{within_byte_budget, ListProps, Order, StartAfter};
+use valu3::value::Value;
+
+/// A read-only copy of the live entries of a cache at one point in time.
+///
+/// Later changes to the cache, including expiration, are not reflected, and
+/// reading a snapshot never mutates anything. This makes it suitable for long
+/// iterations over a [`SyncCache`](crate::SyncCache) without holding its lock.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::{Cache, ListProps};
+/// use quickleaf::valu3::traits::ToValueBehavior;
+///
+/// let mut cache = Cache::new(10);
+/// cache.insert("a", 1);
+/// cache.insert("b", 2);
+///
+/// let snapshot = cache.snapshot();
+/// cache.remove("a").unwrap();
+///
+/// assert_eq!(snapshot.len(), 2);
+/// assert_eq!(snapshot.get("a"), Some(&1.to_value()));
+/// assert_eq!(snapshot.list(ListProps::default()).unwrap().len(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CacheSnapshot {
+    /// Entries sorted by key
+    entries: Vec<(Key, Value)>,
+    key_normalizer: Option<KeyNormalizer>,
+}
+
+impl CacheSnapshot {
+    /// Creates a snapshot from `entries`, which must have unique keys.
+    /// Lookups normalize keys with `key_normalizer`, like the cache did.
+    pub(crate) fn new(
+        mut entries: Vec<(Key, Value)>,
+        key_normalizer: Option<KeyNormalizer>,
+    ) -> Self {
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        Self {
+            entries,
+            key_normalizer,
+        }
+    }
+
+    /// Returns the value stored for `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Returns `true` if the snapshot holds `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Value)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Lists entries with filtering, ordering and pagination, like
+    /// [`Cache::list`](crate::Cache::list).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SortKeyNotFound`] if `props` starts after a key that is
+    /// not in the snapshot.
+    pub fn list<T>(&self, props: T) -> Result<Vec<(Key, &Value)>, Error>
+    where
+        T: Into<ListProps>,
+    {
+        let props = props.into();
+
+        let mut entries: Vec<&(Key, Value)> = self.entries.iter().collect();
+        if let Some(collator) = props.collator {
+            entries.sort_by(|(a, _), (b, _)| collator(a, b).then_with(|| a.cmp(b)));
+        }
+        if let Order::Desc = props.order {
+            entries.reverse();
+        }
+
+        let from = match props.start_after_key {
+            StartAfter::Key(ref key) => {
+                entries
+                    .iter()
+                    .position(|(candidate, _)| candidate == key)
+                    .ok_or(Error::SortKeyNotFound)?
+                    + 1
+            }
+            StartAfter::None => 0,
+        };
+
+        let mut fits = within_byte_budget(props.byte_budget);
+        Ok(entries[from..]
+            .iter()
+            .filter(|(key, _)| apply_filter_fast(key, &props.filter))
+            .take(props.limit)
+            .take_while(|(_, value)| fits(value))
+            .map(|(key, value)| (key.clone(), value))
+            .collect())
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        let normalized;
+        let key = match self.key_normalizer {
+            Some(normalize) => {
+                normalized = normalize(key);
+                normalized.as_str()
+            }
+            None => key,
+        };
+
+        self.entries
+            .binary_search_by(|(candidate, _)| candidate.as_str().cmp(key))
+            .ok()
+    }
+}
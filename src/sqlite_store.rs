@@ -5,32 +5,199 @@
 
 #![cfg(feature = "persist")]
 
-use crate::cache::CacheItem;
-use crate::event::Event;
+use crate::backing_store::{run_writer, BackingStore, StoreError, StoreOp};
+use crate::cache::{CacheItem, Key};
 use crate::valu3::prelude::*;
 use crate::valu3::traits::ToValueBehavior;
-use rusqlite::{params, Connection, Result};
+use crate::value_json;
+use rusqlite::types::{Type, Value as SqlValue};
+use rusqlite::{params, Connection, OpenFlags, Result, TransactionBehavior};
+use std::fmt::Display;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The persistence operation that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PersistOperation {
+    /// Opening or initializing the database for the background writer.
+    Open,
+    /// Writing an inserted item.
+    Insert,
+    /// Deleting a removed item.
+    Remove,
+    /// Deleting all items after a clear.
+    Clear,
+    /// Deleting expired items in the background.
+    Cleanup,
+    /// Reading the database to compare it with the cache.
+    Verify,
+}
+
+impl Display for PersistOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PersistOperation::Open => write!(f, "open"),
+            PersistOperation::Insert => write!(f, "insert"),
+            PersistOperation::Remove => write!(f, "remove"),
+            PersistOperation::Clear => write!(f, "clear"),
+            PersistOperation::Cleanup => write!(f, "cleanup"),
+            PersistOperation::Verify => write!(f, "verify"),
+        }
+    }
+}
+
+/// A failed write to the SQLite persistence layer.
+///
+/// Errors are collected in the background and can be drained with
+/// [`Cache::persist_errors`](crate::Cache::persist_errors).
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "persist")]
+/// # {
+/// use quickleaf::Cache;
+///
+/// let mut cache = Cache::with_persist("data/cache.db", 1000).unwrap();
+/// cache.insert("key", "value");
+///
+/// for error in cache.persist_errors() {
+///     eprintln!("Failed to {} {:?}: {}", error.operation, error.key, error.message);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PersistError {
+    /// The operation that failed.
+    pub operation: PersistOperation,
+    /// The key involved in the operation, if any.
+    pub key: Option<Key>,
+    /// The underlying error message.
+    pub message: String,
+}
+
+impl PersistError {
+    pub(crate) fn new(operation: PersistOperation, key: Option<Key>, error: impl Display) -> Self {
+        Self {
+            operation,
+            key,
+            message: error.to_string(),
+        }
+    }
+
+    fn from_op(op: &StoreOp, error: impl Display) -> Self {
+        match op {
+            StoreOp::Put(key, _) => Self::new(PersistOperation::Insert, Some(key.clone()), error),
+            StoreOp::Delete(key) => Self::new(PersistOperation::Remove, Some(key.clone()), error),
+            StoreOp::Clear => Self::new(PersistOperation::Clear, None, error),
+            StoreOp::PurgeExpired => Self::new(PersistOperation::Cleanup, None, error),
+        }
+    }
+}
 
-/// Extended event structure for persistence
+impl Display for PersistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.key {
+            Some(key) => write!(
+                f,
+                "Persist {} failed for '{}': {}",
+                self.operation, key, self.message
+            ),
+            None => write!(f, "Persist {} failed: {}", self.operation, self.message),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+/// Differences between a cache and its SQLite database.
+///
+/// Returned by [`Cache::verify_persistence`](crate::Cache::verify_persistence).
+/// Keys in each list are sorted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PersistReport {
+    /// Live keys in memory that are missing from the database.
+    pub in_memory_only: Vec<Key>,
+    /// Keys in the database that are missing from memory.
+    pub db_only: Vec<Key>,
+    /// Keys present in both whose values differ.
+    pub value_mismatches: Vec<Key>,
+}
+
+impl PersistReport {
+    /// Returns `true` if no discrepancies were found.
+    pub fn is_clean(&self) -> bool {
+        self.in_memory_only.is_empty()
+            && self.db_only.is_empty()
+            && self.value_mismatches.is_empty()
+    }
+}
+
+/// Report a persistence failure, falling back to a warning when nobody is listening
+fn report_error(errors: &Sender<PersistError>, error: PersistError) {
+    log_debug!("{}", error);
+    if let Err(unsent) = errors.send(error) {
+        log_warn!("{}", unsent.0);
+    }
+}
+
+/// Channel through which persistence failures reach the cache owner
 #[derive(Clone, Debug)]
-pub(crate) struct PersistentEvent {
-    pub event: Event,
-    pub timestamp: SystemTime,
+pub(crate) struct PersistErrorChannel {
+    sender: Sender<PersistError>,
+    receiver: Arc<Mutex<Receiver<PersistError>>>,
 }
 
-impl PersistentEvent {
-    pub fn new(event: Event) -> Self {
+impl PersistErrorChannel {
+    pub fn new() -> Self {
+        let (sender, receiver) = channel();
         Self {
-            event,
-            timestamp: SystemTime::now(),
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+        }
+    }
+
+    pub fn sender(&self) -> Sender<PersistError> {
+        self.sender.clone()
+    }
+
+    pub fn drain(&self) -> Vec<PersistError> {
+        match self.receiver.lock() {
+            Ok(receiver) => receiver.try_iter().collect(),
+            Err(_) => Vec::new(),
         }
     }
 }
 
+/// `value_format` of values stored as JSON
+const JSON_FORMAT: &str = "json";
+
+/// `value_format` of strings stored verbatim
+const TEXT_FORMAT: &str = "text";
+
+/// `value_format` of values stored as DEFLATE-compressed JSON
+#[cfg(feature = "compress")]
+const DEFLATE_JSON_FORMAT: &str = "json+deflate";
+
+/// `value_format` of strings stored as DEFLATE-compressed text
+#[cfg(feature = "compress")]
+const DEFLATE_TEXT_FORMAT: &str = "text+deflate";
+
+/// How long [`check_writable`] waits for another connection's write lock, kept
+/// short so a health probe never stalls behind the background writer
+const HEALTH_CHECK_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Version of the `cache_items` layout created by [`init_database`], stored in
+/// `PRAGMA user_version`.
+///
+/// Version 0 databases were created before versioning, without the `pinned`
+/// and `value_format` columns. Bump this and add a step to [`migrate`] whenever
+/// the layout changes.
+const SCHEMA_VERSION: i64 = 1;
+
 /// Initialize SQLite database with schema
 fn init_database(conn: &Connection) -> Result<()> {
     conn.execute(
@@ -39,11 +206,15 @@ fn init_database(conn: &Connection) -> Result<()> {
             value TEXT NOT NULL,
             created_at INTEGER NOT NULL,
             ttl_seconds INTEGER,
-            expires_at INTEGER
+            expires_at INTEGER,
+            pinned INTEGER NOT NULL DEFAULT 0,
+            value_format TEXT NOT NULL DEFAULT 'json'
         )",
         [],
     )?;
 
+    migrate(conn)?;
+
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_expires 
          ON cache_items(expires_at) 
@@ -60,6 +231,142 @@ fn init_database(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Upgrade a database created by an older version to [`SCHEMA_VERSION`].
+///
+/// Databases from newer versions are rejected rather than read with a layout
+/// that may no longer match.
+fn migrate(conn: &Connection) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let version: i64 = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version > SCHEMA_VERSION {
+        return Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+            Some(format!(
+                "database schema version {} is newer than supported version {}",
+                version, SCHEMA_VERSION
+            )),
+        ));
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    if version < 1 {
+        add_column_if_missing(&tx, "pinned", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&tx, "value_format", "TEXT NOT NULL DEFAULT 'json'")?;
+    }
+
+    tx.execute_batch(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))?;
+    tx.commit()
+}
+
+/// Add a column to `cache_items` unless the table already has it
+fn add_column_if_missing(conn: &Connection, name: &str, definition: &str) -> Result<()> {
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cache_items') WHERE name = ?",
+        params![name],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE cache_items ADD COLUMN {} {}", name, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+/// Serialize a value for the `value` column, returning the text and its `value_format`.
+///
+/// Top-level strings are stored verbatim; anything else goes through
+/// [`value_json`], which escapes and restores nested strings losslessly.
+fn encode_value(value: &Value) -> (String, &'static str) {
+    if value.is_string() {
+        (value.as_string(), TEXT_FORMAT)
+    } else {
+        (value_json::to_json(value), JSON_FORMAT)
+    }
+}
+
+/// Deserialize the `value` column written by [`encode_value`]
+fn decode_value(text: String, format: &str) -> Value {
+    if format == TEXT_FORMAT {
+        return text.to_value();
+    }
+    value_json::from_json(&text)
+        .or_else(|| Value::json_to_value(&text).ok())
+        .unwrap_or_else(|| text.to_value())
+}
+
+/// Compress the encoded `text` if it is longer than `compress_over` bytes,
+/// returning the `value` column contents and their `value_format`
+#[cfg(feature = "compress")]
+fn compress_value(
+    text: String,
+    format: &'static str,
+    compress_over: Option<usize>,
+) -> (SqlValue, &'static str) {
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    if compress_over.filter(|limit| text.len() > *limit).is_none() {
+        return (SqlValue::Text(text), format);
+    }
+
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    let compressed = encoder
+        .write_all(text.as_bytes())
+        .and_then(|_| encoder.finish());
+    match compressed {
+        Ok(bytes) if format == TEXT_FORMAT => (SqlValue::Blob(bytes), DEFLATE_TEXT_FORMAT),
+        Ok(bytes) => (SqlValue::Blob(bytes), DEFLATE_JSON_FORMAT),
+        Err(_) => (SqlValue::Text(text), format),
+    }
+}
+
+#[cfg(not(feature = "compress"))]
+fn compress_value(
+    text: String,
+    format: &'static str,
+    _compress_over: Option<usize>,
+) -> (SqlValue, &'static str) {
+    (SqlValue::Text(text), format)
+}
+
+/// Decompress a `value` column written by [`compress_value`] back to its text
+/// and uncompressed `value_format`
+#[cfg(feature = "compress")]
+fn decompress_value(
+    bytes: &[u8],
+    format: &str,
+) -> Result<(String, &'static str), Box<dyn std::error::Error + Send + Sync>> {
+    use flate2::read::DeflateDecoder;
+    use std::io::Read;
+
+    let base_format = match format {
+        DEFLATE_TEXT_FORMAT => TEXT_FORMAT,
+        DEFLATE_JSON_FORMAT => JSON_FORMAT,
+        other => return Err(format!("unknown compressed value format '{}'", other).into()),
+    };
+
+    let mut text = String::new();
+    DeflateDecoder::new(bytes).read_to_string(&mut text)?;
+    Ok((text, base_format))
+}
+
+#[cfg(not(feature = "compress"))]
+fn decompress_value(
+    _bytes: &[u8],
+    format: &str,
+) -> Result<(String, &'static str), Box<dyn std::error::Error + Send + Sync>> {
+    Err(format!(
+        "value stored as '{}' requires the `compress` feature",
+        format
+    )
+    .into())
+}
+
 /// Read cache items from SQLite database
 pub(crate) fn items_from_db(
     path: &Path,
@@ -70,26 +377,72 @@ pub(crate) fn items_from_db(
     let _ = conn.execute_batch("PRAGMA journal_mode = DELETE;");
     let _ = conn.execute_batch("PRAGMA busy_timeout = 5000;");
 
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    Ok(load_items(&conn)?)
+}
+
+/// Seconds since UNIX_EPOCH, as stored in the database
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
 
+/// Delete expired rows, then read the remaining ones
+fn load_items(conn: &Connection) -> Result<Vec<(String, CacheItem)>> {
     conn.execute(
         "DELETE FROM cache_items WHERE expires_at IS NOT NULL AND expires_at < ?",
-        params![now],
+        params![now_secs()],
+    )?;
+
+    read_items(conn)
+}
+
+/// Read the unexpired rows through a read-only connection, leaving the
+/// database untouched (no migration, no cleanup, no PRAGMA changes)
+pub(crate) fn read_items_from_db(path: &Path) -> Result<Vec<(String, CacheItem)>> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
     )?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+
+    read_items(&conn)
+}
+
+/// Read the unexpired rows
+fn read_items(conn: &Connection) -> Result<Vec<(String, CacheItem)>> {
+    let now = now_secs();
 
     let mut stmt = conn.prepare(
-        "SELECT key, value, created_at, ttl_seconds 
+        "SELECT key, value, created_at, ttl_seconds, pinned, value_format 
          FROM cache_items 
          WHERE expires_at IS NULL OR expires_at >= ?",
     )?;
 
     let items = stmt.query_map(params![now], |row| {
         let key: String = row.get(0)?;
-        let value_json: String = row.get(1)?;
+        let value_column: SqlValue = row.get(1)?;
         let created_at_secs: i64 = row.get(2)?;
         let ttl_seconds: Option<i64> = row.get(3)?;
-
-        let value = Value::json_to_value(&value_json).unwrap_or_else(|_| value_json.to_value());
+        let pinned: bool = row.get(4)?;
+        let value_format: String = row.get(5)?;
+
+        let value = match value_column {
+            SqlValue::Blob(bytes) => {
+                let (text, format) = decompress_value(&bytes, &value_format)
+                    .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, Type::Blob, e))?;
+                decode_value(text, format)
+            }
+            SqlValue::Text(text) => decode_value(text, &value_format),
+            other => {
+                return Err(rusqlite::Error::InvalidColumnType(
+                    1,
+                    "value".to_string(),
+                    other.data_type(),
+                ))
+            }
+        };
         let created_at = created_at_secs as u64 * 1000;
         let ttl_millis = ttl_seconds.map(|secs| secs as u64 * 1000);
 
@@ -98,17 +451,16 @@ pub(crate) fn items_from_db(
             CacheItem {
                 value,
                 created_at,
+                modified_at: created_at,
                 ttl_millis,
+                pinned,
+                frequency: 0,
+                access_count: 0,
             },
         ))
     })?;
 
-    let mut result = Vec::new();
-    for item in items {
-        result.push(item?);
-    }
-
-    Ok(result)
+    items.collect()
 }
 
 /// Ensure the database file exists and is initialized
@@ -126,18 +478,40 @@ pub(crate) fn ensure_db_file(path: &Path) -> Result<(), Box<dyn std::error::Erro
     Ok(())
 }
 
-/// Background worker for persisting events to SQLite
-pub(crate) struct SqliteWriter {
-    receiver: Receiver<PersistentEvent>,
-    conn: Connection,
+/// A [`BackingStore`] that keeps entries in a SQLite database.
+///
+/// This is the store behind [`Cache::with_persist`](crate::Cache::with_persist)
+/// and its variants, which write to it from a background thread. It can also be
+/// passed to [`Cache::with_backing_store`](crate::Cache::with_backing_store), e.g.
+/// to persist synchronously with `WriteMode::WriteThrough`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "persist")]
+/// # {
+/// use quickleaf::{Cache, SqliteStore, WriteMode};
+///
+/// let store = SqliteStore::open("data/cache.db").unwrap();
+/// let mut cache = Cache::with_backing_store(1000, store, WriteMode::WriteThrough).unwrap();
+/// cache.insert("key", "value");
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+    compress_over: Option<usize>,
 }
 
-impl SqliteWriter {
-    pub fn new(
-        path: PathBuf,
-        receiver: Receiver<PersistentEvent>,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
-        let conn = Connection::open(&path)?;
+impl SqliteStore {
+    /// Opens (or creates) the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(path)?;
         init_database(&conn)?;
 
         match conn.execute_batch("PRAGMA journal_mode = WAL;") {
@@ -154,103 +528,142 @@ impl SqliteWriter {
              PRAGMA busy_timeout = 5000;",
         );
 
-        Ok(Self { receiver, conn })
+        Ok(Self {
+            conn: Mutex::new(conn),
+            compress_over: None,
+        })
     }
 
-    pub fn run(mut self) {
-        loop {
-            match self.receiver.recv_timeout(Duration::from_millis(100)) {
-                Ok(event) => {
-                    if let Err(e) = self.process_event(&event) {
-                        eprintln!("Error processing event: {}", e);
-                    }
-                }
-                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
-                    if let Err(e) = self.cleanup_expired() {
-                        eprintln!("Error cleaning up expired items: {}", e);
-                    }
-                }
-                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
-                    break;
-                }
-            }
-        }
+    /// Compresses values whose serialized form is longer than `bytes` before
+    /// writing them, which shrinks databases holding large text or JSON.
+    ///
+    /// Compression only applies to the stored form: values are decompressed
+    /// when the cache loads them, so reads are unaffected. Databases with
+    /// compressed rows can only be loaded with the `compress` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use quickleaf::{Cache, SqliteStore, WriteMode};
+    ///
+    /// let store = SqliteStore::open("data/cache.db")
+    ///     .unwrap()
+    ///     .compress_values_over(4096);
+    /// let mut cache = Cache::with_backing_store(1000, store, WriteMode::WriteThrough).unwrap();
+    /// cache.insert("report", "x".repeat(100_000));
+    /// ```
+    #[cfg(feature = "compress")]
+    pub fn compress_values_over(mut self, bytes: usize) -> Self {
+        self.compress_over = Some(bytes);
+        self
     }
 
-    fn process_event(&mut self, event: &PersistentEvent) -> Result<()> {
-        let timestamp = event
-            .timestamp
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        match &event.event {
-            Event::Insert(data) => {
-                let value_json = data.value.to_json(JsonMode::Inline);
-
-                self.conn.execute(
-                    "INSERT OR REPLACE INTO cache_items (key, value, created_at, ttl_seconds, expires_at) 
-                     VALUES (?, ?, ?, NULL, NULL)",
-                    params![&data.key, &value_json, timestamp],
-                )?;
-            }
-            Event::Remove(data) => {
-                self.conn
-                    .execute("DELETE FROM cache_items WHERE key = ?", params![&data.key])?;
-            }
-            Event::Clear => {
-                self.conn.execute("DELETE FROM cache_items", [])?;
-            }
-        }
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl BackingStore for SqliteStore {
+    fn load(&self) -> Result<Vec<(Key, CacheItem)>, StoreError> {
+        Ok(load_items(&self.conn())?)
+    }
+
+    fn put(&self, key: &str, item: &CacheItem) -> Result<(), StoreError> {
+        Ok(write_item(&self.conn(), key, item, self.compress_over)?)
+    }
 
+    fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.conn()
+            .execute("DELETE FROM cache_items WHERE key = ?", params![key])?;
         Ok(())
     }
 
-    fn cleanup_expired(&mut self) -> Result<()> {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+    fn clear(&self) -> Result<(), StoreError> {
+        self.conn().execute("DELETE FROM cache_items", [])?;
+        Ok(())
+    }
 
-        self.conn.execute(
+    fn purge_expired(&self) -> Result<(), StoreError> {
+        self.conn().execute(
             "DELETE FROM cache_items WHERE expires_at IS NOT NULL AND expires_at < ?",
-            params![now],
+            params![now_secs()],
         )?;
-
         Ok(())
     }
 }
 
-/// Spawn the background writer thread
+/// Write `item` to its row, replacing any previous one, compressing values
+/// longer than `compress_over` bytes
+fn write_item(
+    conn: &Connection,
+    key: &str,
+    item: &CacheItem,
+    compress_over: Option<usize>,
+) -> Result<()> {
+    let (value_text, value_format) = encode_value(&item.value);
+    let (value_column, value_format) = compress_value(value_text, value_format, compress_over);
+    let created_at = (item.created_at / 1000) as i64;
+    let ttl_seconds = item.ttl_millis.map(|ttl| (ttl / 1000) as i64);
+    let expires_at = ttl_seconds.map(|ttl| created_at + ttl);
+
+    conn.execute(
+        "INSERT OR REPLACE INTO cache_items 
+             (key, value, created_at, ttl_seconds, expires_at, pinned, value_format) 
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            key,
+            value_column,
+            created_at,
+            ttl_seconds,
+            expires_at,
+            item.pinned,
+            value_format
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Spawn the background writer thread, compressing values longer than
+/// `compress_over` bytes
 pub(crate) fn spawn_writer(
     path: PathBuf,
-    receiver: Receiver<PersistentEvent>,
+    receiver: Receiver<StoreOp>,
+    errors: Sender<PersistError>,
+    compress_over: Option<usize>,
 ) -> thread::JoinHandle<()> {
-    thread::spawn(move || match SqliteWriter::new(path, receiver) {
-        Ok(writer) => writer.run(),
-        Err(e) => eprintln!("Failed to create SQLite writer: {}", e),
+    thread::spawn(move || match SqliteStore::open(&path) {
+        Ok(mut store) => {
+            store.compress_over = compress_over;
+            run_writer(&store, receiver, |op, e| {
+                report_error(&errors, PersistError::from_op(op, e))
+            })
+        }
+        Err(e) => report_error(&errors, PersistError::new(PersistOperation::Open, None, e)),
     })
 }
 
-/// Persist an item with TTL directly to the database
-pub(crate) fn persist_item_with_ttl(
+/// Check that the existing database at `path` can take the write lock, releasing
+/// it right away without writing anything
+pub(crate) fn check_writable(path: &Path) -> Result<()> {
+    let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    conn.busy_timeout(HEALTH_CHECK_BUSY_TIMEOUT)?;
+
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    tx.rollback()
+}
+
+/// Persist an item directly to the database, bypassing the background writer
+/// and compressing values longer than `compress_over` bytes like it does
+pub(crate) fn persist_item(
     path: &Path,
     key: &str,
-    value: &Value,
-    ttl_seconds: u64,
-) -> Result<(), Box<dyn std::error::Error>> {
+    item: &CacheItem,
+    compress_over: Option<usize>,
+) -> Result<()> {
     let conn = Connection::open(path)?;
-
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
-
-    let expires_at = now + ttl_seconds as i64;
-    let value_json = value.to_json(JsonMode::Inline);
-
-    conn.execute(
-        "INSERT OR REPLACE INTO cache_items (key, value, created_at, ttl_seconds, expires_at) 
-         VALUES (?, ?, ?, ?, ?)",
-        params![key, value_json, now, ttl_seconds as i64, expires_at],
-    )?;
-
-    Ok(())
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    write_item(&conn, key, item, compress_over)
 }
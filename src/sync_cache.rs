@@ -0,0 +1,148 @@
+//! Thread-safe shared cache handle.
+//!
+//! This module provides [`SyncCache`], a cloneable handle to a single [`Cache`]
+//! guarded by a mutex, for sharing one cache between threads.
+
+use crate::cache::Cache;
+use crate::error::Error;
+use crate::snapshot::CacheSnapshot;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use valu3::traits::ToValueBehavior;
+use valu3::value::Value;
+
+/// A shared handle to a [`Cache`].
+///
+/// Unlike `Cache::clone`, which deep-copies the cache, cloning a `SyncCache`
+/// returns another handle to the same underlying cache: an insert through one
+/// handle is visible through every other handle, and events are emitted in the
+/// order the operations acquire the lock.
+///
+/// Accessors return owned values because references cannot outlive the lock.
+/// Use [`lock`](Self::lock) to run several operations, or any `Cache` method
+/// not mirrored here, under a single lock.
+///
+/// # Examples
+///
+/// ```
+/// use quickleaf::Cache;
+/// use quickleaf::valu3::traits::ToValueBehavior;
+/// use std::thread;
+///
+/// let cache = Cache::new(10).shared_handle();
+///
+/// let handle = cache.clone();
+/// thread::spawn(move || handle.insert("key", "value"))
+///     .join()
+///     .unwrap();
+///
+/// assert_eq!(cache.get("key"), Some("value".to_value()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SyncCache {
+    inner: Arc<Mutex<Cache>>,
+}
+
+impl SyncCache {
+    /// Wraps `cache` in a shared handle.
+    pub fn new(cache: Cache) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(cache)),
+        }
+    }
+
+    /// Locks the underlying cache for direct access.
+    ///
+    /// A poisoned lock is recovered, since every cache operation leaves the
+    /// cache in a consistent state.
+    pub fn lock(&self) -> MutexGuard<'_, Cache> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Inserts a key-value pair. See [`Cache::insert`].
+    pub fn insert<T, V>(&self, key: T, value: V)
+    where
+        T: Into<String> + Clone + AsRef<str>,
+        V: ToValueBehavior,
+    {
+        self.lock().insert(key, value);
+    }
+
+    /// Inserts a key-value pair with a TTL. See [`Cache::insert_with_ttl`].
+    pub fn insert_with_ttl<T, V>(&self, key: T, value: V, ttl: Duration)
+    where
+        T: Into<String> + Clone + AsRef<str>,
+        V: ToValueBehavior,
+    {
+        self.lock().insert_with_ttl(key, value, ttl);
+    }
+
+    /// Returns a copy of the value for `key`. See [`Cache::get`].
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.lock().get(key).cloned()
+    }
+
+    /// Removes `key`. See [`Cache::remove`].
+    pub fn remove(&self, key: &str) -> Result<(), Error> {
+        self.lock().remove(key)
+    }
+
+    /// Checks if `key` exists and hasn't expired. See [`Cache::contains_key`].
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.lock().contains_key(key)
+    }
+
+    /// Removes all items. See [`Cache::clear`].
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Copies the live entries under the lock. See [`Cache::snapshot`].
+    ///
+    /// The lock is released before this returns, so the snapshot can be
+    /// iterated while other handles keep writing.
+    pub fn snapshot(&self) -> CacheSnapshot {
+        self.lock().snapshot()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+}
+
+impl From<Cache> for SyncCache {
+    fn from(cache: Cache) -> Self {
+        Self::new(cache)
+    }
+}
+
+impl Cache {
+    /// Converts this cache into a [`SyncCache`] whose clones all share it.
+    ///
+    /// Use this instead of `clone` when several owners or threads must see the
+    /// same entries. Entries, capacity, TTL settings and the event sender all
+    /// carry over, so a cache can be populated during setup and then shared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use quickleaf::Cache;
+    /// use quickleaf::valu3::traits::ToValueBehavior;
+    /// use std::thread;
+    ///
+    /// let mut cache = Cache::new(10);
+    /// cache.insert("config:mode", "production");
+    ///
+    /// let shared = cache.shared_handle();
+    /// let handle = shared.clone();
+    /// let mode = thread::spawn(move || handle.get("config:mode")).join().unwrap();
+    /// assert_eq!(mode, Some("production".to_value()));
+    /// ```
+    pub fn shared_handle(self) -> SyncCache {
+        SyncCache::new(self)
+    }
+}
@@ -1,9 +1,12 @@
 #[cfg(test)]
 mod test {
+    use std::borrow::Cow;
+
+    use valu3::prelude::StringBehavior;
     use valu3::traits::ToValueBehavior;
 
     use crate::list_props::{Order, StartAfter};
-    use crate::{Cache, Event, EventData, Filter, ListProps};
+    use crate::{Cache, Event, EventData, EvictionPolicy, Filter, ListProps};
 
     #[test]
     fn test_cache_insert() {
@@ -51,6 +54,8 @@ mod test {
             filter: Filter::None,
             start_after_key: StartAfter::Key("key2".to_string()),
             limit: 10,
+            collator: None,
+            byte_budget: None,
         });
 
         assert_eq!(result_res.is_ok(), true);
@@ -85,6 +90,8 @@ mod test {
             filter: Filter::StartWith("post".to_string()),
             start_after_key: StartAfter::Key("postmodern".to_string()),
             limit: 10,
+            collator: None,
+            byte_budget: None,
         });
 
         assert_eq!(result_res.is_ok(), true);
@@ -113,6 +120,8 @@ mod test {
             filter: Filter::None,
             start_after_key: StartAfter::Key("key3".to_string()),
             limit: 10,
+            collator: None,
+            byte_budget: None,
         });
 
         assert_eq!(result_res.is_ok(), true);
@@ -224,261 +233,2957 @@ mod test {
     }
 
     #[test]
-    fn test_list_respects_start_after_key() {
-        let mut cache = Cache::new(10);
-        
-        // Insert items with predictable keys
-        for i in 0..10 {
-            cache.insert(format!("key_{:02}", i), i);
+    fn test_list_unlimited_returns_all_matching() {
+        let mut cache = Cache::new(100);
+        for i in 0..40 {
+            cache.insert(format!("item_{:02}", i), i);
         }
+        cache.insert("other", 0);
+
+        let results = cache
+            .list(
+                ListProps::default()
+                    .filter(Filter::StartWith("item_".to_string()))
+                    .unlimited(),
+            )
+            .unwrap();
+        assert_eq!(results.len(), 40);
+        assert_eq!(results[0].0, "item_00");
+        assert_eq!(results[39].0, "item_39");
+
+        let results = cache
+            .list(ListProps::default().order(Order::Desc).unlimited())
+            .unwrap();
+        assert_eq!(results.len(), 41);
+        assert_eq!(results[0].0, "other");
+
+        let results = cache
+            .list(ListProps::default().unlimited().limit(0))
+            .unwrap();
+        assert!(results.is_empty());
+    }
 
-        // Test starting after key_05
-        let props = ListProps::default().start_after_key("key_05");
-        let results = cache.list(props).unwrap();
-        
-        // Should return key_06 through key_09 (4 items)
-        assert_eq!(results.len(), 4);
-        assert_eq!(results[0].0, "key_06");
-        assert_eq!(results[1].0, "key_07");
-        assert_eq!(results[2].0, "key_08");
-        assert_eq!(results[3].0, "key_09");
+    #[test]
+    fn test_cache_with_custom_hasher() {
+        let mut cache = Cache::with_hasher(3, hashbrown::DefaultHashBuilder::default());
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        assert_eq!(cache.get("a"), Some(&1.to_value()));
+        assert!(cache.contains_key("b"));
+
+        // LRU eviction still removes the oldest entry
+        cache.insert("d", 4);
+        assert_eq!(cache.len(), 3);
+        assert!(!cache.contains_key("a"));
+
+        cache.remove("c").unwrap();
+        let keys: Vec<_> = cache
+            .list(ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["b", "d"]);
+
+        let (matching, rest) = cache.partition(|key, _| key == "d");
+        assert_eq!(matching.len(), 1);
+        assert_eq!(rest.len(), 1);
+
+        let mut built = Cache::builder(10)
+            .eviction_policy(EvictionPolicy::Clock)
+            .build_with_hasher(hashbrown::DefaultHashBuilder::default());
+        built.insert("key", "value");
+        assert_eq!(built.get("key"), Some(&"value".to_value()));
+        assert_eq!(built.get_eviction_policy(), EvictionPolicy::Clock);
+    }
 
-        // Test starting after last key (should return empty)
-        let props = ListProps::default().start_after_key("key_09");
-        let results = cache.list(props).unwrap();
-        assert_eq!(results.len(), 0);
+    #[test]
+    fn test_increment_and_decrement() {
+        let mut cache = Cache::new(10);
 
-        // Test starting after non-existent key (should return error)
-        let props = ListProps::default().start_after_key("non_existent");
-        let results = cache.list(props);
-        assert!(results.is_err());
+        // A new key starts from 0
+        assert_eq!(cache.increment("counter", 1), Ok(1));
+        assert_eq!(cache.get("counter"), Some(&1i64.to_value()));
+
+        // An existing integer is updated in place
+        cache.insert("visits", 10);
+        assert_eq!(cache.increment("visits", 5), Ok(15));
+        assert_eq!(cache.decrement("visits", 20), Ok(-5));
+        assert_eq!(cache.get_as::<i64>("visits"), Some(-5));
+
+        assert_eq!(cache.increment("visits", i64::MAX), Ok(i64::MAX - 5));
+        assert_eq!(cache.increment("visits", i64::MAX), Ok(i64::MAX));
+
+        // A non-integer value is an error and is left untouched
+        cache.insert("name", "Alice");
+        assert_eq!(cache.increment("name", 1), Err(crate::Error::TypeMismatch));
+        assert_eq!(cache.decrement("name", 1), Err(crate::Error::TypeMismatch));
+        assert_eq!(cache.get("name"), Some(&"Alice".to_value()));
     }
 
     #[test]
-    fn test_list_pagination_combined() {
-        let mut cache = Cache::new(20);
-        
-        // Insert 20 items
-        for i in 0..20 {
-            cache.insert(format!("key_{:02}", i), i);
-        }
+    fn test_list_push_and_set_add() {
+        let mut cache = Cache::new(10);
 
-        // Get first page: 5 items starting from beginning
-        let props = ListProps::default().limit(5);
-        let page1 = cache.list(props).unwrap();
-        assert_eq!(page1.len(), 5);
-        assert_eq!(page1[0].0, "key_00");
-        assert_eq!(page1[4].0, "key_04");
+        // Pushing to a new key builds an array
+        assert_eq!(cache.list_push("queue", 1), Ok(1));
+        assert_eq!(cache.list_push("queue", 2), Ok(2));
+        assert_eq!(cache.list_push("queue", 2), Ok(3));
+        assert_eq!(cache.get("queue"), Some(&vec![1, 2, 2].to_value()));
+
+        // `set_add` ignores duplicates
+        assert_eq!(cache.set_add("tags", "a"), Ok(true));
+        assert_eq!(cache.set_add("tags", "b"), Ok(true));
+        assert_eq!(cache.set_add("tags", "a"), Ok(false));
+        assert_eq!(cache.get("tags"), Some(&vec!["a", "b"].to_value()));
+
+        // Non-array values are an error and are left untouched
+        cache.insert("name", "Alice");
+        assert_eq!(cache.list_push("name", 1), Err(crate::Error::TypeMismatch));
+        assert_eq!(cache.set_add("name", 1), Err(crate::Error::TypeMismatch));
+        assert_eq!(cache.get("name"), Some(&"Alice".to_value()));
+    }
 
-        // Get second page: 5 items starting after key_04
-        let props = ListProps::default()
-            .start_after_key("key_04")
-            .limit(5);
-        let page2 = cache.list(props).unwrap();
-        assert_eq!(page2.len(), 5);
-        assert_eq!(page2[0].0, "key_05");
-        assert_eq!(page2[4].0, "key_09");
+    #[test]
+    fn test_zero_capacity_cache_stores_nothing() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(0, tx);
 
-        // Get third page: 5 items starting after key_09
-        let props = ListProps::default()
-            .start_after_key("key_09")
-            .limit(5);
-        let page3 = cache.list(props).unwrap();
-        assert_eq!(page3.len(), 5);
-        assert_eq!(page3[0].0, "key_10");
-        assert_eq!(page3[4].0, "key_14");
+        cache.insert("a", 1);
+        cache.insert_with_ttl("b", 2, std::time::Duration::from_secs(60));
+        assert!(!cache.insert_if_absent("c", 3));
 
-        // Get fourth page: should return remaining 5 items
-        let props = ListProps::default()
-            .start_after_key("key_14")
-            .limit(5);
-        let page4 = cache.list(props).unwrap();
-        assert_eq!(page4.len(), 5);
-        assert_eq!(page4[0].0, "key_15");
-        assert_eq!(page4[4].0, "key_19");
+        assert_eq!(cache.len(), 0);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get("a"), None);
+        assert!(rx.try_recv().is_err());
+    }
 
-        // Try to get fifth page: should be empty
-        let props = ListProps::default()
-            .start_after_key("key_19")
-            .limit(5);
-        let page5 = cache.list(props).unwrap();
-        assert_eq!(page5.len(), 0);
+    #[test]
+    fn test_get_or_insert_with_returns_unstorable_values() {
+        let mut cache = Cache::new(0);
+        assert!(matches!(
+            cache.get_or_insert_with("a", || 1),
+            Cow::Owned(value) if value == 1.to_value()
+        ));
+        assert!(matches!(
+            cache.get_or_try_insert_with("a", || Ok::<_, ()>(2)),
+            Ok(Cow::Owned(value)) if value == 2.to_value()
+        ));
+        assert_eq!(cache.get_or_insert_default("b"), None);
+        assert_eq!(cache.increment("hits", 3), Ok(3));
+        assert_eq!(cache.list_push("log", "x"), Ok(1));
+        assert!(cache.is_empty());
+
+        let mut cache = Cache::new(1);
+        cache.insert("config", "v1");
+        cache.pin("config");
+        assert!(matches!(cache.get_or_insert_with("a", || 1), Cow::Owned(_)));
+        assert_eq!(cache.get_or_insert_default("b"), None);
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains_key("a"));
+        assert!(!cache.contains_key("b"));
     }
 
     #[test]
-    fn test_list_pagination_with_filter() {
-        let mut cache = Cache::new(30);
-        
-        // Insert items with different prefixes
-        for i in 0..10 {
-            cache.insert(format!("user_{:02}", i), i);
-            cache.insert(format!("admin_{:02}", i), i + 10);
-            cache.insert(format!("guest_{:02}", i), i + 20);
-        }
+    fn test_on_evict_observes_evicted_entries() {
+        use std::sync::{Arc, Mutex};
 
-        // Get first page of user items
-        let props = ListProps::default()
-            .filter(Filter::StartWith("user_".to_string()))
-            .limit(3);
-        let page1 = cache.list(props).unwrap();
-        assert_eq!(page1.len(), 3);
-        assert_eq!(page1[0].0, "user_00");
-        assert_eq!(page1[1].0, "user_01");
-        assert_eq!(page1[2].0, "user_02");
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&evicted);
 
-        // Get second page of user items
-        let props = ListProps::default()
-            .filter(Filter::StartWith("user_".to_string()))
-            .start_after_key("user_02")
-            .limit(3);
-        let page2 = cache.list(props).unwrap();
-        assert_eq!(page2.len(), 3);
-        assert_eq!(page2[0].0, "user_03");
-        assert_eq!(page2[1].0, "user_04");
-        assert_eq!(page2[2].0, "user_05");
+        let mut cache = Cache::new(3);
+        cache.on_evict(move |key, value| {
+            sink.lock().unwrap().push((key.to_string(), value.clone()));
+        });
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        assert!(evicted.lock().unwrap().is_empty());
+
+        cache.insert("d", 4);
+        cache.insert("e", 5);
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            vec![
+                ("a".to_string(), 1.to_value()),
+                ("b".to_string(), 2.to_value())
+            ]
+        );
+
+        // Explicit removals are not evictions
+        cache.remove("c").unwrap();
+        cache.clear();
+        assert_eq!(evicted.lock().unwrap().len(), 2);
+
+        cache.remove_on_evict();
+        for i in 0..5 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(evicted.lock().unwrap().len(), 2);
     }
 
-    #[test]
-    fn test_list_pagination_desc_order() {
-        let mut cache = Cache::new(15);
-        
-        // Insert 15 items
-        for i in 0..15 {
-            cache.insert(format!("key_{:02}", i), i);
+    #[derive(Clone, Default)]
+    struct MockStore {
+        initial: Vec<(String, crate::CacheItem)>,
+        calls: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl crate::BackingStore for MockStore {
+        fn load(&self) -> Result<Vec<(String, crate::CacheItem)>, crate::StoreError> {
+            Ok(self.initial.clone())
         }
 
-        // Get first page in descending order
-        let props = ListProps::default()
-            .order(Order::Desc)
-            .limit(5);
-        let page1 = cache.list(props).unwrap();
-        assert_eq!(page1.len(), 5);
-        assert_eq!(page1[0].0, "key_14");
-        assert_eq!(page1[4].0, "key_10");
+        fn put(&self, key: &str, item: &crate::CacheItem) -> Result<(), crate::StoreError> {
+            let call = format!("put {}={}", key, item.value);
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
 
-        // Get second page in descending order
-        let props = ListProps::default()
-            .order(Order::Desc)
-            .start_after_key("key_10")
-            .limit(5);
-        let page2 = cache.list(props).unwrap();
-        assert_eq!(page2.len(), 5);
-        assert_eq!(page2[0].0, "key_09");
-        assert_eq!(page2[4].0, "key_05");
+        fn delete(&self, key: &str) -> Result<(), crate::StoreError> {
+            self.calls.lock().unwrap().push(format!("delete {}", key));
+            Ok(())
+        }
 
-        // Get third page in descending order
-        let props = ListProps::default()
-            .order(Order::Desc)
-            .start_after_key("key_05")
-            .limit(5);
-        let page3 = cache.list(props).unwrap();
-        assert_eq!(page3.len(), 5);
-        assert_eq!(page3[0].0, "key_04");
-        assert_eq!(page3[4].0, "key_00");
+        fn clear(&self) -> Result<(), crate::StoreError> {
+            self.calls.lock().unwrap().push("clear".to_string());
+            Ok(())
+        }
     }
 
     #[test]
-    fn test_filter_ends_with() {
-        let mut cache = Cache::new(10);
+    fn test_backing_store_write_through() {
+        let mut stale = crate::CacheItem::with_ttl(0.to_value(), std::time::Duration::from_secs(1));
+        stale.created_at -= 10_000;
+        let store = MockStore {
+            initial: vec![
+                ("loaded".to_string(), crate::CacheItem::new(0.to_value())),
+                ("stale".to_string(), stale),
+            ],
+            ..Default::default()
+        };
+        let calls = store.calls.clone();
 
-        cache.insert("postmodern", 8);
-        cache.insert("postpone", 6);
-        cache.insert("precept", 2);
-        cache.insert("postmortem", 9);
-        cache.insert("precaution", 3);
-        cache.insert("precede", 1);
-        cache.insert("precognition", 5);
-        cache.insert("postmark", 10);
-        cache.insert("postgraduate", 7);
-        cache.insert("preconceive", 4);
+        let mut cache =
+            Cache::with_backing_store(2, store, crate::WriteMode::WriteThrough).unwrap();
+        assert_eq!(cache.get("loaded"), Some(&0.to_value()));
+        assert!(!cache.contains_key("stale"));
+        assert!(calls.lock().unwrap().is_empty());
 
-        let result_res = cache.list(Filter::EndWith("tion".to_string()));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.remove("b").unwrap();
+        cache.clear();
 
-        assert_eq!(result_res.is_ok(), true);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["put a=1", "delete loaded", "put b=2", "delete b", "clear"]
+        );
+    }
 
-        let result = match result_res {
-            Ok(result) => result,
-            Err(_) => panic!("Error"),
-        };
+    #[test]
+    fn test_backing_store_write_back() {
+        let store = MockStore::default();
+        let calls = store.calls.clone();
 
-        assert_eq!(result.len(), 2);
-        assert_eq!(result[0], ("precaution".to_string(), &3.to_value()));
-        assert_eq!(result[1], ("precognition".to_string(), &5.to_value()));
+        let mut cache = Cache::with_backing_store(10, store, crate::WriteMode::WriteBack).unwrap();
+        cache.insert("a", 1);
+        cache.remove("a").unwrap();
+        cache.clear();
+
+        let expected = vec!["put a=1", "delete a", "clear"];
+        for _ in 0..100 {
+            if calls.lock().unwrap().len() == expected.len() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(*calls.lock().unwrap(), expected);
     }
 
     #[test]
-    fn test_filter_start_and_end_with() {
+    fn test_find_and_find_all() {
+        use valu3::prelude::NumberBehavior;
+
         let mut cache = Cache::new(10);
+        cache.insert("a", 5);
+        cache.insert("b", 50);
+        cache.insert("c", 20);
+        cache.insert("d", 50);
+        cache.insert("e", "text");
+
+        let found = cache.find(|value| *value == 50.to_value());
+        assert_eq!(found, Some((&"b".to_string(), &50.to_value())));
+        assert_eq!(cache.find(|value| *value == 7.to_value()), None);
+
+        let in_range =
+            cache.find_all(|value| value.to_i64().is_some_and(|n| (10..=50).contains(&n)));
+        let keys: Vec<&str> = in_range.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["b", "c", "d"]);
+        assert!(cache.find_all(|value| *value == 7.to_value()).is_empty());
+    }
 
-        cache.insert("applemorepie", 1);
-        cache.insert("banana", 2);
-        cache.insert("pineapplepie", 3);
+    #[test]
+    fn test_list_orders_non_ascii_keys_by_code_point() {
+        let mut cache = Cache::new(10);
+        for key in ["\u{e9}", "z", "a", "Z", "\u{e8}"] {
+            cache.insert(key, 1);
+        }
 
-        let list_props = ListProps::default()
-            .filter(Filter::StartAndEndWith(
-                "apple".to_string(),
-                "pie".to_string(),
-            ))
-            .order(Order::Asc);
+        let results = cache.list(ListProps::default()).unwrap();
+        let keys: Vec<&str> = results.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["Z", "a", "z", "\u{e8}", "\u{e9}"]);
 
-        let result_res = cache.list(list_props);
+        let props = ListProps::default().order(Order::Desc).limit(2);
+        let results = cache.list(props).unwrap();
+        let keys: Vec<&str> = results.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["\u{e9}", "\u{e8}"]);
+    }
 
-        assert_eq!(result_res.is_ok(), true);
+    #[test]
+    fn test_list_with_collator() {
+        fn fold_accents(a: &str, b: &str) -> std::cmp::Ordering {
+            let fold = |s: &str| s.to_lowercase().replace(['\u{e8}', '\u{e9}'], "e");
+            fold(a).cmp(&fold(b))
+        }
 
-        let result = match result_res {
-            Ok(result) => result,
-            Err(_) => panic!("Error"),
-        };
+        let mut cache = Cache::new(10);
+        for key in ["\u{e9}clair", "zebra", "apple", "Eagle", "fig"] {
+            cache.insert(key, 1);
+        }
 
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0], ("applemorepie".to_string(), &1.to_value()));
+        let props = ListProps::default().collate(fold_accents);
+        let results = cache.list(props).unwrap();
+        let keys: Vec<&str> = results.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["apple", "Eagle", "\u{e9}clair", "fig", "zebra"]);
+
+        let props = ListProps::default()
+            .collate(fold_accents)
+            .order(Order::Desc)
+            .start_after_key("fig")
+            .limit(2);
+        let results = cache.list(props).unwrap();
+        let keys: Vec<&str> = results.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["\u{e9}clair", "Eagle"]);
+
+        let props = ListProps::default()
+            .collate(fold_accents)
+            .start_after_key("missing");
+        assert_eq!(cache.list(props), Err(crate::Error::SortKeyNotFound));
     }
 
     #[test]
-    fn test_with_sender() {
-        let (tx, rx) = std::sync::mpsc::channel();
-        let mut cache = Cache::with_sender(10, tx);
+    fn test_logical_capacity_is_separate_from_allocation() {
+        let mut cache = Cache::builder(1000).preallocate(4).build();
+        assert_eq!(cache.capacity(), 1000);
+        assert!(cache.allocated_capacity() < 1000);
 
-        let mut clone_cache = cache.clone();
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(1));
-            clone_cache.insert("key1", 1);
-        });
+        for i in 0..50 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(cache.len(), 50);
+        assert!(cache.allocated_capacity() >= 50);
 
-        cache.insert("key2", 2);
-        cache.insert("key3", 3);
+        let mut cache = Cache::new(3);
+        assert!(cache.allocated_capacity() >= 3);
+        cache.reserve(100);
+        assert!(cache.allocated_capacity() >= 100);
+        assert_eq!(cache.capacity(), 3);
 
-        let mut items = Vec::new();
+        for i in 0..5 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get_list(), vec!["key2", "key3", "key4"]);
+    }
 
-        for data in rx {
-            items.push(data);
+    #[test]
+    fn test_try_reserve_reports_allocation_failure() {
+        let mut cache = Cache::builder(1000).preallocate(4).build();
+        cache.insert("a", 1);
 
-            if items.len() == 3 {
-                break;
-            }
-        }
+        assert_eq!(cache.try_reserve(500), Ok(()));
+        assert!(cache.allocated_capacity() >= 501);
+        assert_eq!(cache.capacity(), 1000);
 
-        assert_eq!(items.len(), 3);
+        let allocated = cache.allocated_capacity();
         assert_eq!(
-            items[0],
-            Event::Insert(EventData {
-                key: "key2".to_string(),
-                value: 2.to_value()
-            })
+            cache.try_reserve(usize::MAX),
+            Err(crate::Error::AllocationFailed)
         );
-        assert_eq!(
-            items[1],
-            Event::Insert(EventData {
-                key: "key3".to_string(),
-                value: 3.to_value()
+        assert_eq!(cache.allocated_capacity(), allocated);
+        assert_eq!(cache.get("a"), Some(&1.to_value()));
+    }
+
+    #[test]
+    fn test_diff_between_caches() {
+        let mut primary = Cache::new(10);
+        primary.insert("same", 1);
+        primary.insert("changed", 2);
+        primary.insert("removed", 3);
+
+        let mut replica = primary.clone();
+        assert!(primary.diff(&replica).is_empty());
+
+        replica.insert("added", 4);
+        replica.insert("changed", 20);
+        replica.remove("removed").unwrap();
+
+        let diff = primary.diff(&replica);
+        assert_eq!(diff.only_self, vec!["removed"]);
+        assert_eq!(diff.only_other, vec!["added"]);
+        assert_eq!(diff.value_mismatch, vec!["changed"]);
+        assert!(!diff.is_empty());
+
+        let reverse = replica.diff(&primary);
+        assert_eq!(reverse.only_self, vec!["added"]);
+        assert_eq!(reverse.only_other, vec!["removed"]);
+        assert_eq!(reverse.value_mismatch, vec!["changed"]);
+    }
+
+    #[test]
+    fn test_remove_matching() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("a_cache", 1);
+        cache.insert("b_data", 2);
+        cache.insert("c_cache", 3);
+        cache.insert("d_other", 4);
+        while rx.try_recv().is_ok() {}
+
+        let removed = cache.remove_matching(&Filter::EndWith("_cache".to_string()));
+        assert_eq!(
+            removed,
+            vec![
+                ("a_cache".to_string(), 1.to_value()),
+                ("c_cache".to_string(), 3.to_value()),
+            ]
+        );
+        assert_eq!(cache.get_list(), vec!["b_data", "d_other"]);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Event::remove("a_cache".to_string(), 1.to_value()),
+                Event::remove("c_cache".to_string(), 3.to_value()),
+            ]
+        );
+
+        assert!(cache
+            .remove_matching(&Filter::EndWith("_cache".to_string()))
+            .is_empty());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_pinned_keys_survive_eviction() {
+        for policy in [EvictionPolicy::Lru, EvictionPolicy::Clock] {
+            let mut cache = Cache::new(3);
+            cache.set_eviction_policy(policy);
+            cache.insert("config", "v1");
+            assert!(cache.pin("config"));
+            assert!(cache.is_pinned("config"));
+            assert!(!cache.pin("missing"));
+
+            for i in 0..10 {
+                cache.insert(format!("key{}", i), i);
+            }
+
+            assert_eq!(cache.len(), 3);
+            assert!(cache.contains_key("config"));
+            assert!(cache.contains_key("key9"));
+            assert!(!cache.contains_key("key0"));
+
+            cache.insert("config", "v2");
+            assert!(cache.is_pinned("config"));
+
+            assert!(cache.unpin("config"));
+            assert!(!cache.is_pinned("config"));
+        }
+    }
+
+    #[test]
+    fn test_inserts_dropped_when_every_entry_is_pinned() {
+        let mut cache = Cache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.pin("a");
+        cache.pin("b");
+
+        cache.insert("c", 3);
+        assert!(!cache.insert_if_absent("d", 4));
+        assert_eq!(cache.get_list(), vec!["a", "b"]);
+
+        cache.insert("a", 10);
+        assert_eq!(cache.get("a"), Some(&10.to_value()));
+
+        cache.unpin("a");
+        cache.insert("c", 3);
+        assert_eq!(cache.get_list(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_resize_policy_grows_up_to_max() {
+        let mut cache = Cache::builder(4)
+            .resize_policy(crate::ResizePolicy::Grow {
+                factor: 2.0,
+                max: 10,
             })
+            .build();
+
+        for i in 0..4 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(cache.capacity(), 4);
+        assert_eq!(cache.capacity_utilization(), 1.0);
+
+        cache.insert("key4", 4);
+        assert_eq!(cache.capacity(), 8);
+        assert_eq!(cache.len(), 5);
+
+        for i in 5..20 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(cache.capacity(), 10);
+        assert_eq!(cache.len(), 10);
+        assert_eq!(cache.get_list().first().unwrap().as_str(), "key10");
+    }
+
+    #[test]
+    fn test_utilization_watermarks() {
+        let crossings = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = crossings.clone();
+
+        let mut cache = Cache::new(10);
+        cache.on_utilization(0.3, 0.8, move |mark, utilization| {
+            sink.lock().unwrap().push((mark, utilization));
+        });
+
+        for i in 0..12 {
+            cache.insert(format!("key{}", i), i);
+        }
+        for i in 2..9 {
+            cache.remove(&format!("key{}", i)).unwrap();
+        }
+        cache.clear();
+
+        assert_eq!(
+            *crossings.lock().unwrap(),
+            vec![(crate::Watermark::High, 0.8), (crate::Watermark::Low, 0.3)]
         );
+    }
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_later_changes() {
+        let mut cache = Cache::new(10);
+        cache.insert("apple", 1);
+        cache.insert("banana", 2);
+        cache.insert("cherry", 3);
+
+        let snapshot = cache.snapshot();
+        cache.insert("apple", 10);
+        cache.remove("banana").unwrap();
+        cache.insert("date", 4);
+        cache.clear();
+
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.get("apple"), Some(&1.to_value()));
+        assert_eq!(snapshot.get("banana"), Some(&2.to_value()));
+        assert!(!snapshot.contains_key("date"));
+
+        let listed = snapshot
+            .list(
+                ListProps::default()
+                    .order(Order::Desc)
+                    .start_after_key("cherry"),
+            )
+            .unwrap();
         assert_eq!(
-            items[2],
-            Event::Insert(EventData {
-                key: "key1".to_string(),
-                value: 1.to_value()
+            listed,
+            vec![
+                ("banana".to_string(), &2.to_value()),
+                ("apple".to_string(), &1.to_value())
+            ]
+        );
+        assert_eq!(
+            snapshot.list(ListProps::default().start_after_key("date")),
+            Err(crate::Error::SortKeyNotFound)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_skips_expired_entries_and_outlives_sync_lock() {
+        let mut cache = Cache::new(10);
+        cache.insert("live", 1);
+        cache.insert_with_ttl("expired", 2, std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let cache = cache.shared_handle();
+        let snapshot = cache.snapshot();
+        cache.insert("later", 3);
+
+        assert_eq!(snapshot.len(), 1);
+        assert!(!snapshot.contains_key("expired"));
+        assert_eq!(
+            snapshot.iter().collect::<Vec<_>>(),
+            vec![(&"live".to_string(), &1.to_value())]
+        );
+        assert!(cache.contains_key("later"));
+        assert!(!snapshot.contains_key("later"));
+    }
+
+    #[test]
+    fn test_list_with_count_reports_all_matches() {
+        let mut cache = Cache::new(200);
+        for i in 0..142 {
+            cache.insert(format!("item:{:03}", i), i);
+        }
+        cache.insert("other", 0);
+        cache.insert_with_ttl("item:expired", 0, std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let props = ListProps::default()
+            .filter(Filter::StartWith("item:".to_string()))
+            .limit(10);
+        let (page, total) = cache.list_with_count(props).unwrap();
+        assert_eq!(page.len(), 10);
+        assert_eq!(page[0].0, "item:000");
+        assert_eq!(total, 142);
+
+        let props = ListProps::default()
+            .filter(Filter::StartWith("item:".to_string()))
+            .start_after_key("item:139")
+            .limit(10);
+        let (page, total) = cache.list_with_count(props).unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(total, 142);
+
+        let (page, total) = cache.list_with_count(ListProps::default()).unwrap();
+        assert_eq!(page.len(), 10);
+        assert_eq!(total, 143);
+    }
+
+    #[test]
+    fn test_list_with_count_matches_list_and_count_on_every_path() {
+        fn by_length(a: &str, b: &str) -> std::cmp::Ordering {
+            a.len().cmp(&b.len())
+        }
+        fn case(index: usize) -> ListProps {
+            let props = ListProps::default().filter(Filter::StartWith("k1".to_string()));
+            match index {
+                0 => props.limit(3),
+                1 => props.order(Order::Desc).start_after_key("k15").limit(4),
+                2 => props.byte_budget(40),
+                3 => props.collate(by_length).start_after_key("k19"),
+                _ => props.limit(0),
+            }
+        }
+
+        let mut cache = Cache::new(200);
+        for i in 0..120 {
+            cache.insert(format!("k{}", i), i);
+        }
+        let total = cache.count(&Filter::StartWith("k1".to_string()));
+
+        for index in 0..5 {
+            for dirty in [true, false] {
+                if dirty {
+                    cache.insert(format!("other{}", index), 0);
+                } else {
+                    cache.list(ListProps::default().unlimited()).unwrap();
+                }
+
+                let expected: Vec<String> = cache
+                    .list(case(index))
+                    .unwrap()
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect();
+                let (page, counted) = cache.list_with_count(case(index)).unwrap();
+                let page: Vec<String> = page.into_iter().map(|(key, _)| key).collect();
+                assert_eq!(page, expected, "case {} (dirty: {})", index, dirty);
+                assert_eq!(counted, total, "case {} (dirty: {})", index, dirty);
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_transform_applies_to_stored_and_event_values() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.set_value_transform(Some(|value: crate::Value| vec![value].to_value()));
+
+        cache.insert("plain", 1);
+        cache.insert_with_ttl("ttl", 2, std::time::Duration::from_secs(60));
+        assert!(cache.set_value("plain", 3));
+
+        assert_eq!(cache.get("plain"), Some(&vec![3].to_value()));
+        assert_eq!(cache.get("ttl"), Some(&vec![2].to_value()));
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("plain".to_string(), vec![1].to_value()),
+                Event::insert("ttl".to_string(), vec![2].to_value()),
+                Event::insert("plain".to_string(), vec![3].to_value()),
+            ]
+        );
+
+        cache.insert("plain", 3);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_builder_transform_values() {
+        let mut cache = Cache::builder(10)
+            .transform_values(|value| vec![value].to_value())
+            .build();
+        assert!(cache.get_value_transform().is_some());
+
+        let value = cache.get_or_insert_with("key", || "computed");
+        assert_eq!(*value, vec!["computed"].to_value());
+    }
+
+    #[test]
+    fn test_clear_itemized_sends_a_remove_event_per_key() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("one", 1);
+        cache.insert("two", 2);
+        cache.insert("three", 3);
+        assert_eq!(rx.try_iter().count(), 3);
+
+        cache.clear_itemized();
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::remove("one".to_string(), 1.to_value()),
+                Event::remove("two".to_string(), 2.to_value()),
+                Event::remove("three".to_string(), 3.to_value()),
+            ]
+        );
+        assert!(cache.is_empty());
+        assert_eq!(cache.list(ListProps::default()).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_get_by_prefix_groups_live_entries() {
+        let mut cache = Cache::new(20);
+        cache.insert("user:2:name", "bob");
+        cache.insert("user:1:name", "alice");
+        cache.insert("user:1:email", "alice@example.com");
+        cache.insert("user:10:name", "carol");
+        cache.insert("order:1", 100);
+        cache.insert_with_ttl("user:1:session", "x", std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let email = "alice@example.com".to_value();
+        let name = "alice".to_value();
+        let expected = vec![
+            ("user:1:email".to_string(), &email),
+            ("user:1:name".to_string(), &name),
+        ];
+        assert_eq!(cache.get_by_prefix("user:1:"), expected);
+
+        // Same results once the sorted key index has been built
+        cache.list(ListProps::default()).unwrap();
+        assert_eq!(cache.get_by_prefix("user:1:"), expected);
+
+        let keys: Vec<String> = cache
+            .get_by_prefix("user:")
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec!["user:10:name", "user:1:email", "user:1:name", "user:2:name"]
+        );
+        assert_eq!(cache.get_by_prefix("order:").len(), 1);
+        assert!(cache.get_by_prefix("missing:").is_empty());
+        assert_eq!(cache.get_by_prefix("").len(), 5);
+    }
+
+    #[test]
+    fn test_observe_replays_live_entries_then_streams_events() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("first", 1);
+        cache.insert("second", 2);
+        cache.insert_with_ttl("expired", 3, std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        let observer = cache.observe();
+        cache.insert("third", 3);
+        cache.remove("first").unwrap();
+
+        let events: Vec<Event> = observer.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("first".to_string(), 1.to_value()),
+                Event::insert("second".to_string(), 2.to_value()),
+                Event::insert("third".to_string(), 3.to_value()),
+                Event::remove("first".to_string(), 1.to_value()),
+            ]
+        );
+
+        // The regular sender still gets every real event, and no replay
+        assert_eq!(rx.try_iter().count(), 5);
+
+        drop(observer);
+        cache.insert("fourth", 4);
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_for_each_listed_matches_list_order() {
+        let mut cache = Cache::new(100);
+        for i in 0..40 {
+            let parity = if i % 2 == 0 { "even" } else { "odd" };
+            cache.insert(format!("{}:{:02}", parity, i), i);
+        }
+
+        let variants: Vec<fn() -> ListProps> = vec![
+            || ListProps::default().unlimited(),
+            || ListProps::default().order(Order::Desc).limit(7),
+            || {
+                ListProps::default()
+                    .filter(Filter::StartWith("odd:".to_string()))
+                    .start_after_key("odd:09")
+                    .limit(5)
+            },
+            || {
+                ListProps::default()
+                    .order(Order::Desc)
+                    .collate(|a, b| a.len().cmp(&b.len()).then(b.cmp(a)))
+                    .limit(12)
+            },
+        ];
+
+        for props in variants {
+            let expected: Vec<(String, crate::Value)> = cache
+                .list(props())
+                .unwrap()
+                .into_iter()
+                .map(|(key, value)| (key, value.clone()))
+                .collect();
+
+            let mut visited = Vec::new();
+            cache
+                .for_each_listed(props(), |key, value| {
+                    visited.push((key.clone(), value.clone()));
+                    std::ops::ControlFlow::Continue(())
+                })
+                .unwrap();
+            assert_eq!(visited, expected);
+        }
+    }
+
+    #[test]
+    fn test_for_each_listed_stops_on_break() {
+        let mut cache = Cache::new(100);
+        for i in 0..50 {
+            cache.insert(format!("key{:02}", i), i);
+        }
+
+        let mut calls = 0;
+        cache
+            .for_each_listed(ListProps::default().unlimited(), |_, _| {
+                calls += 1;
+                if calls == 4 {
+                    std::ops::ControlFlow::Break(())
+                } else {
+                    std::ops::ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+        assert_eq!(calls, 4);
+
+        let result = cache.for_each_listed(ListProps::default().start_after_key("nope"), |_, _| {
+            std::ops::ControlFlow::Continue(())
+        });
+        assert_eq!(result, Err(crate::Error::SortKeyNotFound));
+    }
+
+    #[test]
+    fn test_count_matches_list_length() {
+        let mut cache = Cache::new(100);
+        for i in 0..30 {
+            cache.insert(format!("user:{}:name", i), i);
+        }
+        for i in 0..12 {
+            cache.insert(format!("order:{}", i), i);
+        }
+        cache.insert_with_ttl("user:expired:name", 0, std::time::Duration::from_millis(1));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        assert_eq!(cache.count(&Filter::None), 42);
+
+        let filters = vec![
+            Filter::None,
+            Filter::StartWith("user:".to_string()),
+            Filter::EndWith(":name".to_string()),
+            Filter::StartAndEndWith("user:1".to_string(), ":name".to_string()),
+            Filter::StartWith("missing".to_string()),
+        ];
+        for filter in filters {
+            let counted = cache.count(&filter);
+            let listed = cache
+                .list(ListProps::default().filter(filter).unlimited())
+                .unwrap()
+                .len();
+            assert_eq!(counted, listed);
+        }
+        assert_eq!(cache.count(&Filter::None), cache.len());
+    }
+
+    #[test]
+    fn test_insert_validator_rejects_empty_values() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.set_insert_validator(Some(|_: &str, value: &crate::Value| {
+            if value.as_string().is_empty() {
+                Err("empty value".to_string())
+            } else {
+                Ok(())
+            }
+        }));
+
+        assert_eq!(
+            cache.try_insert("empty", ""),
+            Err(crate::Error::Rejected("empty value".to_string()))
+        );
+        assert_eq!(
+            cache.try_insert_with_ttl("empty", "", std::time::Duration::from_secs(60)),
+            Err(crate::Error::Rejected("empty value".to_string()))
+        );
+        cache.insert("empty", "");
+        assert!(!cache.contains_key("empty"));
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(cache.try_insert("name", "alice"), Ok(()));
+        cache.insert_with_ttl("session", "abc", std::time::Duration::from_secs(60));
+        assert_eq!(cache.get("name"), Some(&"alice".to_value()));
+        assert!(cache.contains_key("session"));
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_insert_validator_enforces_key_prefix() {
+        let mut cache = Cache::builder(10)
+            .normalize_keys(|key| key.to_lowercase())
+            .validate_inserts(|key, _| {
+                if key.starts_with("user:") {
+                    Ok(())
+                } else {
+                    Err(format!("key '{}' must start with 'user:'", key))
+                }
+            })
+            .build();
+
+        assert_eq!(
+            cache.try_insert("admin:1", "root"),
+            Err(crate::Error::Rejected(
+                "key 'admin:1' must start with 'user:'".to_string()
+            ))
+        );
+        assert_eq!(cache.try_insert("USER:1", "alice"), Ok(()));
+        cache.insert("guest", "bob");
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("user:1"), Some(&"alice".to_value()));
+
+        cache.set_insert_validator(None);
+        cache.insert("guest", "bob");
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_merge_value() {
+        use valu3::prelude::NumberBehavior;
+
+        let sum = |a: &crate::Value, b: &crate::Value| {
+            (a.to_i64().unwrap_or(0) + b.to_i64().unwrap_or(0)).to_value()
+        };
+        let first_write = |existing: &crate::Value, _: &crate::Value| existing.clone();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert_with_ttl("hits", 1, std::time::Duration::from_secs(60));
+        cache.merge_value("hits", 2, sum);
+        cache.merge_value("hits", 3, sum);
+        assert_eq!(cache.get_as::<i64>("hits"), Some(6));
+        assert_eq!(
+            cache.get_ttl("hits"),
+            Some(std::time::Duration::from_secs(60))
+        );
+
+        cache.merge_value("owner", "alice", first_write);
+        cache.merge_value("owner", "bob", first_write);
+        assert_eq!(cache.get("owner"), Some(&"alice".to_value()));
+        assert_eq!(cache.get_ttl("owner"), None);
+
+        let values: Vec<crate::Value> = rx
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::Insert(data) => Some(data.value),
+                _ => None,
             })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                1.to_value(),
+                3i64.to_value(),
+                6i64.to_value(),
+                "alice".to_value()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cache_is_send_and_sync() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+
+        assert_send::<Cache>();
+        assert_sync::<Cache>();
+        assert_send::<crate::SyncCache>();
+        assert_sync::<crate::SyncCache>();
+        assert_send::<crate::CacheSnapshot>();
+        assert_sync::<crate::CacheSnapshot>();
+    }
+
+    #[test]
+    fn test_list_filter_key_len_range() {
+        let mut cache = Cache::new(10);
+        cache.insert("", 0);
+        cache.insert("id", 1);
+        cache.insert("key", 2);
+        cache.insert("user", 3);
+        cache.insert("session", 4);
+
+        let props = ListProps::default().filter(Filter::KeyLenRange { min: 2, max: 4 });
+        let keys: Vec<String> = cache
+            .list(props)
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["id", "key", "user"]);
+
+        assert_eq!(cache.count(&Filter::KeyLenRange { min: 0, max: 0 }), 1);
+        assert_eq!(cache.count(&Filter::KeyLenRange { min: 7, max: 100 }), 1);
+    }
+
+    #[test]
+    fn test_remove_many_returns_removed_keys() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        rx.try_iter().count();
+
+        let removed = cache.remove_many(["c", "missing", "a", "a"]);
+        assert_eq!(removed, vec!["c".to_string(), "a".to_string()]);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key("b"));
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::remove("c".to_string(), 3.to_value()),
+                Event::remove("a".to_string(), 1.to_value()),
+            ]
+        );
+
+        assert!(cache.remove_many(Vec::<&str>::new()).is_empty());
+        assert!(cache.remove_many(["x", "y"]).is_empty());
+    }
+
+    #[test]
+    fn test_string_cache_uses_native_strings() {
+        let mut cache = Cache::new_strings(10);
+        cache.insert_str("name", "Alice");
+        cache.insert_str(String::from("city"), "Lisbon");
+        cache.insert("age", 30);
+
+        assert_eq!(cache.get_str("name"), Some("Alice"));
+        assert_eq!(cache.get_str("city"), Some("Lisbon"));
+        assert_eq!(cache.get_str("age"), None);
+        assert_eq!(cache.get_str("missing"), None);
+        assert_eq!(cache.len(), 2);
+
+        let mut mixed = Cache::new(10);
+        mixed.insert("age", 30);
+        mixed.insert_str("name", "Bob");
+        assert_eq!(mixed.get_str("age"), None);
+        assert!(mixed.contains_key("age"));
+        assert_eq!(mixed.get_str("name"), Some("Bob"));
+    }
+
+    #[test]
+    fn test_simulate_hit_ratio() {
+        let trace = ["a", "b", "a", "c", "a", "b"];
+
+        // FIFO order: "c" evicts "a", then "a" evicts "b" and "b" evicts "c"
+        let lru = Cache::simulate(trace, 2, EvictionPolicy::Lru);
+        assert_eq!((lru.hits, lru.misses), (1, 5));
+        assert_eq!(lru.hit_ratio, 1.0 / 6.0);
+
+        // The read of "a" spares it when "c" and then "b" come in
+        let clock = Cache::simulate(trace, 2, EvictionPolicy::Clock);
+        assert_eq!((clock.hits, clock.misses), (2, 4));
+        assert_eq!(clock.hit_ratio, 2.0 / 6.0);
+
+        let fits = Cache::simulate(trace.map(String::from), 3, EvictionPolicy::Lru);
+        assert_eq!((fits.hits, fits.misses), (3, 3));
+        assert_eq!(fits.hit_ratio, 0.5);
+
+        let empty = Cache::simulate(Vec::<String>::new(), 2, EvictionPolicy::Lru);
+        assert_eq!(empty, crate::SimReport::default());
+    }
+
+    #[test]
+    fn test_from_events_replays_log() {
+        let events = vec![
+            Event::insert("a".to_string(), 1.to_value()),
+            Event::insert("b".to_string(), 2.to_value()),
+            Event::insert("a".to_string(), 10.to_value()),
+            Event::remove("b".to_string(), 2.to_value()),
+            Event::insert("c".to_string(), 3.to_value()),
+        ];
+        let mut cache = Cache::from_events(10, events.clone());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), Some(&10.to_value()));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(&3.to_value()));
+
+        let mut events = events;
+        events.push(Event::clear());
+        events.push(Event::insert("d".to_string(), 4.to_value()));
+        events.push(Event::remove("missing".to_string(), 0.to_value()));
+        events.push(Event::insert("e".to_string(), 5.to_value()));
+
+        let mut cache = Cache::from_events(10, events);
+        let keys: Vec<String> = cache
+            .list(ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec!["d", "e"]);
+        assert_eq!(cache.get("e"), Some(&5.to_value()));
+    }
+
+    #[test]
+    fn test_watch_receives_only_its_key() {
+        let mut cache = Cache::new(10);
+        let key1_events = cache.watch("key1");
+        let dropped = cache.watch("key1");
+        drop(dropped);
+
+        cache.insert("key1", 1);
+        cache.insert("key2", 2);
+        cache.insert("key1", 10);
+        cache.remove("key2").unwrap();
+        cache.remove("key1").unwrap();
+        cache.insert("key2", 3);
+        cache.clear();
+
+        assert_eq!(
+            key1_events.try_iter().collect::<Vec<_>>(),
+            vec![
+                Event::insert("key1".to_string(), 1.to_value()),
+                Event::insert("key1".to_string(), 10.to_value()),
+                Event::remove("key1".to_string(), 10.to_value()),
+                Event::clear(),
+            ]
         );
     }
+
+    #[test]
+    fn test_limit_zero_is_empty_on_every_list_path() {
+        let mut cache = Cache::new(100);
+        for i in 0..20 {
+            cache.insert(format!("key_{:02}", i), i);
+        }
+        let zero = || ListProps::default().limit(0);
+
+        // Stale index: partial selection
+        assert!(cache.list(zero()).unwrap().is_empty());
+        assert!(cache.list(zero().order(Order::Desc)).unwrap().is_empty());
+
+        // Up-to-date index
+        cache.list(ListProps::default()).unwrap();
+        assert!(cache.list(zero()).unwrap().is_empty());
+        assert!(cache.list(zero().order(Order::Desc)).unwrap().is_empty());
+        assert!(cache
+            .list(zero().start_after_key("key_05"))
+            .unwrap()
+            .is_empty());
+        assert!(cache
+            .list(zero().filter(Filter::StartWith("key_1".to_string())))
+            .unwrap()
+            .is_empty());
+
+        // Custom collation
+        assert!(cache
+            .list(zero().collate(|a, b| b.cmp(a)))
+            .unwrap()
+            .is_empty());
+
+        let mut visited = 0;
+        cache
+            .for_each_listed(zero(), |_, _| {
+                visited += 1;
+                std::ops::ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(visited, 0);
+
+        let (page, total) = cache.list_with_count(zero()).unwrap();
+        assert!(page.is_empty());
+        assert_eq!(total, 20);
+
+        assert!(cache.snapshot().list(zero()).unwrap().is_empty());
+
+        // A missing start key is still reported
+        assert!(cache.list(zero().start_after_key("missing")).is_err());
+    }
+
+    #[test]
+    fn test_insert_keeps_insertion_order_and_list_sorts_lazily() {
+        let mut cache = Cache::new(1000);
+        let keys: Vec<String> = (0..1000).rev().map(|i| format!("key_{:04}", i)).collect();
+        for key in &keys {
+            cache.insert(key.as_str(), 1);
+        }
+
+        // Inserts leave the map in insertion order instead of sorting it
+        let stored: Vec<&String> = cache.get_list();
+        assert_eq!(stored, keys.iter().collect::<Vec<_>>());
+
+        let listed: Vec<String> = cache
+            .list(ListProps::default().unlimited())
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(listed, sorted);
+
+        cache.remove("key_0000").unwrap();
+        cache.insert("key_0000", 2);
+        assert_eq!(cache.get_list().last().unwrap().as_str(), "key_0000");
+        let first = cache.list(ListProps::default().limit(1)).unwrap();
+        assert_eq!(first[0].0, "key_0000");
+    }
+
+    #[test]
+    fn test_list_sorted_after_scrambled_bulk_insert() {
+        let size = 997;
+        let mut cache = Cache::new(size * 2);
+        for i in 0..size {
+            cache.insert(format!("key{:04}", (i * 7919) % size), i);
+        }
+
+        let listed = |cache: &mut Cache| -> Vec<String> {
+            cache
+                .list(ListProps::default().unlimited())
+                .unwrap()
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect()
+        };
+        let expected: Vec<String> = (0..size).map(|i| format!("key{:04}", i)).collect();
+        assert_eq!(listed(&mut cache), expected);
+
+        for i in (0..size).step_by(100) {
+            cache.insert(format!("key{:04}a", i), i);
+        }
+        let keys = listed(&mut cache);
+        assert_eq!(keys.len(), size + 10);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_get_many_mut_disjoint_keys() {
+        let mut cache = Cache::new(10);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        cache.insert("d", 4);
+
+        let mut values = cache.get_many_mut(&["c", "missing", "a", "b"]);
+        assert!(values[1].is_none());
+        for value in values.iter_mut().flatten() {
+            **value = "updated".to_value();
+        }
+
+        assert_eq!(cache.get("a"), Some(&"updated".to_value()));
+        assert_eq!(cache.get("b"), Some(&"updated".to_value()));
+        assert_eq!(cache.get("c"), Some(&"updated".to_value()));
+        assert_eq!(cache.get("d"), Some(&4.to_value()));
+        assert!(cache.get_many_mut(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_get_many_mut_duplicate_key() {
+        let mut cache = Cache::new(10);
+        cache.set_key_normalizer(Some(|key: &str| key.to_lowercase()));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+
+        let values = cache.get_many_mut(&["a", "b", "A"]);
+        assert!(values[0].is_none());
+        assert_eq!(values[1].as_deref(), Some(&2.to_value()));
+        assert!(values[2].is_none());
+    }
+
+    #[test]
+    fn test_get_batch_ordered_returns_stored_keys() {
+        let mut cache = Cache::new(10);
+        cache.set_key_normalizer(Some(|key: &str| key.to_lowercase()));
+        cache.insert("a", 1);
+        cache.insert("B", 2);
+
+        assert_eq!(
+            cache.get_batch_ordered(&["A", "a", "b"], Order::Asc),
+            vec![("a".to_string(), 1.to_value()), ("b".to_string(), 2.to_value())]
+        );
+    }
+
+    #[test]
+    fn test_bounded_event_channel_drops_when_full() {
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        let mut cache = Cache::builder(10)
+            .bounded_sender(tx, crate::EventOverflow::Drop)
+            .build();
+
+        // Nobody drains `rx`, so a blocking send would hang here.
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.remove("a").unwrap();
+        cache.clear();
+
+        assert_eq!(cache.dropped_events_count(), 3);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(rx.try_recv(), Ok(Event::insert("a".to_string(), 1.to_value())));
+        assert!(rx.try_recv().is_err());
+
+        cache.insert("c", 3);
+        assert_eq!(cache.dropped_events_count(), 3);
+        assert_eq!(rx.try_recv(), Ok(Event::insert("c".to_string(), 3.to_value())));
+    }
+
+    #[test]
+    fn test_bounded_event_channel_blocks_by_default() {
+        let (tx, rx) = std::sync::mpsc::sync_channel(1);
+        let mut cache = Cache::new(10);
+        cache.set_bounded_event(tx, crate::EventOverflow::default());
+
+        let consumer = std::thread::spawn(move || rx.iter().take(3).count());
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+
+        assert_eq!(consumer.join().unwrap(), 3);
+        assert_eq!(cache.dropped_events_count(), 0);
+    }
+
+    #[test]
+    fn test_list_owned_matches_list_and_outlives_mutations() {
+        let mut cache = Cache::new(10);
+        for (key, value) in [("banana", 2), ("apple", 1), ("cherry", 3), ("date", 4)] {
+            cache.insert(key, value);
+        }
+
+        let props = || {
+            ListProps::default()
+                .order(Order::Desc)
+                .filter(Filter::EndWith("e".to_string()))
+                .limit(5)
+        };
+        let borrowed: Vec<(String, crate::Value)> = cache
+            .list(props())
+            .unwrap()
+            .into_iter()
+            .map(|(key, value)| (key, value.clone()))
+            .collect();
+        let owned = cache.list_owned(props()).unwrap();
+        assert_eq!(owned, borrowed);
+
+        cache.insert("apple", 10);
+        cache.remove("date").unwrap();
+        cache.clear();
+
+        assert_eq!(
+            owned,
+            vec![
+                ("date".to_string(), 4.to_value()),
+                ("apple".to_string(), 1.to_value()),
+            ]
+        );
+        assert_eq!(
+            cache.list_owned(ListProps::default().start_after_key("missing")),
+            Err(crate::Error::SortKeyNotFound)
+        );
+    }
+
+    #[test]
+    fn test_evict_from_prefix_only_touches_the_targeted_prefix() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(6, tx);
+        let evicted = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = std::sync::Arc::clone(&evicted);
+        cache.on_evict(move |key, _| sink.lock().unwrap().push(key.to_string()));
+
+        for i in 0..3 {
+            cache.insert(format!("gold:{}", i), i);
+            cache.insert(format!("free:{}", i), i);
+        }
+        assert!(cache.pin("free:1"));
+        rx.try_iter().count();
+
+        assert_eq!(
+            cache.evict_from_prefix("free:"),
+            Some(("free:0".to_string(), 0.to_value()))
+        );
+        assert_eq!(
+            cache.evict_from_prefix("free:"),
+            Some(("free:2".to_string(), 2.to_value()))
+        );
+        assert_eq!(cache.evict_from_prefix("free:"), None);
+        assert_eq!(cache.evict_from_prefix("silver:"), None);
+
+        assert_eq!(*evicted.lock().unwrap(), vec!["free:0", "free:2"]);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Event::remove("free:0".to_string(), 0.to_value()),
+                Event::remove("free:2".to_string(), 2.to_value()),
+            ]
+        );
+        assert_eq!(
+            cache.get_list(),
+            vec!["gold:0", "gold:1", "free:1", "gold:2"]
+        );
+        assert_eq!(cache.list(ListProps::default()).unwrap().len(), cache.len());
+    }
+
+    #[test]
+    fn test_shrink_to_returns_evicted_entries_in_order() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        let evicted = std::sync::Arc::new(std::sync::Mutex::new(0));
+        let counter = std::sync::Arc::clone(&evicted);
+        cache.on_evict(move |_, _| *counter.lock().unwrap() += 1);
+        for i in 0..10 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert!(cache.pin("key1"));
+        rx.try_iter().count();
+
+        let shrunk = cache.shrink_to(4);
+
+        let expected: Vec<_> = [0, 2, 3, 4, 5, 6]
+            .into_iter()
+            .map(|i| (format!("key{}", i), i.to_value()))
+            .collect();
+        assert_eq!(shrunk, expected);
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.capacity(), 10);
+        assert_eq!(cache.get_list(), vec!["key1", "key7", "key8", "key9"]);
+        assert_eq!(*evicted.lock().unwrap(), 6);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            expected
+                .into_iter()
+                .map(|(key, value)| Event::remove(key, value))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(cache.shrink_to(4).is_empty());
+        assert_eq!(cache.shrink_to(0).len(), 3);
+        assert_eq!(cache.get_list(), vec!["key1"]);
+    }
+
+    #[test]
+    fn test_list_byte_budget_truncates_before_limit() {
+        let mut cache = Cache::new(100);
+        for i in 0..5 {
+            cache.insert(format!("big{}", i), "x".repeat(1000));
+        }
+        let keys = |page: Vec<(String, &crate::Value)>| -> Vec<String> {
+            page.into_iter().map(|(key, _)| key).collect()
+        };
+
+        let props = ListProps::default().limit(4).byte_budget(2500);
+        assert_eq!(keys(cache.list(props).unwrap()), vec!["big0", "big1"]);
+
+        let props = ListProps::default().limit(2).byte_budget(1_000_000);
+        assert_eq!(cache.list(props).unwrap().len(), 2);
+
+        // The first entry is returned even when it alone is over budget
+        let props = ListProps::default().byte_budget(10).start_after_key("big2");
+        assert_eq!(keys(cache.list(props).unwrap()), vec!["big3"]);
+
+        let props = ListProps::default().order(Order::Desc).byte_budget(2500);
+        assert_eq!(keys(cache.list(props).unwrap()), vec!["big4", "big3"]);
+
+        let mut visited = Vec::new();
+        cache
+            .for_each_listed(ListProps::default().byte_budget(2500), |key, _| {
+                visited.push(key.clone());
+                std::ops::ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(visited, vec!["big0", "big1"]);
+
+        let snapshot = cache.snapshot();
+        let page = snapshot
+            .list(ListProps::default().byte_budget(2500))
+            .unwrap();
+        assert_eq!(keys(page), vec!["big0", "big1"]);
+
+        // Small pages of a large unsorted cache take the partial-selection path
+        for i in 0..80 {
+            cache.insert(format!("small{:02}", i), i);
+        }
+        let props = ListProps::default().limit(3).byte_budget(1500);
+        assert_eq!(keys(cache.list(props).unwrap()), vec!["big0"]);
+    }
+
+    #[test]
+    fn test_eviction_guard_skips_vetoed_victim() {
+        use crate::{EvictDecision, EvictionPolicy};
+
+        for policy in [EvictionPolicy::Lru, EvictionPolicy::Clock] {
+            let mut cache = Cache::new(3);
+            cache.set_eviction_policy(policy);
+            cache.guard_eviction(|key, _| {
+                if key == "a" {
+                    EvictDecision::Keep
+                } else {
+                    EvictDecision::Evict
+                }
+            });
+
+            cache.insert("a", 1);
+            cache.insert("b", 2);
+            cache.insert("c", 3);
+            cache.insert("d", 4);
+
+            assert!(cache.contains_key("a"), "{:?}", policy);
+            assert!(!cache.contains_key("b"), "{:?}", policy);
+            assert_eq!(cache.len(), 3);
+
+            assert_eq!(
+                cache.evict_from_prefix(""),
+                Some(("c".to_string(), 3.to_value()))
+            );
+
+            // Keeping every candidate falls back to the policy's victim
+            cache.guard_eviction(|_, _| EvictDecision::Keep);
+            cache.insert("e", 5);
+            cache.insert("f", 6);
+            assert_eq!(cache.len(), 3, "{:?}", policy);
+            assert!(cache.contains_key("f"));
+            assert_eq!(cache.evict_from_prefix(""), None);
+
+            cache.remove_eviction_guard();
+            assert!(cache.evict_from_prefix("").is_some());
+        }
+    }
+
+    #[test]
+    fn test_insert_transaction_is_all_or_nothing() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.set_insert_validator(Some(|_: &str, value: &crate::Value| match value {
+            crate::Value::String(_) => Ok(()),
+            _ => Err("strings only".to_string()),
+        }));
+        cache.insert("existing", "zero");
+        while rx.try_recv().is_ok() {}
+
+        let batch = vec![
+            ("a".to_string(), "one".to_value()),
+            ("b".to_string(), 2.to_value()),
+            ("c".to_string(), "three".to_value()),
+        ];
+        assert_eq!(
+            cache.insert_transaction(batch),
+            Err(crate::Error::Rejected("strings only".to_string()))
+        );
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains_key("a"));
+        assert!(!cache.contains_key("c"));
+        assert!(rx.try_recv().is_err());
+
+        let batch = vec![("a".to_string(), "one"), ("b".to_string(), "two")];
+        assert_eq!(cache.insert_transaction(batch), Ok(()));
+        assert_eq!(cache.get("a"), Some(&"one".to_value()));
+        assert_eq!(cache.get("b"), Some(&"two".to_value()));
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_insert_transaction_checks_room_up_front() {
+        let mut cache = Cache::new(3);
+        cache.insert("config", 0);
+        cache.pin("config");
+        cache.insert("b", 1);
+
+        // "b" is updated by the batch, so only the pinned entry is left to evict
+        let batch = vec![
+            ("b".to_string(), 2),
+            ("c".to_string(), 3),
+            ("d".to_string(), 4),
+        ];
+        assert_eq!(
+            cache.insert_transaction(batch),
+            Err(crate::Error::CapacityExceeded)
+        );
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("b"), Some(&1.to_value()));
+
+        // The oldest entry is updated by the batch, so the next one is evicted
+        let mut cache = Cache::new(2);
+        cache.insert("x", 1);
+        cache.insert("y", 2);
+        let batch = vec![("x".to_string(), 10), ("z".to_string(), 30)];
+        assert_eq!(cache.insert_transaction(batch), Ok(()));
+        assert_eq!(cache.get("x"), Some(&10.to_value()));
+        assert_eq!(cache.get("z"), Some(&30.to_value()));
+        assert!(!cache.contains_key("y"));
+        assert!(!cache.is_pinned("x"));
+
+        // Validation runs before the capacity check
+        let mut cache = Cache::new_strings(0);
+        assert_eq!(
+            cache.insert_transaction(vec![("a".to_string(), 1)]),
+            Err(crate::Error::Rejected("expected a string value".to_string()))
+        );
+        assert_eq!(
+            cache.insert_transaction(vec![("a".to_string(), "one")]),
+            Err(crate::Error::CapacityExceeded)
+        );
+    }
+
+    #[test]
+    fn test_watch_sweeps_dropped_watchers() {
+        let mut cache = Cache::new(10);
+        for i in 0..100 {
+            drop(cache.watch(&format!("key{}", i)));
+        }
+        for _ in 0..100 {
+            drop(cache.watch("same"));
+        }
+        assert!(cache.watcher_count() <= 64);
+
+        let kept = cache.watch("kept");
+        for i in 0..200 {
+            drop(cache.watch(&format!("other{}", i)));
+        }
+        assert!(cache.watcher_count() <= 129);
+
+        cache.insert("kept", 1);
+        assert_eq!(
+            kept.try_recv().unwrap(),
+            Event::insert("kept".to_string(), 1.to_value())
+        );
+    }
+
+    #[test]
+    fn test_list_respects_start_after_key() {
+        let mut cache = Cache::new(10);
+        
+        // Insert items with predictable keys
+        for i in 0..10 {
+            cache.insert(format!("key_{:02}", i), i);
+        }
+
+        // Test starting after key_05
+        let props = ListProps::default().start_after_key("key_05");
+        let results = cache.list(props).unwrap();
+        
+        // Should return key_06 through key_09 (4 items)
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].0, "key_06");
+        assert_eq!(results[1].0, "key_07");
+        assert_eq!(results[2].0, "key_08");
+        assert_eq!(results[3].0, "key_09");
+
+        // Test starting after last key (should return empty)
+        let props = ListProps::default().start_after_key("key_09");
+        let results = cache.list(props).unwrap();
+        assert_eq!(results.len(), 0);
+
+        // Test starting after non-existent key (should return error)
+        let props = ListProps::default().start_after_key("non_existent");
+        let results = cache.list(props);
+        assert!(results.is_err());
+    }
+
+    #[test]
+    fn test_list_pagination_combined() {
+        let mut cache = Cache::new(20);
+        
+        // Insert 20 items
+        for i in 0..20 {
+            cache.insert(format!("key_{:02}", i), i);
+        }
+
+        // Get first page: 5 items starting from beginning
+        let props = ListProps::default().limit(5);
+        let page1 = cache.list(props).unwrap();
+        assert_eq!(page1.len(), 5);
+        assert_eq!(page1[0].0, "key_00");
+        assert_eq!(page1[4].0, "key_04");
+
+        // Get second page: 5 items starting after key_04
+        let props = ListProps::default()
+            .start_after_key("key_04")
+            .limit(5);
+        let page2 = cache.list(props).unwrap();
+        assert_eq!(page2.len(), 5);
+        assert_eq!(page2[0].0, "key_05");
+        assert_eq!(page2[4].0, "key_09");
+
+        // Get third page: 5 items starting after key_09
+        let props = ListProps::default()
+            .start_after_key("key_09")
+            .limit(5);
+        let page3 = cache.list(props).unwrap();
+        assert_eq!(page3.len(), 5);
+        assert_eq!(page3[0].0, "key_10");
+        assert_eq!(page3[4].0, "key_14");
+
+        // Get fourth page: should return remaining 5 items
+        let props = ListProps::default()
+            .start_after_key("key_14")
+            .limit(5);
+        let page4 = cache.list(props).unwrap();
+        assert_eq!(page4.len(), 5);
+        assert_eq!(page4[0].0, "key_15");
+        assert_eq!(page4[4].0, "key_19");
+
+        // Try to get fifth page: should be empty
+        let props = ListProps::default()
+            .start_after_key("key_19")
+            .limit(5);
+        let page5 = cache.list(props).unwrap();
+        assert_eq!(page5.len(), 0);
+    }
+
+    #[test]
+    fn test_list_pagination_with_filter() {
+        let mut cache = Cache::new(30);
+        
+        // Insert items with different prefixes
+        for i in 0..10 {
+            cache.insert(format!("user_{:02}", i), i);
+            cache.insert(format!("admin_{:02}", i), i + 10);
+            cache.insert(format!("guest_{:02}", i), i + 20);
+        }
+
+        // Get first page of user items
+        let props = ListProps::default()
+            .filter(Filter::StartWith("user_".to_string()))
+            .limit(3);
+        let page1 = cache.list(props).unwrap();
+        assert_eq!(page1.len(), 3);
+        assert_eq!(page1[0].0, "user_00");
+        assert_eq!(page1[1].0, "user_01");
+        assert_eq!(page1[2].0, "user_02");
+
+        // Get second page of user items
+        let props = ListProps::default()
+            .filter(Filter::StartWith("user_".to_string()))
+            .start_after_key("user_02")
+            .limit(3);
+        let page2 = cache.list(props).unwrap();
+        assert_eq!(page2.len(), 3);
+        assert_eq!(page2[0].0, "user_03");
+        assert_eq!(page2[1].0, "user_04");
+        assert_eq!(page2[2].0, "user_05");
+    }
+
+    #[test]
+    fn test_list_pagination_desc_order() {
+        let mut cache = Cache::new(15);
+        
+        // Insert 15 items
+        for i in 0..15 {
+            cache.insert(format!("key_{:02}", i), i);
+        }
+
+        // Get first page in descending order
+        let props = ListProps::default()
+            .order(Order::Desc)
+            .limit(5);
+        let page1 = cache.list(props).unwrap();
+        assert_eq!(page1.len(), 5);
+        assert_eq!(page1[0].0, "key_14");
+        assert_eq!(page1[4].0, "key_10");
+
+        // Get second page in descending order
+        let props = ListProps::default()
+            .order(Order::Desc)
+            .start_after_key("key_10")
+            .limit(5);
+        let page2 = cache.list(props).unwrap();
+        assert_eq!(page2.len(), 5);
+        assert_eq!(page2[0].0, "key_09");
+        assert_eq!(page2[4].0, "key_05");
+
+        // Get third page in descending order
+        let props = ListProps::default()
+            .order(Order::Desc)
+            .start_after_key("key_05")
+            .limit(5);
+        let page3 = cache.list(props).unwrap();
+        assert_eq!(page3.len(), 5);
+        assert_eq!(page3[0].0, "key_04");
+        assert_eq!(page3[4].0, "key_00");
+    }
+
+    #[test]
+    fn test_filter_ends_with() {
+        let mut cache = Cache::new(10);
+
+        cache.insert("postmodern", 8);
+        cache.insert("postpone", 6);
+        cache.insert("precept", 2);
+        cache.insert("postmortem", 9);
+        cache.insert("precaution", 3);
+        cache.insert("precede", 1);
+        cache.insert("precognition", 5);
+        cache.insert("postmark", 10);
+        cache.insert("postgraduate", 7);
+        cache.insert("preconceive", 4);
+
+        let result_res = cache.list(Filter::EndWith("tion".to_string()));
+
+        assert_eq!(result_res.is_ok(), true);
+
+        let result = match result_res {
+            Ok(result) => result,
+            Err(_) => panic!("Error"),
+        };
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], ("precaution".to_string(), &3.to_value()));
+        assert_eq!(result[1], ("precognition".to_string(), &5.to_value()));
+    }
+
+    #[test]
+    fn test_filter_start_and_end_with() {
+        let mut cache = Cache::new(10);
+
+        cache.insert("applemorepie", 1);
+        cache.insert("banana", 2);
+        cache.insert("pineapplepie", 3);
+
+        let list_props = ListProps::default()
+            .filter(Filter::StartAndEndWith(
+                "apple".to_string(),
+                "pie".to_string(),
+            ))
+            .order(Order::Asc);
+
+        let result_res = cache.list(list_props);
+
+        assert_eq!(result_res.is_ok(), true);
+
+        let result = match result_res {
+            Ok(result) => result,
+            Err(_) => panic!("Error"),
+        };
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0], ("applemorepie".to_string(), &1.to_value()));
+    }
+
+    #[test]
+    fn test_with_sender() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+
+        cache.insert("key2", 2);
+        cache.insert("key3", 3);
+
+        let mut clone_cache = cache.clone();
+        std::thread::spawn(move || {
+            clone_cache.insert("key1", 1);
+        })
+        .join()
+        .unwrap();
+
+        let mut items = Vec::new();
+
+        for data in rx {
+            items.push(data);
+
+            if items.len() == 3 {
+                break;
+            }
+        }
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0],
+            Event::Insert(EventData {
+                key: "key2".to_string(),
+                value: 2.to_value()
+            })
+        );
+        assert_eq!(
+            items[1],
+            Event::Insert(EventData {
+                key: "key3".to_string(),
+                value: 3.to_value()
+            })
+        );
+        assert_eq!(
+            items[2],
+            Event::Insert(EventData {
+                key: "key1".to_string(),
+                value: 1.to_value()
+            })
+        );
+    }
+
+    #[test]
+    fn test_warm_up_respects_capacity() {
+        let mut cache = Cache::new(3);
+        cache.insert("existing", 0);
+
+        let loaded = cache.warm_up(|| {
+            vec![
+                ("existing".to_string(), 10),
+                ("a".to_string(), 1),
+                ("b".to_string(), 2),
+                ("c".to_string(), 3),
+            ]
+        });
+
+        assert_eq!(loaded, 3);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.get("existing"), Some(&10.to_value()));
+        assert_eq!(cache.get("a"), Some(&1.to_value()));
+        assert_eq!(cache.get("b"), Some(&2.to_value()));
+        assert_eq!(cache.get("c"), None);
+    }
+
+    #[test]
+    fn test_warm_up_normalizes_keys_and_counts_stored_pairs() {
+        let mut cache = Cache::new(2);
+        cache.set_key_normalizer(Some(|key: &str| key.to_lowercase()));
+        cache.set_insert_validator(Some(|key: &str, _: &crate::Value| match key {
+            "blocked" => Err("read-only".to_string()),
+            _ => Ok(()),
+        }));
+        cache.insert("a", 0);
+
+        let loaded = cache.warm_up(|| {
+            vec![
+                ("BLOCKED".to_string(), 1),
+                ("b".to_string(), 2),
+                ("A".to_string(), 10),
+            ]
+        });
+
+        assert_eq!(loaded, 2);
+        assert_eq!(cache.get("a"), Some(&10.to_value()));
+        assert_eq!(cache.get("b"), Some(&2.to_value()));
+        assert!(!cache.contains_key("blocked"));
+
+        let loaded = cache.replace_all(vec![
+            ("BLOCKED".to_string(), 1),
+            ("C".to_string(), 3),
+            ("D".to_string(), 4),
+            ("c".to_string(), 30),
+        ]);
+
+        assert_eq!(loaded, 3);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("c"), Some(&30.to_value()));
+        assert_eq!(cache.get("d"), Some(&4.to_value()));
+    }
+
+    #[test]
+    fn test_warm_up_keys_skips_cached_keys() {
+        let mut cache = Cache::new(10);
+        cache.insert("key1", "cached");
+
+        let calls = std::cell::RefCell::new(Vec::new());
+        let loaded = cache.warm_up_keys(&["key1", "key2", "key3"], |key| {
+            calls.borrow_mut().push(key.to_string());
+            if key == "key3" {
+                None
+            } else {
+                Some(format!("loaded_{}", key))
+            }
+        });
+
+        assert_eq!(loaded, 1);
+        assert_eq!(*calls.borrow(), vec!["key2".to_string(), "key3".to_string()]);
+        assert_eq!(cache.get("key1"), Some(&"cached".to_value()));
+        assert_eq!(cache.get("key2"), Some(&"loaded_key2".to_value()));
+        assert_eq!(cache.get("key3"), None);
+    }
+
+    #[test]
+    fn test_warm_up_keys_respects_capacity() {
+        let mut cache = Cache::new(2);
+        cache.insert("key1", 1);
+
+        let loaded = cache.warm_up_keys(&["key2", "key3", "key4"], |_| Some(0));
+
+        assert_eq!(loaded, 1);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("key1"), Some(&1.to_value()));
+        assert_eq!(cache.get("key2"), Some(&0.to_value()));
+        assert_eq!(cache.get("key3"), None);
+    }
+
+    #[test]
+    fn test_clock_eviction_keeps_hot_entries() {
+        let mut cache = Cache::new(3);
+        cache.set_eviction_policy(EvictionPolicy::Clock);
+
+        cache.insert("hot", 0);
+        cache.insert("cold1", 1);
+        cache.insert("cold2", 2);
+
+        for i in 0..10 {
+            cache.get("hot");
+            cache.insert(format!("new{}", i), i);
+            assert!(cache.contains_key("hot"));
+        }
+
+        assert_eq!(cache.len(), 3);
+        assert!(!cache.contains_key("cold1"));
+        assert!(!cache.contains_key("cold2"));
+    }
+
+    #[test]
+    fn test_lru_eviction_drops_oldest_regardless_of_access() {
+        let mut cache = Cache::new(3);
+
+        cache.insert("hot", 0);
+        cache.insert("cold1", 1);
+        cache.insert("cold2", 2);
+
+        cache.get("hot");
+        cache.insert("new", 3);
+
+        assert!(!cache.contains_key("hot"));
+        assert!(cache.contains_key("cold1"));
+    }
+
+    #[test]
+    fn test_clock_eviction_decays_frequency() {
+        let mut cache = Cache::new(2);
+        cache.set_eviction_policy(EvictionPolicy::Clock);
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.get("a");
+
+        // "a" gets a second chance, "b" is evicted
+        cache.insert("c", 3);
+        assert!(cache.contains_key("a"));
+        assert!(!cache.contains_key("b"));
+
+        // The sweep decayed "a", so without further reads it is evicted next
+        cache.insert("d", 4);
+        assert!(!cache.contains_key("a"));
+        assert!(cache.contains_key("c"));
+        assert!(cache.contains_key("d"));
+    }
+
+    #[test]
+    fn test_clock_eviction_sends_remove_event() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(1, tx);
+        cache.set_eviction_policy(EvictionPolicy::Clock);
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+
+        let events: Vec<_> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("a".to_string(), 1.to_value()),
+                Event::remove("a".to_string(), 1.to_value()),
+                Event::insert("b".to_string(), 2.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_key_value_returns_stored_key() {
+        let mut cache = Cache::new(10);
+        cache.insert("key1", 1);
+        cache.insert("key2", 2);
+
+        let stored_ptr = cache.get_list()[1].as_ptr();
+
+        let lookup = String::from("key2");
+        let (key, value) = cache.get_key_value(&lookup).unwrap();
+        assert_eq!(key, "key2");
+        assert_eq!(value, &2.to_value());
+        assert_eq!(key.as_ptr(), stored_ptr);
+        assert_ne!(key.as_ptr(), lookup.as_ptr());
+
+        assert_eq!(cache.get_key_value("missing"), None);
+    }
+
+    #[test]
+    fn test_contains_all_and_any() {
+        let mut cache = Cache::new(10);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+
+        assert!(cache.contains_all(&["a", "b", "c"]));
+        assert!(!cache.contains_all(&["a", "x", "c"]));
+        assert!(!cache.contains_all(&["x", "y"]));
+        assert!(cache.contains_all(&[]));
+
+        assert!(cache.contains_any(&["a", "b"]));
+        assert!(cache.contains_any(&["x", "c"]));
+        assert!(!cache.contains_any(&["x", "y"]));
+        assert!(!cache.contains_any(&[]));
+    }
+
+    #[test]
+    fn test_map_values_doubles_integers() {
+        use valu3::prelude::NumberBehavior;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("a", 1i64);
+        cache.insert("b", 2i64);
+        cache.insert("zero", 0i64);
+        let _: Vec<_> = rx.try_iter().collect();
+
+        let changed = cache.map_values(|_, value| {
+            *value = (value.to_i64().unwrap() * 2).to_value();
+        });
+
+        assert_eq!(changed, 2);
+        assert_eq!(cache.get("a"), Some(&2i64.to_value()));
+        assert_eq!(cache.get("b"), Some(&4i64.to_value()));
+        assert_eq!(cache.get("zero"), Some(&0i64.to_value()));
+
+        let events: Vec<_> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("a".to_string(), 2i64.to_value()),
+                Event::insert("b".to_string(), 4i64.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_partial_selection_matches_full_sort() {
+        let mut cache = Cache::new(500);
+
+        // Insert in a scrambled order so insertion order differs from key order
+        for i in 0..400 {
+            let n = (i * 37) % 400;
+            let prefix = if n % 3 == 0 { "user" } else { "item" };
+            cache.insert(format!("{}_{:03}", prefix, n), n);
+        }
+
+        let mut sorted: Vec<String> = cache.get_list().into_iter().cloned().collect();
+        sorted.sort();
+
+        let expected = |order: &Order, filter: &str, start_after: Option<&str>, limit: usize| {
+            let mut keys: Vec<String> = sorted.clone();
+            if let Order::Desc = order {
+                keys.reverse();
+            }
+            let skip = match start_after {
+                Some(start) => keys.iter().position(|k| k == start).unwrap() + 1,
+                None => 0,
+            };
+            keys.into_iter()
+                .skip(skip)
+                .filter(|k| k.starts_with(filter))
+                .take(limit)
+                .collect::<Vec<_>>()
+        };
+
+        for order in [Order::Asc, Order::Desc] {
+            for filter in ["", "user", "item_1"] {
+                for start_after in [None, Some("item_200"), Some("user_300")] {
+                    let mut props = ListProps::default()
+                        .order(order.clone())
+                        .filter(Filter::StartWith(filter.to_string()))
+                        .limit(10);
+                    if let Some(start) = start_after {
+                        props = props.start_after_key(start);
+                    }
+
+                    let result: Vec<String> = cache
+                        .list(props)
+                        .unwrap()
+                        .into_iter()
+                        .map(|(k, _)| k)
+                        .collect();
+
+                    assert_eq!(result, expected(&order, filter, start_after, 10));
+                }
+            }
+        }
+
+        let props = ListProps::default().start_after_key("missing").limit(10);
+        assert!(cache.list(props).is_err());
+
+        let props = ListProps::default().limit(0);
+        assert_eq!(cache.list(props).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_list_tracks_key_changes_between_calls() {
+        let mut cache = Cache::new(10);
+        let keys = |cache: &mut Cache, props: ListProps| -> Vec<String> {
+            cache
+                .list(props)
+                .unwrap()
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect()
+        };
+
+        cache.insert("b", 2);
+        cache.insert("d", 4);
+        assert_eq!(keys(&mut cache, ListProps::default()), vec!["b", "d"]);
+
+        cache.insert("a", 1);
+        cache.insert("c", 3);
+        assert_eq!(
+            keys(&mut cache, ListProps::default()),
+            vec!["a", "b", "c", "d"]
+        );
+
+        cache.remove("b").unwrap();
+        assert_eq!(
+            keys(&mut cache, ListProps::default().order(Order::Desc)),
+            vec!["d", "c", "a"]
+        );
+
+        cache.insert("c", 30);
+        let list = cache
+            .list(ListProps::default().start_after_key("a"))
+            .unwrap();
+        assert_eq!(
+            list,
+            vec![
+                ("c".to_string(), &30.to_value()),
+                ("d".to_string(), &4.to_value())
+            ]
+        );
+
+        let props = ListProps::default().start_after_key("b");
+        assert!(cache.list(props).is_err());
+
+        cache.clear();
+        assert!(keys(&mut cache, ListProps::default()).is_empty());
+
+        cache.insert("z", 26);
+        assert_eq!(keys(&mut cache, ListProps::default()), vec!["z"]);
+    }
+
+    #[test]
+    fn test_prefix_histogram() {
+        let mut cache = Cache::new(10);
+        cache.insert("user:1", "alice");
+        cache.insert("user:2", "bob");
+        cache.insert("user:3", "carol");
+        cache.insert("session:x", "active");
+        cache.insert("config:app:theme", "dark");
+        cache.insert("standalone", "value");
+
+        let histogram = cache.prefix_histogram(':');
+
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram["user"], 3);
+        assert_eq!(histogram["session"], 1);
+        assert_eq!(histogram["config"], 1);
+        assert_eq!(histogram["standalone"], 1);
+
+        assert!(Cache::new(10).prefix_histogram(':').is_empty());
+    }
+
+    #[test]
+    fn test_recent_events_ring_buffer() {
+        let mut cache = Cache::new(10);
+        cache.insert("ignored", 0);
+        assert!(cache.recent_events().is_empty());
+
+        cache.set_recent_events_capacity(3);
+        assert_eq!(cache.get_recent_events_capacity(), 3);
+
+        cache.insert("key1", 1);
+        cache.insert("key2", 2);
+        assert_eq!(
+            cache.recent_events(),
+            vec![
+                Event::insert("key1".to_string(), 1.to_value()),
+                Event::insert("key2".to_string(), 2.to_value()),
+            ]
+        );
+
+        cache.insert("key3", 3);
+        cache.remove("key1").unwrap();
+        cache.clear();
+        assert_eq!(
+            cache.recent_events(),
+            vec![
+                Event::insert("key3".to_string(), 3.to_value()),
+                Event::remove("key1".to_string(), 1.to_value()),
+                Event::clear(),
+            ]
+        );
+
+        cache.set_recent_events_capacity(1);
+        assert_eq!(cache.recent_events(), vec![Event::clear()]);
+
+        cache.set_recent_events_capacity(0);
+        cache.insert("key4", 4);
+        assert!(cache.recent_events().is_empty());
+    }
+
+    #[test]
+    fn test_recent_events_does_not_consume_sender() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.set_recent_events_capacity(10);
+
+        cache.insert("key1", 1);
+        cache.insert("key2", 2);
+
+        assert_eq!(cache.recent_events().len(), 2);
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), cache.recent_events());
+    }
+
+    #[test]
+    fn test_compound_keys() {
+        let mut cache = Cache::new(10);
+
+        cache.insert_tuple(("user", "42"), "alice");
+        cache.insert_tuple(["org", "acme", "team"], "core");
+        cache.insert_tuple(&["session", "x"][..], "active");
+
+        assert_eq!(cache.get("user:42"), Some(&"alice".to_value()));
+        assert_eq!(cache.get_tuple(("user", "42")), Some(&"alice".to_value()));
+        assert_eq!(
+            cache.get_tuple(vec!["org", "acme", "team"]),
+            Some(&"core".to_value())
+        );
+        assert_eq!(cache.get_tuple(("session", "x")), Some(&"active".to_value()));
+        assert_eq!(cache.get_tuple(("user", "43")), None);
+
+        assert_eq!(
+            cache.split_key("org:acme:team"),
+            vec!["org", "acme", "team"]
+        );
+        assert_eq!(cache.split_key("plain"), vec!["plain"]);
+    }
+
+    #[test]
+    fn test_compound_keys_escape_separator() {
+        let mut cache = Cache::new(10);
+
+        cache.insert_tuple(("a:b", "c"), 1);
+        cache.insert_tuple(("a", "b:c"), 2);
+        cache.insert_tuple(("a", "b", "c"), 3);
+        cache.insert_tuple(("a\\", "b"), 4);
+
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.get_tuple(("a:b", "c")), Some(&1.to_value()));
+        assert_eq!(cache.get_tuple(("a", "b:c")), Some(&2.to_value()));
+        assert_eq!(cache.get_tuple(("a", "b", "c")), Some(&3.to_value()));
+        assert_eq!(cache.get_tuple(("a\\", "b")), Some(&4.to_value()));
+
+        for segments in [
+            vec!["a:b", "c"],
+            vec!["a", "b:c"],
+            vec!["a\\", "b"],
+            vec!["a\\:", ":b", ""],
+        ] {
+            let key = cache.compound_key(segments.clone());
+            assert_eq!(cache.split_key(&key), segments);
+        }
+    }
+
+    #[test]
+    fn test_compound_keys_custom_separator() {
+        let mut cache = Cache::new(10);
+        cache.set_key_separator('/');
+        assert_eq!(cache.get_key_separator(), '/');
+
+        cache.insert_tuple(("user", "a:b"), "alice");
+        cache.insert_tuple(("user", "c/d"), "bob");
+
+        assert_eq!(cache.get("user/a:b"), Some(&"alice".to_value()));
+        assert_eq!(cache.get("user/c\\/d"), Some(&"bob".to_value()));
+        assert_eq!(cache.split_key("user/c\\/d"), vec!["user", "c/d"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compound_keys_reject_escape_separator() {
+        let mut cache = Cache::new(10);
+        cache.set_key_separator('\\');
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(3, tx);
+
+        cache.insert("old1", 1);
+        cache.insert("old2", 2);
+        rx.try_iter().for_each(drop);
+
+        let loaded = cache.replace_all(vec![
+            ("new1".to_string(), 10),
+            ("new2".to_string(), 20),
+            ("new3".to_string(), 30),
+            ("new4".to_string(), 40),
+        ]);
+
+        assert_eq!(loaded, 3);
+        assert_eq!(cache.len(), 3);
+        assert!(!cache.contains_key("old1"));
+        assert!(!cache.contains_key("old2"));
+        assert!(!cache.contains_key("new4"));
+        assert_eq!(cache.get("new1"), Some(&10.to_value()));
+        assert_eq!(cache.get("new3"), Some(&30.to_value()));
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::clear(),
+                Event::insert("new1".to_string(), 10.to_value()),
+                Event::insert("new2".to_string(), 20.to_value()),
+                Event::insert("new3".to_string(), 30.to_value()),
+            ]
+        );
+
+        assert_eq!(cache.replace_all(Vec::<(String, i32)>::new()), 0);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_get_batch_ordered() {
+        let mut cache = Cache::new(10);
+        cache.insert("banana", 2);
+        cache.insert("apple", 1);
+        cache.insert("cherry", 3);
+        cache.insert("date", 4);
+
+        let keys = ["cherry", "missing", "apple", "date", "apple"];
+
+        let results = cache.get_batch_ordered(&keys, Order::Asc);
+        assert_eq!(
+            results,
+            vec![
+                ("apple".to_string(), 1.to_value()),
+                ("cherry".to_string(), 3.to_value()),
+                ("date".to_string(), 4.to_value()),
+            ]
+        );
+
+        let results = cache.get_batch_ordered(&keys, Order::Desc);
+        let result_keys: Vec<&str> = results.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(result_keys, vec!["date", "cherry", "apple"]);
+
+        assert!(cache.get_batch_ordered(&[], Order::Asc).is_empty());
+        assert!(cache.get_batch_ordered(&["missing"], Order::Asc).is_empty());
+    }
+
+    #[test]
+    fn test_key_normalization() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .normalize_keys(|key| key.to_lowercase())
+            .build();
+
+        cache.insert("ABC", 1);
+        cache.insert("Mixed:Key", 2);
+
+        assert_eq!(cache.get("abc"), Some(&1.to_value()));
+        assert_eq!(cache.get("ABC"), Some(&1.to_value()));
+        assert_eq!(cache.get_mut("aBc"), Some(&mut 1.to_value()));
+        assert!(cache.contains_key("mixed:key"));
+        assert!(cache.contains_key("MIXED:KEY"));
+
+        cache.insert("abc", 3);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("Abc"), Some(&3.to_value()));
+
+        let keys: Vec<String> = cache
+            .list(ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["abc", "mixed:key"]);
+
+        cache.remove("MIXED:key").unwrap();
+        assert!(!cache.contains_key("mixed:key"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_builder() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::builder(2)
+            .default_ttl(std::time::Duration::from_secs(60))
+            .eviction_policy(EvictionPolicy::Clock)
+            .sender(tx)
+            .build();
+
+        assert_eq!(cache.capacity(), 2);
+        assert_eq!(
+            cache.get_default_ttl(),
+            Some(std::time::Duration::from_secs(60))
+        );
+        assert_eq!(cache.get_eviction_policy(), EvictionPolicy::Clock);
+        assert!(cache.get_key_normalizer().is_none());
+
+        cache.insert("key", "value");
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            Event::insert("key".to_string(), "value".to_value())
+        );
+    }
+
+    #[test]
+    fn test_try_get_typed_values() {
+        let mut cache = Cache::new(10);
+        cache.insert("count", 42);
+        cache.insert("name", "Alice");
+        cache.insert("enabled", true);
+
+        assert_eq!(cache.try_get::<i64>("count"), Some(Ok(42)));
+        assert_eq!(
+            cache.try_get::<String>("name"),
+            Some(Ok("Alice".to_string()))
+        );
+        assert_eq!(cache.try_get::<bool>("enabled"), Some(Ok(true)));
+
+        cache.insert("wide", 300u64);
+        assert_eq!(cache.try_get::<u8>("count"), Some(Ok(42)));
+        assert_eq!(cache.try_get::<i16>("wide"), Some(Ok(300)));
+        assert_eq!(cache.try_get::<f64>("wide"), Some(Ok(300.0)));
+        assert_eq!(
+            cache.try_get::<u8>("wide"),
+            Some(Err(crate::Error::TypeMismatch))
+        );
+        cache.insert("ratio", 0.5);
+        assert_eq!(
+            cache.try_get::<i64>("ratio"),
+            Some(Err(crate::Error::TypeMismatch))
+        );
+
+        assert_eq!(
+            cache.try_get::<i64>("name"),
+            Some(Err(crate::Error::TypeMismatch))
+        );
+        assert_eq!(cache.try_get::<i64>("missing"), None);
+
+        assert_eq!(cache.get_as::<i64>("count"), Some(42));
+        assert_eq!(cache.get_as::<String>("count"), None);
+        assert_eq!(cache.get_as::<String>("missing"), None);
+    }
+
+    #[test]
+    fn test_hot_keys() {
+        let mut cache = Cache::builder(10).track_access_counts(true).build();
+        assert!(cache.get_track_access_counts());
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        cache.insert("d", 4);
+
+        for _ in 0..5 {
+            cache.get("b");
+        }
+        for _ in 0..3 {
+            cache.get("d");
+        }
+        cache.get_mut("a");
+        cache.get("c");
+        cache.get("missing");
+
+        assert_eq!(
+            cache.hot_keys(3),
+            vec![
+                ("b".to_string(), 5),
+                ("d".to_string(), 3),
+                ("a".to_string(), 1),
+            ]
+        );
+        assert_eq!(cache.hot_keys(10).len(), 4);
+        assert!(cache.hot_keys(0).is_empty());
+
+        cache.remove("b").unwrap();
+        assert_eq!(cache.hot_keys(1), vec![("d".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_hot_keys_disabled_by_default() {
+        let mut cache = Cache::new(10);
+        cache.insert("a", 1);
+        cache.get("a");
+
+        assert!(!cache.get_track_access_counts());
+        assert!(cache.hot_keys(10).is_empty());
+    }
+
+    #[test]
+    fn test_clone_is_deep_copy() {
+        let mut cache = Cache::new(10);
+        cache.insert("shared", 1);
+
+        let mut clone_cache = cache.clone();
+        clone_cache.insert("only_in_clone", 2);
+        clone_cache.insert("shared", 10);
+
+        assert!(!cache.contains_key("only_in_clone"));
+        assert_eq!(cache.get("shared"), Some(&1.to_value()));
+        assert_eq!(clone_cache.get("shared"), Some(&10.to_value()));
+    }
+
+    #[test]
+    fn test_shared_handle_preserves_setup_across_threads() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender_and_ttl(5, tx, std::time::Duration::from_secs(60));
+        for i in 0..5 {
+            cache.insert(format!("key{}", i), i);
+        }
+        assert_eq!(rx.try_iter().count(), 5);
+
+        let cache = cache.shared_handle();
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = cache.clone();
+                std::thread::spawn(move || {
+                    (0..5)
+                        .map(|i| handle.get(&format!("key{}", i)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for reader in readers {
+            let values = reader.join().unwrap();
+            let expected: Vec<_> = (0..5).map(|i| Some(i.to_value())).collect();
+            assert_eq!(values, expected);
+        }
+
+        {
+            let guard = cache.lock();
+            assert_eq!(guard.capacity(), 5);
+            assert_eq!(
+                guard.get_default_ttl(),
+                Some(std::time::Duration::from_secs(60))
+            );
+        }
+
+        cache.insert("key5", 5);
+        assert_eq!(cache.len(), 5);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Event::remove("key0".to_string(), 0.to_value()),
+                Event::insert("key5".to_string(), 5.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shared_handle_shares_entries() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let cache = Cache::with_sender(10, tx).shared_handle();
+
+        let handle = cache.clone();
+        std::thread::spawn(move || {
+            handle.insert("from_thread", 1);
+        })
+        .join()
+        .unwrap();
+
+        cache.insert("from_main", 2);
+
+        assert_eq!(cache.get("from_thread"), Some(1.to_value()));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.lock().get("from_main"), Some(&2.to_value()));
+
+        cache.remove("from_thread").unwrap();
+        assert!(!cache.contains_key("from_thread"));
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("from_thread".to_string(), 1.to_value()),
+                Event::insert("from_main".to_string(), 2.to_value()),
+                Event::remove("from_thread".to_string(), 1.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cloned_caches_share_sender_in_operation_order() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(2, tx);
+        cache.insert("a", 1);
+
+        let mut clone_cache = cache.clone();
+        clone_cache.insert("b", 2);
+        cache.insert("c", 3);
+        clone_cache.insert("d", 4);
+        cache.insert("e", 5);
+
+        // Each copy evicts from its own entries: the clone still holds "a" when
+        // "d" arrives, and the original still holds "a" when "e" arrives.
+        assert_eq!(clone_cache.get_list(), vec!["b", "d"]);
+        assert_eq!(cache.get_list(), vec!["c", "e"]);
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("a".to_string(), 1.to_value()),
+                Event::insert("b".to_string(), 2.to_value()),
+                Event::insert("c".to_string(), 3.to_value()),
+                Event::remove("a".to_string(), 1.to_value()),
+                Event::insert("d".to_string(), 4.to_value()),
+                Event::remove("a".to_string(), 1.to_value()),
+                Event::insert("e".to_string(), 5.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_or_try_insert_with() {
+        let mut cache = Cache::new(10);
+        cache.insert("cached", "hit");
+
+        let mut calls = 0;
+        let result = cache.get_or_try_insert_with("cached", || {
+            calls += 1;
+            Ok::<_, String>("loaded")
+        });
+        assert_eq!(result.map(Cow::into_owned), Ok("hit".to_value()));
+        assert_eq!(calls, 0);
+
+        let result = cache.get_or_try_insert_with("fresh", || Ok::<_, String>("loaded"));
+        assert_eq!(result.map(Cow::into_owned), Ok("loaded".to_value()));
+        assert_eq!(cache.get("fresh"), Some(&"loaded".to_value()));
+
+        let result =
+            cache.get_or_try_insert_with("broken", || Err::<&str, _>("db unavailable".to_string()));
+        assert_eq!(result, Err("db unavailable".to_string()));
+        assert!(!cache.contains_key("broken"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut cache = Cache::new(10);
+
+        assert_eq!(*cache.get_or_insert_with("key", || 1), 1.to_value());
+        assert_eq!(*cache.get_or_insert_with("key", || 2), 1.to_value());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_get_or_insert_with_rejected_value_is_not_cached() {
+        let mut cache = Cache::new_strings(10);
+
+        assert!(matches!(cache.get_or_insert_with("count", || 1), Cow::Owned(_)));
+        assert!(!cache.contains_key("count"));
+        assert_eq!(*cache.get_or_insert_with("count", || 2), 2.to_value());
+        assert_eq!(*cache.get_or_insert_with("name", || "alice"), "alice".to_value());
+        assert_eq!(cache.len(), 1);
+
+        // The Null placeholder skips the validator
+        let value = cache.get_or_insert_default("greeting").unwrap();
+        assert_eq!(value, &crate::Value::Null);
+        *value = "hello".to_value();
+        assert_eq!(cache.get("greeting"), Some(&"hello".to_value()));
+    }
+
+    #[test]
+    fn test_set_value_and_updates_respect_insert_validator() {
+        let mut cache = Cache::new(10);
+        cache.set_insert_validator(Some(|_: &str, value: &crate::Value| match value {
+            crate::Value::Array(array) if array.values.len() > 2 => Err("too long".to_string()),
+            _ => Ok(()),
+        }));
+        let rejected = || crate::Error::Rejected("too long".to_string());
+
+        assert_eq!(cache.list_push("log", "a"), Ok(1));
+        assert_eq!(cache.list_push("log", "b"), Ok(2));
+        assert_eq!(cache.list_push("log", "c"), Err(rejected()));
+        assert_eq!(cache.set_add("log", "d"), Err(rejected()));
+        assert_eq!(cache.get("log"), Some(&vec!["a", "b"].to_value()));
+
+        assert!(!cache.set_value("log", vec!["x", "y", "z"]));
+        assert_eq!(cache.try_set_value("log", vec!["x", "y", "z"]), Err(rejected()));
+        assert_eq!(cache.try_set_value("log", vec!["x"]), Ok(true));
+        assert_eq!(cache.get("log"), Some(&vec!["x"].to_value()));
+    }
+
+    #[test]
+    fn test_ordering_contract_matrix() {
+        let inserted = ["delta", "alpha", "echo", "charlie", "bravo"];
+
+        for policy in [EvictionPolicy::Lru, EvictionPolicy::Clock] {
+            let mut cache = Cache::new(10);
+            cache.set_eviction_policy(policy);
+            for (i, key) in inserted.iter().enumerate() {
+                cache.insert(*key, i);
+            }
+
+            // Updating a key keeps its position, removing keeps the others' order
+            cache.insert("alpha", 100);
+            cache.remove("echo").unwrap();
+            let insertion_order = vec!["delta", "alpha", "charlie", "bravo"];
+
+            assert_eq!(cache.get_list(), insertion_order);
+            assert_eq!(cache.get_map().keys().collect::<Vec<_>>(), insertion_order);
+
+            let asc: Vec<String> = cache
+                .list(ListProps::default().order(Order::Asc))
+                .unwrap()
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect();
+            assert_eq!(asc, vec!["alpha", "bravo", "charlie", "delta"]);
+
+            let desc: Vec<String> = cache
+                .list(ListProps::default().order(Order::Desc))
+                .unwrap()
+                .into_iter()
+                .map(|(k, _)| k)
+                .collect();
+            assert_eq!(desc, vec!["delta", "charlie", "bravo", "alpha"]);
+
+            assert_ne!(cache.get_list(), asc.iter().collect::<Vec<_>>());
+
+            let (early, late) = cache.partition(|key, _| key < "c");
+            assert_eq!(early.get_list(), vec!["alpha", "bravo"]);
+            assert_eq!(late.get_list(), vec!["delta", "charlie"]);
+        }
+    }
+
+    #[test]
+    fn test_lru_evicts_oldest_after_removals() {
+        let mut cache = Cache::new(3);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        cache.remove("a").unwrap();
+        cache.insert("d", 4);
+
+        // "b" is now the oldest entry and is evicted first
+        cache.insert("e", 5);
+        assert_eq!(cache.get_list(), vec!["c", "d", "e"]);
+    }
+
+    #[test]
+    fn test_insert_if_absent() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut cache = Cache::with_sender(2, tx);
+
+        assert!(cache.insert_if_absent("key", "original"));
+        assert!(!cache.insert_if_absent("key", "replacement"));
+        assert_eq!(cache.get("key"), Some(&"original".to_value()));
+
+        assert!(cache.insert_if_absent("other", 1));
+        assert!(cache.insert_if_absent("third", 2));
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains_key("key"));
+
+        let events: Vec<Event> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                Event::insert("key".to_string(), "original".to_value()),
+                Event::insert("other".to_string(), 1.to_value()),
+                Event::remove("key".to_string(), "original".to_value()),
+                Event::insert("third".to_string(), 2.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eviction_batch_size() {
+        for policy in [EvictionPolicy::Lru, EvictionPolicy::Clock] {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let mut cache = Cache::builder(10)
+                .eviction_policy(policy)
+                .eviction_batch_size(4)
+                .sender(tx)
+                .build();
+            assert_eq!(cache.get_eviction_batch_size(), 4);
+
+            let mut removals_per_insert = Vec::new();
+            for i in 0..30 {
+                cache.insert(format!("key{:02}", i), i);
+                assert!(cache.len() <= 10);
+
+                let removals = rx
+                    .try_iter()
+                    .filter(|event| matches!(event, Event::Remove(_)))
+                    .count();
+                removals_per_insert.push(removals);
+            }
+
+            // The first eviction happens on the 11th insert, then every 4th insert
+            let expected: Vec<usize> = (0..30)
+                .map(|i| if i >= 10 && (i - 10) % 4 == 0 { 4 } else { 0 })
+                .collect();
+            assert_eq!(removals_per_insert, expected);
+            assert_eq!(cache.len(), 10);
+            assert!(cache.contains_key("key29"));
+        }
+    }
+
+    #[test]
+    fn test_eviction_batch_size_defaults_to_one() {
+        let mut cache = Cache::new(2);
+        assert_eq!(cache.get_eviction_batch_size(), 1);
+
+        cache.set_eviction_batch_size(0);
+        assert_eq!(cache.get_eviction_batch_size(), 1);
+
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("c", 3);
+        assert_eq!(cache.get_list(), vec!["b", "c"]);
+    }
 }
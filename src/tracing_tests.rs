@@ -0,0 +1,117 @@
+//! Tests for tracing instrumentation
+
+#[cfg(test)]
+#[cfg(feature = "tracing")]
+mod tests {
+    use crate::cache::Cache;
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    #[derive(Clone, Default)]
+    struct CapturingSubscriber {
+        events: Arc<Mutex<Vec<(Level, String)>>>,
+    }
+
+    impl CapturingSubscriber {
+        fn messages(&self, level: Level) -> Vec<String> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(l, _)| *l == level)
+                .map(|(_, message)| message.clone())
+                .collect()
+        }
+    }
+
+    struct MessageVisitor(String);
+
+    impl Visit for MessageVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for CapturingSubscriber {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut visitor = MessageVisitor(String::new());
+            event.record(&mut visitor);
+            self.events
+                .lock()
+                .unwrap()
+                .push((*event.metadata().level(), visitor.0));
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_eviction_emits_debug_event() {
+        let subscriber = CapturingSubscriber::default();
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut cache = Cache::new(1);
+            cache.insert("first", 1);
+            cache.insert("second", 2);
+        });
+
+        let messages = subscriber.messages(Level::DEBUG);
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("Evicted key 'first'") && m.contains("'second'")));
+    }
+
+    #[test]
+    fn test_cleanup_emits_expired_count() {
+        let subscriber = CapturingSubscriber::default();
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut cache = Cache::new(10);
+            cache.insert_with_ttl("temp1", 1, Duration::from_millis(10));
+            cache.insert_with_ttl("temp2", 2, Duration::from_millis(10));
+            thread::sleep(Duration::from_millis(20));
+            assert_eq!(cache.cleanup_expired(), 2);
+        });
+
+        let messages = subscriber.messages(Level::DEBUG);
+        assert!(messages.iter().any(|m| m == "Cleaned up 2 expired items"));
+    }
+
+    #[test]
+    fn test_closed_channel_emits_debug_event() {
+        let subscriber = CapturingSubscriber::default();
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let (tx, rx) = channel();
+            let mut cache = Cache::with_sender(10, tx);
+            drop(rx);
+            cache.insert("key", "value");
+        });
+
+        let messages = subscriber.messages(Level::DEBUG);
+        assert!(messages
+            .iter()
+            .any(|m| m.starts_with("Failed to send insert event")));
+    }
+}
@@ -1,8 +1,10 @@
 #[cfg(test)]
 mod ttl_tests {
     use crate::{Cache, CacheItem};
+    use std::borrow::Cow;
+    use std::sync::mpsc::channel;
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
     use valu3::traits::ToValueBehavior;
 
     #[test]
@@ -85,6 +87,61 @@ mod ttl_tests {
         assert_eq!(cache.get("normal"), Some(&3.to_value()));
     }
 
+    #[test]
+    fn test_cleanup_expired_with_runs_callback_before_removal() {
+        let (tx, rx) = channel();
+        let mut cache = Cache::with_sender(10, tx);
+        let ttl = Duration::from_millis(50);
+
+        cache.insert_with_ttl("expired1", 1, ttl);
+        cache.insert_with_ttl("expired2", 2, ttl);
+        cache.insert("normal", 3);
+        while rx.try_recv().is_ok() {}
+
+        thread::sleep(Duration::from_millis(100));
+
+        let mut seen = Vec::new();
+        let removed_count = cache.cleanup_expired_with(|key, value| {
+            // No remove event has been sent for this entry yet
+            assert!(rx.try_recv().is_err());
+            seen.push((key.to_string(), value.clone()));
+        });
+
+        assert_eq!(removed_count, 2);
+        assert_eq!(
+            seen,
+            vec![
+                ("expired1".to_string(), 1.to_value()),
+                ("expired2".to_string(), 2.to_value()),
+            ]
+        );
+        assert_eq!(cache.len(), 1);
+        assert_eq!(rx.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_get_created_at_and_get_ttl() {
+        let mut cache = Cache::new(10);
+        let before = SystemTime::now() - Duration::from_secs(1);
+
+        cache.insert_with_ttl("ttl", 1, Duration::from_secs(60));
+        cache.insert("permanent", 2);
+
+        assert_eq!(cache.get_ttl("ttl"), Some(Duration::from_secs(60)));
+        assert!(cache.get_created_at("ttl").unwrap() >= before);
+
+        assert_eq!(cache.get_ttl("permanent"), None);
+        assert!(cache.get_created_at("permanent").unwrap() >= before);
+
+        assert_eq!(cache.get_ttl("missing"), None);
+        assert_eq!(cache.get_created_at("missing"), None);
+
+        cache.insert_with_ttl("expired", 3, Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get_ttl("expired"), None);
+        assert_eq!(cache.get_created_at("expired"), None);
+    }
+
     #[test]
     fn test_contains_key_with_expired() {
         let mut cache = Cache::new(10);
@@ -128,4 +185,893 @@ mod ttl_tests {
         cache.set_default_ttl(None);
         assert_eq!(cache.get_default_ttl(), None);
     }
+
+    #[test]
+    fn test_get_key_value_with_expired() {
+        let mut cache = Cache::new(10);
+
+        cache.insert_with_ttl("test", 42, Duration::from_millis(50));
+        assert!(cache.get_key_value("test").is_some());
+
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(cache.get_key_value("test"), None);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_contains_all_and_any_with_expired() {
+        let mut cache = Cache::new(10);
+        let ttl = Duration::from_millis(50);
+
+        cache.insert_with_ttl("expired1", 1, ttl);
+        cache.insert_with_ttl("expired2", 2, ttl);
+        cache.insert("live", 3);
+
+        thread::sleep(Duration::from_millis(100));
+
+        assert!(!cache.contains_all(&["live", "expired1"]));
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.contains_any(&["expired2", "live"]));
+        assert_eq!(cache.len(), 1);
+
+        assert!(cache.contains_all(&["live"]));
+        assert!(!cache.contains_any(&["expired1", "expired2"]));
+    }
+
+    #[test]
+    fn test_map_values_skips_expired() {
+        let mut cache = Cache::new(10);
+
+        cache.insert_with_ttl("expired", 1, Duration::from_millis(50));
+        cache.insert("live", 2);
+
+        thread::sleep(Duration::from_millis(100));
+
+        let mut seen = Vec::new();
+        let changed = cache.map_values(|key, value| {
+            seen.push(key.to_string());
+            *value = 10.to_value();
+        });
+
+        assert_eq!(changed, 1);
+        assert_eq!(seen, vec!["live".to_string()]);
+        assert_eq!(cache.get("live"), Some(&10.to_value()));
+    }
+
+    #[test]
+    fn test_partition_preserves_ttl() {
+        let mut cache = Cache::new(10);
+
+        cache.insert_with_ttl("user:1", "alice", Duration::from_millis(100));
+        cache.insert("user:2", "bob");
+        cache.insert_with_ttl("session:1", "active", Duration::from_millis(100));
+        cache.insert("session:2", "idle");
+        cache.insert_with_ttl("user:expired", "gone", Duration::from_millis(10));
+
+        thread::sleep(Duration::from_millis(20));
+
+        let (mut users, mut sessions) = cache.partition(|key, _| key.starts_with("user:"));
+
+        assert_eq!(users.capacity(), 10);
+        assert_eq!(sessions.capacity(), 10);
+        assert_eq!(users.get_list(), vec!["user:1", "user:2"]);
+        assert_eq!(sessions.get_list(), vec!["session:1", "session:2"]);
+
+        assert_eq!(users.get("user:1"), Some(&"alice".to_value()));
+        assert_eq!(sessions.get("session:1"), Some(&"active".to_value()));
+
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(users.get("user:1"), None);
+        assert_eq!(users.get("user:2"), Some(&"bob".to_value()));
+        assert_eq!(sessions.get("session:1"), None);
+        assert_eq!(sessions.get("session:2"), Some(&"idle".to_value()));
+    }
+
+    #[test]
+    fn test_list_after_expiration_between_calls() {
+        let mut cache = Cache::new(10);
+
+        cache.insert("a", 1);
+        cache.insert_with_ttl("b", 2, Duration::from_millis(10));
+        cache.insert("c", 3);
+
+        let keys: Vec<String> = cache
+            .list(crate::ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+
+        thread::sleep(Duration::from_millis(20));
+
+        let keys: Vec<String> = cache
+            .list(crate::ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["a", "c"]);
+
+        let props = crate::ListProps::default().start_after_key("b");
+        assert!(cache.list(props).is_err());
+    }
+
+    #[test]
+    fn test_prefix_histogram_skips_expired() {
+        let mut cache = Cache::new(10);
+        cache.insert("user:1", "alice");
+        cache.insert_with_ttl("user:2", "bob", Duration::from_millis(10));
+        cache.insert_with_ttl("session:x", "active", Duration::from_millis(10));
+
+        thread::sleep(Duration::from_millis(20));
+
+        let histogram = cache.prefix_histogram(':');
+        assert_eq!(histogram.len(), 1);
+        assert_eq!(histogram["user"], 1);
+    }
+
+    #[test]
+    fn test_get_batch_ordered_skips_expired() {
+        let mut cache = Cache::new(10);
+        cache.insert("b", 2);
+        cache.insert_with_ttl("a", 1, Duration::from_millis(10));
+        cache.insert("c", 3);
+
+        thread::sleep(Duration::from_millis(20));
+
+        let results = cache.get_batch_ordered(&["c", "a", "b"], crate::Order::Asc);
+        let keys: Vec<&str> = results.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, vec!["b", "c"]);
+        assert!(!cache.contains_key("a"));
+    }
+
+    thread_local! {
+        static MOCK_NOW: std::cell::Cell<u64> = const { std::cell::Cell::new(1_000) };
+    }
+
+    fn mock_clock() -> u64 {
+        MOCK_NOW.with(|now| now.get())
+    }
+
+    fn advance_mock_clock(millis: u64) {
+        MOCK_NOW.with(|now| now.set(now.get() + millis));
+    }
+
+    #[test]
+    fn test_mock_clock_drives_expiration() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_millis(500))
+            .build();
+
+        cache.insert("default_ttl", 1);
+        cache.insert_with_ttl("short", 2, Duration::from_millis(100));
+        cache.insert_with_ttl("long", 3, Duration::from_secs(10));
+
+        advance_mock_clock(100);
+        assert_eq!(cache.get("short"), Some(&2.to_value()));
+        assert_eq!(cache.len(), 3);
+
+        advance_mock_clock(1);
+        assert_eq!(cache.get("short"), None);
+        assert!(cache.contains_key("default_ttl"));
+
+        advance_mock_clock(400);
+        let keys: Vec<String> = cache
+            .list(crate::ListProps::default())
+            .unwrap()
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["long"]);
+        assert_eq!(cache.cleanup_expired(), 0);
+
+        advance_mock_clock(10_000);
+        assert_eq!(cache.cleanup_expired(), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_get_or_try_insert_with_reloads_expired() {
+        let mut cache = Cache::new(10);
+        cache.insert_with_ttl("key", "stale", Duration::from_millis(10));
+
+        thread::sleep(Duration::from_millis(20));
+
+        let result = cache.get_or_try_insert_with("key", || Ok::<_, ()>("fresh"));
+        assert_eq!(result.map(Cow::into_owned), Ok("fresh".to_value()));
+        assert_eq!(cache.get("key"), Some(&"fresh".to_value()));
+    }
+
+    #[test]
+    fn test_get_or_insert_with_dyn_ttl_uses_loader_ttl() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_secs(60))
+            .build();
+
+        let value = cache
+            .get_or_insert_with_dyn_ttl("short", || ("fresh", Some(Duration::from_millis(100))));
+        assert_eq!(*value, "fresh".to_value());
+        assert_eq!(cache.get_ttl("short"), Some(Duration::from_millis(100)));
+
+        cache.get_or_insert_with_dyn_ttl("default", || ("value", None));
+        assert_eq!(cache.get_ttl("default"), Some(Duration::from_secs(60)));
+
+        let mut calls = 0;
+        let value = cache.get_or_insert_with_dyn_ttl("short", || {
+            calls += 1;
+            ("other", Some(Duration::from_secs(1)))
+        });
+        assert_eq!(*value, "fresh".to_value());
+        assert_eq!(calls, 0);
+
+        advance_mock_clock(101);
+        let value = cache.get_or_insert_with_dyn_ttl("short", || {
+            calls += 1;
+            ("reloaded", Some(Duration::from_secs(1)))
+        });
+        assert_eq!(*value, "reloaded".to_value());
+        assert_eq!(calls, 1);
+        assert_eq!(cache.get_ttl("short"), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_insert_if_absent_replaces_expired() {
+        let mut cache = Cache::new(10);
+        cache.insert_with_ttl("key", "stale", Duration::from_millis(10));
+        assert!(!cache.insert_if_absent("key", "fresh"));
+
+        thread::sleep(Duration::from_millis(20));
+
+        assert!(cache.insert_if_absent("key", "fresh"));
+        assert_eq!(cache.get("key"), Some(&"fresh".to_value()));
+    }
+
+    #[test]
+    fn test_set_value_preserves_original_expiry() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+
+        cache.insert_with_ttl("page", "v1", Duration::from_millis(100));
+        advance_mock_clock(60);
+
+        assert!(cache.set_value("page", "v2"));
+        assert_eq!(cache.get("page"), Some(&"v2".to_value()));
+        assert_eq!(cache.get_ttl("page"), Some(Duration::from_millis(100)));
+
+        // `insert` would restart the TTL here; `set_value` keeps the deadline
+        advance_mock_clock(50);
+        assert_eq!(cache.get("page"), None);
+        assert!(!cache.set_value("page", "v3"));
+        assert!(!cache.contains_key("page"));
+    }
+
+    #[test]
+    fn test_increment_keeps_window_ttl() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_millis(100))
+            .build();
+
+        assert_eq!(cache.increment("requests", 1), Ok(1));
+        advance_mock_clock(60);
+        assert_eq!(cache.increment("requests", 1), Ok(2));
+
+        // The counter expires 100ms after it was created, then starts over
+        advance_mock_clock(50);
+        assert_eq!(cache.get("requests"), None);
+        assert_eq!(cache.increment("requests", 1), Ok(1));
+    }
+
+    #[test]
+    fn test_list_push_keeps_original_ttl() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_millis(100))
+            .build();
+
+        assert_eq!(cache.list_push("events", "a"), Ok(1));
+        advance_mock_clock(60);
+        assert_eq!(cache.set_add("events", "b"), Ok(true));
+        assert_eq!(cache.get_ttl("events"), Some(Duration::from_millis(100)));
+
+        advance_mock_clock(50);
+        assert_eq!(cache.get("events"), None);
+        assert_eq!(cache.list_push("events", "c"), Ok(1));
+    }
+
+    #[test]
+    fn test_ttl_distribution() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+
+        assert_eq!(cache.ttl_distribution(), crate::TtlStats::default());
+
+        cache.insert("permanent1", 1);
+        cache.insert("permanent2", 2);
+        cache.insert_with_ttl("short", 3, Duration::from_millis(100));
+        cache.insert_with_ttl("medium", 4, Duration::from_millis(400));
+        advance_mock_clock(50);
+        cache.insert_with_ttl("long", 5, Duration::from_millis(1000));
+        cache.insert_with_ttl("gone", 6, Duration::from_millis(10));
+        advance_mock_clock(20);
+
+        let stats = cache.ttl_distribution();
+        assert_eq!(stats.permanent, 2);
+        assert_eq!(stats.ttl_count, 3);
+        assert_eq!(stats.min_remaining, Some(Duration::from_millis(30)));
+        assert_eq!(stats.max_remaining, Some(Duration::from_millis(980)));
+        // (30 + 330 + 980) / 3
+        assert_eq!(stats.avg_remaining, Some(Duration::from_millis(446)));
+    }
+
+    #[test]
+    fn test_insert_opt_ttl() {
+        let mut cache = Cache::new(10);
+        cache.insert_opt_ttl("explicit", 1, Some(Duration::from_secs(5)));
+        cache.insert_opt_ttl("permanent", 2, None);
+
+        assert_eq!(cache.get_ttl("explicit"), Some(Duration::from_secs(5)));
+        assert_eq!(cache.get_ttl("permanent"), None);
+        assert_eq!(cache.get("permanent"), Some(&2.to_value()));
+
+        let mut cache = Cache::with_default_ttl(10, Duration::from_secs(60));
+        cache.insert_opt_ttl("explicit", 1, Some(Duration::from_secs(5)));
+        cache.insert_opt_ttl("default", 2, None);
+
+        assert_eq!(cache.get_ttl("explicit"), Some(Duration::from_secs(5)));
+        assert_eq!(cache.get_ttl("default"), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_keys_expiring_before() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        let start = mock_clock();
+        cache.insert_with_ttl("late", 1, Duration::from_millis(500));
+        cache.insert_with_ttl("early", 2, Duration::from_millis(100));
+        cache.insert_with_ttl("middle", 3, Duration::from_millis(200));
+        cache.insert("permanent", 4);
+        cache.insert_with_ttl("expired", 5, Duration::from_millis(10));
+        advance_mock_clock(50);
+
+        let deadline = SystemTime::UNIX_EPOCH + Duration::from_millis(start + 300);
+        assert_eq!(
+            cache.keys_expiring_before(deadline),
+            vec!["early", "middle"]
+        );
+
+        let deadline = SystemTime::UNIX_EPOCH + Duration::from_millis(start + 100);
+        assert!(cache.keys_expiring_before(deadline).is_empty());
+        assert_eq!(cache.len(), 5);
+    }
+
+    #[test]
+    fn test_pinned_entries_still_expire() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.insert_with_ttl("config", 1, Duration::from_millis(100));
+        assert!(cache.pin("config"));
+
+        advance_mock_clock(150);
+        assert!(!cache.is_pinned("config"));
+        assert!(!cache.contains_key("config"));
+        assert!(!cache.pin("config"));
+    }
+
+    #[test]
+    fn test_last_modified_tracks_mutations_not_reads() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        assert_eq!(cache.last_modified(), None);
+
+        cache.insert("key", "value");
+        let inserted = cache.last_modified().unwrap();
+        assert_eq!(
+            inserted,
+            SystemTime::UNIX_EPOCH + Duration::from_millis(mock_clock())
+        );
+
+        advance_mock_clock(100);
+        assert!(cache.get("key").is_some());
+        assert!(cache.contains_key("key"));
+        assert_eq!(cache.last_modified(), Some(inserted));
+
+        cache.remove("key").unwrap();
+        let removed = cache.last_modified().unwrap();
+        assert!(removed > inserted);
+
+        advance_mock_clock(100);
+        cache.clear();
+        assert!(cache.last_modified().unwrap() > removed);
+    }
+
+    #[test]
+    fn test_modified_at_advances_on_set_value() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.insert("page", "v1");
+        let created = cache.get_created_at("page").unwrap();
+        assert_eq!(cache.get_modified_at("page"), Some(created));
+
+        advance_mock_clock(100);
+        assert!(cache.set_value("page", "v2"));
+        assert_eq!(cache.get_created_at("page"), Some(created));
+        assert_eq!(
+            cache.get_modified_at("page"),
+            Some(created + Duration::from_millis(100))
+        );
+
+        advance_mock_clock(100);
+        cache.insert("page", "v3");
+        assert_eq!(cache.get_modified_at("page"), cache.get_created_at("page"));
+        assert!(cache.get_modified_at("missing").is_none());
+    }
+
+    #[test]
+    fn test_ttl_jitter_spreads_expiry_deadlines() {
+        let mut cache = crate::CacheBuilder::new(200)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_millis(1000))
+            .ttl_jitter(Duration::from_millis(200))
+            .build();
+
+        for i in 0..100 {
+            cache.insert(format!("default{}", i), i);
+            cache.insert_with_ttl(format!("explicit{}", i), i, Duration::from_millis(1000));
+        }
+
+        let deadline = SystemTime::UNIX_EPOCH + Duration::from_millis(mock_clock() + 1201);
+        let mut ttls: Vec<Duration> = cache
+            .keys_expiring_before(deadline)
+            .iter()
+            .map(|key| cache.get_ttl(key).unwrap())
+            .collect();
+        assert_eq!(ttls.len(), 200);
+        assert!(ttls
+            .iter()
+            .all(|ttl| *ttl >= Duration::from_millis(800) && *ttl <= Duration::from_millis(1200)));
+
+        ttls.sort();
+        ttls.dedup();
+        assert!(ttls.len() > 50, "expected spread out TTLs, got {:?}", ttls);
+    }
+
+    #[test]
+    fn test_ttl_jitter_never_goes_negative() {
+        let mut cache = crate::CacheBuilder::new(100).clock(mock_clock).build();
+        cache.set_ttl_jitter(Duration::from_millis(500));
+
+        for i in 0..50 {
+            cache.insert_with_ttl(format!("key{}", i), i, Duration::from_millis(100));
+        }
+
+        assert!((0..50).all(|i| {
+            cache.get_ttl(&format!("key{}", i)).unwrap() <= Duration::from_millis(600)
+        }));
+        assert_eq!(cache.get_ttl_jitter(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_get_stale_fresh_stale_and_miss() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .stale_grace(Duration::from_millis(500))
+            .build();
+        cache.insert_with_ttl("price", 100, Duration::from_millis(100));
+
+        assert_eq!(
+            cache.get_stale("price"),
+            crate::GetResult::Fresh(&100.to_value())
+        );
+
+        advance_mock_clock(300);
+        assert_eq!(cache.get("price"), None);
+        assert!(!cache.contains_key("price"));
+        assert_eq!(cache.cleanup_expired(), 0);
+        assert_eq!(
+            cache.get_stale("price"),
+            crate::GetResult::Stale(&100.to_value())
+        );
+
+        advance_mock_clock(400);
+        assert_eq!(cache.get_stale("price"), crate::GetResult::Miss);
+        assert_eq!(cache.len(), 0);
+        assert_eq!(cache.get_stale("missing"), crate::GetResult::Miss);
+    }
+
+    #[test]
+    fn test_stale_entry_is_refreshed_by_insert() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.set_stale_grace(Duration::from_millis(500));
+        cache.insert_with_ttl("price", 100, Duration::from_millis(100));
+
+        advance_mock_clock(200);
+        assert_eq!(cache.get_stale("price").value(), Some(&100.to_value()));
+
+        cache.insert_with_ttl("price", 100, Duration::from_millis(100));
+        assert_eq!(
+            cache.get_stale("price"),
+            crate::GetResult::Fresh(&100.to_value())
+        );
+        assert_eq!(cache.get("price"), Some(&100.to_value()));
+    }
+
+    #[test]
+    fn test_cleanup_removes_entries_past_stale_grace() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .stale_grace(Duration::from_millis(100))
+            .build();
+        cache.insert_with_ttl("old", 1, Duration::from_millis(50));
+        cache.insert_with_ttl("recent", 2, Duration::from_millis(200));
+
+        advance_mock_clock(250);
+        assert_eq!(cache.cleanup_expired(), 1);
+        assert_eq!(cache.get_stale("recent").value(), Some(&2.to_value()));
+        assert_eq!(cache.get_stale("old"), crate::GetResult::Miss);
+    }
+
+    #[test]
+    fn test_reset_ttl_all_sets_a_shared_deadline() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.insert("permanent", 1);
+        cache.insert_with_ttl("short", 2, Duration::from_millis(100));
+        advance_mock_clock(50);
+        cache.insert_with_ttl("long", 3, Duration::from_millis(10_000));
+        cache.insert_with_ttl("expired", 4, Duration::from_millis(10));
+        advance_mock_clock(20);
+
+        assert_eq!(cache.reset_ttl_all(Some(Duration::from_millis(500))), 3);
+
+        let reset_at = SystemTime::UNIX_EPOCH + Duration::from_millis(mock_clock());
+        for key in ["permanent", "short", "long"] {
+            assert_eq!(cache.get_created_at(key), Some(reset_at));
+            assert_eq!(cache.get_ttl(key), Some(Duration::from_millis(500)));
+        }
+        assert!(!cache.contains_key("expired"));
+
+        advance_mock_clock(501);
+        assert_eq!(cache.cleanup_expired(), 3);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_reset_ttl_all_none_makes_entries_permanent() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_millis(100))
+            .build();
+        cache.insert("a", 1);
+        cache.insert_with_ttl("b", 2, Duration::from_millis(200));
+
+        assert_eq!(cache.reset_ttl_all(None), 2);
+        advance_mock_clock(1_000);
+
+        assert_eq!(cache.get("a"), Some(&1.to_value()));
+        assert_eq!(cache.get_ttl("b"), None);
+        assert_eq!(cache.ttl_distribution().permanent, 2);
+    }
+
+    #[test]
+    fn test_touch_many_keeps_only_refreshed_keys_alive() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.insert_with_ttl("a", 1, Duration::from_millis(100));
+        cache.insert_with_ttl("b", 2, Duration::from_millis(100));
+        cache.insert_with_ttl("c", 3, Duration::from_millis(100));
+        cache.insert_with_ttl("expired", 4, Duration::from_millis(10));
+        advance_mock_clock(80);
+
+        assert_eq!(cache.touch_many(&["a", "c", "c", "expired", "missing"]), 2);
+        assert_eq!(cache.get_ttl("a"), Some(Duration::from_millis(100)));
+
+        advance_mock_clock(50);
+        assert_eq!(cache.get("a"), Some(&1.to_value()));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(&3.to_value()));
+        assert_eq!(cache.get("expired"), None);
+
+        advance_mock_clock(51);
+        assert_eq!(cache.cleanup_expired(), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_into_sorted_vec_returns_owned_live_entries() {
+        let mut cache = crate::CacheBuilder::new(10).clock(mock_clock).build();
+        cache.insert("delta", 4);
+        cache.insert_with_ttl("alpha", 1, Duration::from_millis(100));
+        cache.insert_with_ttl("expired", 0, Duration::from_millis(10));
+        cache.insert("charlie", 3);
+        cache.insert("bravo", 2);
+        advance_mock_clock(20);
+
+        let entries = cache.into_sorted_vec();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("alpha".to_string(), 1.to_value()),
+                ("bravo".to_string(), 2.to_value()),
+                ("charlie".to_string(), 3.to_value()),
+                ("delta".to_string(), 4.to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug_dump_reports_configuration_and_keys() {
+        let mut cache = crate::CacheBuilder::new(50)
+            .clock(mock_clock)
+            .default_ttl(Duration::from_secs(60))
+            .track_access_counts(true)
+            .build();
+        cache.insert("session:1", "alice");
+        cache.insert_with_ttl("expired", 0, Duration::from_millis(10));
+        cache.insert_opt_ttl("config", "on", None);
+        cache.set_default_ttl(None);
+        cache.insert("permanent", 1);
+        assert!(cache.pin("permanent"));
+        cache.get("permanent");
+        advance_mock_clock(1_000);
+
+        let report = cache.debug_dump();
+
+        assert!(report.contains("capacity: 50"));
+        assert!(report.contains("entries: 3 live / 4 total"));
+        assert!(report.contains("default TTL: none"));
+        assert!(report.contains("eviction policy: Lru"));
+        assert!(report.contains("hot keys: permanent (1)"));
+        assert!(report.contains("session:1: expires in 59s"));
+        assert!(report.contains("expired: expired"));
+        assert!(report.contains("permanent: permanent, pinned"));
+        assert!(report.contains("keys (first 4 of 4, insertion order):"));
+        assert!(!report.contains("IndexMap"));
+    }
+
+    #[test]
+    fn test_expire_now_routes_through_lazy_expiry() {
+        let (tx, rx) = channel();
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .sender(tx)
+            .stale_grace(Duration::from_millis(100))
+            .build();
+        cache.insert("session", "alice");
+        cache.insert_with_ttl("short", 1, Duration::from_millis(10));
+        rx.try_iter().count();
+
+        assert!(cache.expire_now("session"));
+        assert!(!cache.expire_now("missing"));
+        assert_eq!(cache.len(), 2);
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(cache.get("session"), None);
+        assert_eq!(
+            cache.get_stale("session"),
+            crate::GetResult::Stale(&"alice".to_value())
+        );
+
+        advance_mock_clock(200);
+        assert!(!cache.expire_now("short"));
+        assert_eq!(cache.get("session"), None);
+        assert_eq!(
+            rx.try_recv(),
+            Ok(crate::Event::remove(
+                "session".to_string(),
+                "alice".to_value()
+            ))
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_keys_sorted_skips_expired_keys() {
+        use crate::Order;
+
+        let mut cache = Cache::new(10);
+        cache.set_clock(mock_clock);
+        cache.insert("b", 2);
+        cache.insert_with_ttl("d", 4, Duration::from_millis(50));
+        cache.insert("a", 1);
+        cache.insert_with_ttl("c", 3, Duration::from_secs(60));
+
+        assert_eq!(cache.keys_sorted(Order::Asc), vec!["a", "b", "c", "d"]);
+
+        advance_mock_clock(100);
+
+        assert_eq!(cache.keys_sorted(Order::Asc), vec!["a", "b", "c"]);
+        assert_eq!(cache.keys_sorted(Order::Desc), vec!["c", "b", "a"]);
+        // Expired entries are skipped, not removed
+        assert_eq!(cache.len(), 4);
+    }
+
+    #[test]
+    fn test_get_many_mut_with_expired_key_requested_last() {
+        let mut cache = Cache::new(10);
+        cache.insert_with_ttl("x", 0, Duration::from_millis(20));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        thread::sleep(Duration::from_millis(40));
+
+        let values = cache.get_many_mut(&["a", "b", "x"]);
+        assert_eq!(values[0].as_deref(), Some(&1.to_value()));
+        assert_eq!(values[1].as_deref(), Some(&2.to_value()));
+        assert!(values[2].is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_get_item_returns_metadata_including_expired_items() {
+        let mut cache = Cache::new(10);
+        cache.insert_with_ttl("session", "alice", Duration::from_secs(60));
+        cache.insert_with_ttl("short", "bob", Duration::from_millis(10));
+
+        let item = cache.get_item("session").unwrap();
+        assert_eq!(item.value, "alice".to_value());
+        assert_eq!(item.ttl_millis, Some(60_000));
+        assert_eq!(
+            Some(item.created_at_time()),
+            cache.get_created_at("session")
+        );
+        assert!(!item.is_expired());
+
+        thread::sleep(Duration::from_millis(20));
+        let expired = cache.get_item("short").unwrap();
+        assert_eq!(expired.value, "bob".to_value());
+        assert!(expired.is_expired());
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.get("short").is_none());
+        assert!(cache.get_item("short").is_none());
+        assert!(cache.get_item("missing").is_none());
+    }
+
+    #[test]
+    fn test_clear_volatile_keeps_permanent_entries() {
+        let (tx, rx) = channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert("config", "dark-mode");
+        cache.insert_with_ttl("session", "abc", Duration::from_secs(60));
+        cache.insert("limit", 100);
+        cache.insert_with_ttl("token", "xyz", Duration::from_secs(60));
+        cache.pin("token");
+        rx.try_iter().count();
+
+        assert_eq!(cache.clear_volatile(), 2);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains_key("config"));
+        assert!(cache.contains_key("limit"));
+
+        let removed: Vec<String> = rx
+            .try_iter()
+            .map(|event| match event {
+                crate::Event::Remove(data) => data.key,
+                other => panic!("unexpected event {:?}", other),
+            })
+            .collect();
+        assert_eq!(removed, vec!["session".to_string(), "token".to_string()]);
+
+        assert_eq!(cache.clear_volatile(), 0);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_clear_unpinned_keeps_pinned_entries() {
+        let mut cache = Cache::new(10);
+        cache.insert("config", "dark-mode");
+        cache.insert_with_ttl("session", "abc", Duration::from_secs(60));
+        cache.insert("page", "<html>");
+        cache.pin("config");
+        cache.pin("session");
+
+        assert_eq!(cache.clear_unpinned(), 1);
+        assert!(cache.contains_key("config"));
+        assert!(cache.contains_key("session"));
+        assert!(!cache.contains_key("page"));
+    }
+
+    #[test]
+    fn test_insert_raw_keeps_item_metadata_without_events() {
+        let (tx, rx) = channel();
+        let mut cache = Cache::with_sender(2, tx);
+
+        let mut item = CacheItem::with_ttl("data".to_value(), Duration::from_secs(60));
+        item.created_at -= 10_000;
+        let created_at = item.created_at;
+
+        assert!(cache.insert_raw("session", item));
+        assert!(cache.insert_raw("config", CacheItem::new(1.to_value())));
+        assert!(!cache.insert_raw("extra", CacheItem::new(2.to_value())));
+        assert!(cache.insert_raw("config", CacheItem::new(3.to_value())));
+
+        assert!(rx.try_recv().is_err());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("config"), Some(&3.to_value()));
+
+        let stored = cache.get_item("session").unwrap();
+        assert_eq!(stored.created_at, created_at);
+        assert_eq!(stored.ttl(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_get_or_insert_default() {
+        let mut cache = Cache::with_default_ttl(10, Duration::from_secs(60));
+        cache.insert("existing", 5);
+
+        let value = cache.get_or_insert_default("counter").unwrap();
+        assert_eq!(value, &crate::Value::Null);
+        *value = 1.to_value();
+        assert_eq!(cache.get("counter"), Some(&1.to_value()));
+        assert_eq!(cache.get_ttl("counter"), Some(Duration::from_secs(60)));
+
+        assert_eq!(
+            cache.get_or_insert_default("existing"),
+            Some(&mut 5.to_value())
+        );
+        assert_eq!(cache.get("existing"), Some(&5.to_value()));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_get_or_insert_default_respects_capacity() {
+        let mut cache = Cache::new(1);
+        cache.insert("old", 1);
+
+        assert_eq!(
+            cache.get_or_insert_default("new"),
+            Some(&mut crate::Value::Null)
+        );
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains_key("old"));
+    }
+
+    #[test]
+    fn test_watch_receives_expiration() {
+        let mut cache = Cache::new(10);
+        let events = cache.watch("session");
+
+        cache.insert_with_ttl("session", "abc", Duration::from_millis(20));
+        cache.insert_with_ttl("other", "xyz", Duration::from_millis(20));
+        thread::sleep(Duration::from_millis(40));
+        cache.cleanup_expired();
+
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                crate::Event::insert("session".to_string(), "abc".to_value()),
+                crate::Event::remove("session".to_string(), "abc".to_value()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_many_mut_skips_expired_entries() {
+        let (tx, rx) = channel();
+        let mut cache = Cache::with_sender(10, tx);
+        cache.insert_with_ttl("short", 1, Duration::from_millis(20));
+        cache.insert("long", 2);
+        thread::sleep(Duration::from_millis(40));
+        rx.try_iter().count();
+
+        let values = cache.get_many_mut(&["short", "long"]);
+        assert!(values[0].is_none());
+        assert_eq!(values[1].as_deref(), Some(&2.to_value()));
+        assert!(cache.get_item("short").is_none());
+        assert!(matches!(rx.try_recv(), Ok(crate::Event::Remove(_))));
+    }
+
+    #[test]
+    fn test_get_many_mut_skips_expired_entries_within_stale_grace() {
+        let mut cache = crate::CacheBuilder::new(10)
+            .clock(mock_clock)
+            .stale_grace(Duration::from_millis(100))
+            .build();
+        cache.insert_with_ttl("short", 1, Duration::from_millis(50));
+        cache.insert("long", 2);
+
+        advance_mock_clock(60);
+        let values = cache.get_many_mut(&["short", "long"]);
+        assert!(values[0].is_none());
+        assert_eq!(values[1].as_deref(), Some(&2.to_value()));
+        assert_eq!(cache.get_stale("short").value(), Some(&1.to_value()));
+    }
 }
@@ -0,0 +1,200 @@
+//! Lossless JSON encoding of values for the SQLite store.
+//!
+//! valu3's parser keeps escape sequences such as `\"` and `\n` verbatim, so a
+//! string nested in an array or object didn't survive a `to_json` /
+//! `json_to_value` round trip. Strings, arrays and objects are written and
+//! read here; scalars still go through valu3.
+
+#![cfg(feature = "persist")]
+
+use crate::valu3::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Serialize `value` as JSON with every nested string escaped.
+pub(crate) fn to_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(value, &mut out);
+    out
+}
+
+/// Parse JSON written by [`to_json`], or `None` if `text` isn't valid JSON.
+pub(crate) fn from_json(text: &str) -> Option<Value> {
+    let mut reader = Reader { text, pos: 0 };
+    let value = reader.value()?;
+    reader.skip_whitespace();
+    (reader.pos == text.len()).then_some(value)
+}
+
+fn write_value(value: &Value, out: &mut String) {
+    match value {
+        Value::String(_) => write_string(&value.as_string(), out),
+        Value::Array(array) => {
+            out.push('[');
+            for (i, item) in array.values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(object) => {
+            out.push('{');
+            for (i, (key, item)) in object.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(&key.to_string(), out);
+                out.push(':');
+                write_value(item, out);
+            }
+            out.push('}');
+        }
+        other => out.push_str(&other.to_json(JsonMode::Inline)),
+    }
+}
+
+fn write_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `byte` after any whitespace, returning whether it was there
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'"' => self.string().map(|text| text.to_value()),
+            b'[' => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        values.push(self.value()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Some(values.to_value())
+            }
+            b'{' => {
+                self.pos += 1;
+                let mut entries = HashMap::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        if !self.eat(b':') {
+                            return None;
+                        }
+                        entries.insert(key, self.value()?);
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        if !self.eat(b',') {
+                            return None;
+                        }
+                    }
+                }
+                Some(entries.to_value())
+            }
+            _ => {
+                let rest = &self.text[self.pos..];
+                let len = rest
+                    .find(|c: char| matches!(c, ',' | ']' | '}') || c.is_whitespace())
+                    .unwrap_or(rest.len());
+                self.pos += len;
+                Value::json_to_value(&rest[..len]).ok()
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.peek() != Some(b'"') {
+            return None;
+        }
+        self.pos += 1;
+
+        let mut out = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Some(out);
+                }
+                '\\' => match chars.next()?.1 {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let mut code = hex4(&mut chars)?;
+                        if (0xD800..0xDC00).contains(&code) {
+                            if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+                                return None;
+                            }
+                            let low = hex4(&mut chars)?;
+                            code = 0x10000 + ((code - 0xD800) << 10) + low.checked_sub(0xDC00)?;
+                        }
+                        out.push(char::from_u32(code)?);
+                    }
+                    escaped => out.push(escaped),
+                },
+                c => out.push(c),
+            }
+        }
+        None
+    }
+}
+
+fn hex4(chars: &mut std::str::CharIndices) -> Option<u32> {
+    let mut code = 0;
+    for _ in 0..4 {
+        code = code * 16 + chars.next()?.1.to_digit(16)?;
+    }
+    Some(code)
+}